@@ -86,15 +86,22 @@ lazy_static! {
         settings.set_default("assert_timeout", 10_000).unwrap();
         settings.set_default("use_more_complete_exhale", true).unwrap();
         settings.set_default("skip_unsupported_features", false).unwrap();
+        settings.set_default("report_support_status", false).unwrap();
         settings.set_default("allow_unreachable_unsupported_code", false).unwrap();
         settings.set_default("no_verify", false).unwrap();
         settings.set_default("full_compilation", false).unwrap();
+        settings.set_default("std_specs", false).unwrap();
         settings.set_default("json_communication", false).unwrap();
         settings.set_default("json_communication", false).unwrap();
         settings.set_default("optimizations","all").unwrap();
         settings.set_default("intern_names", true).unwrap();
         settings.set_default("enable_purification_optimization", false).unwrap();
         settings.set_default("enable_manual_axiomatization", false).unwrap();
+        settings.set_default("counterexample", false).unwrap();
+        settings.set_default("cache_path", "").unwrap();
+        settings.set_default("verify_only", "").unwrap();
+        settings.set_default("diagnostics_format", "").unwrap();
+        settings.set_default("diagnostics_dir", "./prusti-diagnostics/").unwrap();
 
         settings.set_default("print_desugared_specs", false).unwrap();
         settings.set_default("print_typeckd_specs", false).unwrap();
@@ -112,6 +119,7 @@ lazy_static! {
         let mut allowed_keys = get_keys(&settings);
         allowed_keys.insert("server_max_stored_verifiers".to_string());
         allowed_keys.insert("server_max_concurrency".to_string());
+        allowed_keys.insert("verification_concurrency".to_string());
         allowed_keys.insert("server_address".to_string());
         allowed_keys.insert("config".to_string());
         allowed_keys.insert("log".to_string());
@@ -340,11 +348,67 @@ pub fn server_max_stored_verifiers() -> Option<usize> {
 
 /// The maximum amount of verification requests the server will work on concurrently.
 ///
-/// If not set, this defaults to the number of (logical) cores on the system
+/// If not set, this defaults to 1. Higher values are rejected by `check_concurrency`.
 pub fn server_max_concurrency() -> Option<usize> {
     read_optional_setting("server_max_concurrency")
 }
 
+/// The maximum amount of verification requests, one for each method, that Prusti dispatches
+/// concurrently, either to the server or to verifiers running in the same process.
+///
+/// If not set, this defaults to 1. Higher values are rejected by `check_concurrency`.
+pub fn verification_concurrency() -> Option<usize> {
+    read_optional_setting("verification_concurrency")
+}
+
+/// Check that at most one verification runs at a time. All the verifiers of a process share
+/// the same JVM, in which Viper does not support running several verifiers concurrently.
+pub fn check_concurrency() -> Result<(), String> {
+    let settings = [
+        ("PRUSTI_VERIFICATION_CONCURRENCY", verification_concurrency()),
+        ("PRUSTI_SERVER_MAX_CONCURRENCY", server_max_concurrency()),
+    ];
+    for (name, value) in settings.iter() {
+        if let Some(value) = value {
+            if *value > 1 {
+                return Err(format!(
+                    "{} is {}, but the verifiers share a single JVM, in which Viper cannot run \
+                    several verifications concurrently; it must be at most 1",
+                    name, value,
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The file in which the successful verifications of methods are cached across runs, so that
+/// unchanged methods are not verified again. If empty, no results are cached.
+pub fn cache_path() -> String {
+    read_setting("cache_path")
+}
+
+/// Comma-separated glob patterns (e.g. `crate::module::*`) of the paths of the procedures to
+/// verify. If empty, all procedures are verified.
+pub fn verify_only() -> Vec<String> {
+    read_setting::<String>("verify_only")
+        .split(',')
+        .map(|pattern| pattern.trim().to_string())
+        .filter(|pattern| !pattern.is_empty())
+        .collect()
+}
+
+/// Also write the reported errors in a machine-readable format to `DIAGNOSTICS_DIR`: either
+/// "json" or "sarif". If empty, errors are only reported by the compiler.
+pub fn diagnostics_format() -> String {
+    read_setting("diagnostics_format")
+}
+
+/// The directory in which the machine-readable errors of each crate are written.
+pub fn diagnostics_dir() -> String {
+    read_setting("diagnostics_dir")
+}
+
 /// When set, Prusti will connect to this server and use it for its verification backend (i.e. the things using the JVM/Viper).
 /// Set to "MOCK" to run the server off-thread, effectively mocking connecting to a server without having to start it up separately.
 /// e.g. "127.0.0.1:2468"
@@ -427,6 +491,15 @@ pub fn enable_manual_axiomatization() -> bool {
     read_setting("enable_manual_axiomatization")
 }
 
+/// Ask the backend for a counterexample of each failing verification and
+/// report the values of the Rust arguments and locals.
+///
+/// **Note:** this is currently only supported by Silicon, and only the values
+/// of variables of primitive types (booleans, integers and chars) are reported.
+pub fn counterexample() -> bool {
+    read_setting("counterexample")
+}
+
 /// Replace the given basic blocks with ``assume false``.
 pub fn delete_basic_blocks() -> Vec<String> {
     read_setting("delete_basic_blocks")
@@ -437,6 +510,11 @@ pub fn skip_unsupported_features() -> bool {
     read_setting("skip_unsupported_features")
 }
 
+/// Report, before the encoding, which functions use unsupported constructs
+pub fn report_support_status() -> bool {
+    read_setting("report_support_status")
+}
+
 /// Encode unsupported code as `assert false`, so that we report error messages
 /// only for unsupported code that is actually reachable.
 pub fn allow_unreachable_unsupported_code() -> bool {
@@ -453,6 +531,12 @@ pub fn full_compilation() -> bool {
     read_setting("full_compilation")
 }
 
+/// Add the bundled specifications of the standard library (from `prusti-std`) to the
+/// verified crate. Disabled by default.
+pub fn std_specs() -> bool {
+    read_setting("std_specs")
+}
+
 /// Intern Viper identifiers to shorten them when possible.
 pub fn intern_names() -> bool {
     read_setting("intern_names")
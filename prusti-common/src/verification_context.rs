@@ -5,7 +5,11 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::config;
-use std::{fs::create_dir_all, path::PathBuf};
+use std::{
+    fs::create_dir_all,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 use crate::verification_service::ViperBackendConfig;
 use viper::{self, AstFactory, VerificationBackend, Viper};
 
@@ -38,6 +42,10 @@ impl Default for VerifierBuilder {
     }
 }
 
+/// Used to give each verifier its own temporary directory, so that verifiers running in
+/// parallel in the same JVM do not overwrite each other's files.
+static NEXT_VERIFIER_ID: AtomicUsize = AtomicUsize::new(0);
+
 /// A verification context is an object that lives entire verification's lifetime.
 /// Its main purpose is to build verifiers.
 /// The main motivation for having a verification context is to be able to detach the current
@@ -56,7 +64,10 @@ impl<'v> VerificationContext<'v> {
         backend_config: &ViperBackendConfig,
     ) -> viper::Verifier<viper::state::Started> {
         let mut verifier_args: Vec<String> = backend_config.verifier_args.clone();
-        let log_path: PathBuf = PathBuf::from(config::log_dir()).join("viper_tmp");
+        let verifier_id = NEXT_VERIFIER_ID.fetch_add(1, Ordering::SeqCst);
+        let log_path: PathBuf = PathBuf::from(config::log_dir())
+            .join("viper_tmp")
+            .join(format!("verifier_{}", verifier_id));
         create_dir_all(&log_path).unwrap();
         let report_path: PathBuf = log_path.join("report.csv");
         let log_dir_str = log_path.to_str().unwrap();
@@ -13,6 +13,17 @@ pub struct VerificationRequest {
     pub backend_config: ViperBackendConfig,
 }
 
+/// A request to verify a program given as Viper source code, e.g. one dumped with
+/// `DUMP_VIPER_PROGRAM` and edited by hand.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViperSourceRequest {
+    pub source: String,
+    pub program_name: String,
+    /// If missing, the server uses the backend configured in its own settings.
+    #[serde(default)]
+    pub backend_config: ViperBackendConfig,
+}
+
 /**
 The configuration for the viper backend, (i.e. verifier).
 Expresses which backend (silicon or carbon) should be used, and provides command-line arguments to the viper verifier.
@@ -38,6 +49,9 @@ impl Default for ViperBackendConfig {
                     "--logLevel".to_string(),
                     "ERROR".to_string(),
                 ]);
+                if config::counterexample() {
+                    verifier_args.push("--counterexample=variables".to_string());
+                }
             }
             VerificationBackend::Carbon => {
                 verifier_args.extend(vec![
@@ -51,3 +65,12 @@ impl Default for ViperBackendConfig {
         }
     }
 }
+
+impl ViperBackendConfig {
+    /// Ask the verifier to report all the errors of each method instead of only the first one.
+    pub fn report_all_errors(&mut self) {
+        if self.backend == VerificationBackend::Silicon {
+            self.verifier_args.push("--numberOfErrorsToReport=0".to_string());
+        }
+    }
+}
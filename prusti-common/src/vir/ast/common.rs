@@ -141,6 +141,9 @@ pub enum Type {
     Int,
     Bool,
     Seq(Box<Type>),
+    Set(Box<Type>),
+    /// Map: key type, value type
+    Map(Box<Type>, Box<Type>),
     //Ref, // At the moment we don't need this
     /// TypedRef: the first parameter is the name of the predicate that encodes the type
     TypedRef(String),
@@ -154,6 +157,8 @@ pub enum TypeId {
     Bool,
     Ref,
     Seq,
+    Set,
+    Map,
     Domain,
     Snapshot,
 }
@@ -168,6 +173,8 @@ impl fmt::Display for Type {
             Type::Domain(ref name) => write!(f, "Domain({})", name),
             Type::Snapshot(ref name) => write!(f, "Snapshot({})", name),
             Type::Seq(ref elem_ty) => write!(f, "Seq[{}]", elem_ty),
+            Type::Set(ref elem_ty) => write!(f, "Set[{}]", elem_ty),
+            Type::Map(ref key_ty, ref val_ty) => write!(f, "Map[{}, {}]", key_ty, val_ty),
         }
     }
 }
@@ -193,6 +200,8 @@ impl Type {
             Type::Domain(ref pred_name) => pred_name.to_string(),
             Type::Snapshot(ref pred_name) => pred_name.to_string(),
             Type::Seq(_) => "Seq".to_string(),
+            Type::Set(_) => "Set".to_string(),
+            Type::Map(..) => "Map".to_string(),
         }
     }
 
@@ -229,6 +238,8 @@ impl Type {
             Type::Domain(_) => TypeId::Domain,
             Type::Snapshot(_) => TypeId::Snapshot,
             Type::Seq(_) => TypeId::Seq,
+            Type::Set(_) => TypeId::Set,
+            Type::Map(..) => TypeId::Map,
         }
     }
 }
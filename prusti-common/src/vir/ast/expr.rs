@@ -33,7 +33,8 @@ pub enum Expr {
     BinOp(BinOpKind, Box<Expr>, Box<Expr>, Position),
     /// Container Operation on a Viper container (e.g. Seq index)
     ContainerOp(ContainerOpKind, Box<Expr>, Box<Expr>, Position),
-    /// Viper Seq
+    /// Viper Seq, Set or Map literal, depending on the type. The elements of
+    /// a Map literal are `Maplet` container operations.
     Seq(Type, Vec<Expr>, Position),
     /// Unfolding: predicate name, predicate_args, in_expr, permission amount, enum variant
     Unfolding(String, Vec<Expr>, Box<Expr>, PermAmount, MaybeEnumVariantIndex, Position),
@@ -100,7 +101,24 @@ pub enum ContainerOpKind {
     SeqIndex,
     SeqConcat,
     SeqLen,
-    // more to follow if required
+    /// container, element
+    SeqContains,
+    SetUnion,
+    SetIntersection,
+    SetMinus,
+    SetSubset,
+    /// container, element
+    SetContains,
+    SetLen,
+    /// key, value
+    Maplet,
+    /// map, key
+    MapLookup,
+    /// map, key
+    MapContains,
+    /// map, maplet
+    MapUpdate,
+    MapLen,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -111,6 +129,8 @@ pub enum Const {
     /// All function pointers share the same constant, because their function
     /// is determined by the type system.
     FnPtr,
+    /// The address of a null raw pointer.
+    Null,
 }
 
 impl fmt::Display for Expr {
@@ -130,13 +150,33 @@ impl fmt::Display for Expr {
                 match op {
                     ContainerOpKind::SeqIndex => write!(f, "{}[{}]", left, right),
                     ContainerOpKind::SeqConcat => write!(f, "{} ++ {}", left, right),
-                    ContainerOpKind::SeqLen => write!(f, "|{}|", left),
+                    ContainerOpKind::SeqLen
+                    | ContainerOpKind::SetLen
+                    | ContainerOpKind::MapLen => write!(f, "|{}|", left),
+                    ContainerOpKind::SeqContains
+                    | ContainerOpKind::SetContains
+                    | ContainerOpKind::MapContains => write!(f, "({}) in ({})", right, left),
+                    ContainerOpKind::SetUnion => write!(f, "({}) union ({})", left, right),
+                    ContainerOpKind::SetIntersection => {
+                        write!(f, "({}) intersection ({})", left, right)
+                    }
+                    ContainerOpKind::SetMinus => write!(f, "({}) setminus ({})", left, right),
+                    ContainerOpKind::SetSubset => write!(f, "({}) subset ({})", left, right),
+                    ContainerOpKind::Maplet => write!(f, "{} := {}", left, right),
+                    ContainerOpKind::MapLookup
+                    | ContainerOpKind::MapUpdate => write!(f, "{}[{}]", left, right),
                 }
             }
             Expr::Seq(ty, elems, _) => {
                 let elems_printed = elems.iter().map(|e| format!("{}", e)).collect::<Vec<_>>().join(", ");
-                let elem_ty = if let Type::Seq(box elem_ty) = ty { elem_ty } else { unreachable!() };
-                write!(f, "Seq[{}]({})", elem_ty, elems_printed)
+                match ty {
+                    Type::Seq(box elem_ty) => write!(f, "Seq[{}]({})", elem_ty, elems_printed),
+                    Type::Set(box elem_ty) => write!(f, "Set[{}]({})", elem_ty, elems_printed),
+                    Type::Map(box key_ty, box val_ty) => {
+                        write!(f, "Map[{}, {}]({})", key_ty, val_ty, elems_printed)
+                    }
+                    _ => unreachable!(),
+                }
             }
             Expr::UnaryOp(op, ref expr, ref _pos) => write!(f, "{}({})", op, expr),
             Expr::PredicateAccessPredicate(ref pred_name, ref arg, perm, ref _pos) => {
@@ -301,6 +341,7 @@ impl fmt::Display for Const {
             Const::Int(val) => write!(f, "{}", val),
             Const::BigInt(ref val) => write!(f, "{}", val),
             Const::FnPtr => write!(f, "FnPtr"),
+            Const::Null => write!(f, "null"),
         }
     }
 }
@@ -1034,6 +1075,7 @@ impl Expr {
     pub fn get_type(&self) -> &Type {
         lazy_static! {
             static ref FN_PTR_TYPE: Type = Type::TypedRef("FnPtr".to_string());
+            static ref NULL_TYPE: Type = Type::TypedRef("".to_string());
         }
         match self {
             Expr::Local(LocalVar { ref typ, .. }, _)
@@ -1059,6 +1101,7 @@ impl Expr {
                     Const::Bool(..) => &Type::Bool,
                     Const::Int(..) | Const::BigInt(..) => &Type::Int,
                     Const::FnPtr => &FN_PTR_TYPE,
+                    Const::Null => &NULL_TYPE,
                 }
             }
             Expr::BinOp(ref kind, box ref base1, box ref base2, _pos) => {
@@ -1108,7 +1151,31 @@ impl Expr {
                 expr.get_type()
             }
             Expr::ContainerOp(op_kind, box ref left, box ref right, _) => {
-                todo!("get_type container_op({:?}, {}, {})", op_kind, left, right)
+                match op_kind {
+                    ContainerOpKind::SeqIndex => match left.get_type() {
+                        Type::Seq(box ref elem_ty) => elem_ty,
+                        _ => unreachable!("expr: {:?}", self),
+                    },
+                    ContainerOpKind::MapLookup => match left.get_type() {
+                        Type::Map(_, box ref val_ty) => val_ty,
+                        _ => unreachable!("expr: {:?}", self),
+                    },
+                    ContainerOpKind::SeqConcat
+                    | ContainerOpKind::SetUnion
+                    | ContainerOpKind::SetIntersection
+                    | ContainerOpKind::SetMinus
+                    | ContainerOpKind::MapUpdate => left.get_type(),
+                    ContainerOpKind::SeqLen
+                    | ContainerOpKind::SetLen
+                    | ContainerOpKind::MapLen => &Type::Int,
+                    ContainerOpKind::SeqContains
+                    | ContainerOpKind::SetContains
+                    | ContainerOpKind::SetSubset
+                    | ContainerOpKind::MapContains => &Type::Bool,
+                    ContainerOpKind::Maplet => {
+                        unreachable!("maplets have no type: {} := {}", left, right)
+                    }
+                }
             }
             Expr::Seq(ref ty, ..) => ty,
         }
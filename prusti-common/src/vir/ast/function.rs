@@ -86,6 +86,10 @@ pub fn compute_identifier(name: &str, formal_args: &[LocalVar], return_type: &Ty
             Type::Domain(ref name) => name.to_string(),
             Type::Snapshot(ref name) => format!("Snap${}", name),
             Type::Seq(ref elem_ty) => format!("Seq${}", type_name(elem_ty)),
+            Type::Set(ref elem_ty) => format!("Set${}", type_name(elem_ty)),
+            Type::Map(ref key_ty, ref val_ty) => {
+                format!("Map${}${}", type_name(key_ty), type_name(val_ty))
+            }
         }
     }
     for arg in formal_args {
@@ -4,7 +4,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use std::fmt;
 use std::iter::FromIterator;
 use uuid::Uuid;
@@ -26,7 +26,8 @@ pub struct CfgMethod {
     // FIXME: This should be pub(in super::super). However, the optimization
     // that depends on snapshots needs to modify this field.
     pub local_vars: Vec<LocalVar>,
-    pub(super) labels: HashSet<String>,
+    /// Ordered, so that the serialization of equal methods is equal.
+    pub(super) labels: BTreeSet<String>,
     #[serde(skip)]
     pub(super) reserved_labels: HashSet<String>,
     pub basic_blocks: Vec<CfgBlock>, // FIXME: Hack, should be pub(super).
@@ -147,7 +148,7 @@ impl CfgMethod {
             formal_arg_count,
             formal_returns,
             local_vars,
-            labels: HashSet::new(),
+            labels: BTreeSet::new(),
             reserved_labels: HashSet::from_iter(reserved_labels),
             basic_blocks: vec![],
             basic_blocks_labels: vec![],
@@ -422,7 +422,9 @@ impl ast::StmtFolder for VarPurifier {
                 ast::Type::TypedRef(_) => "builtin$havoc_ref",
                 ast::Type::Domain(_)
                 | ast::Type::Snapshot(_)
-                | ast::Type::Seq(_) => unreachable!(),
+                | ast::Type::Seq(_)
+                | ast::Type::Set(_)
+                | ast::Type::Map(..) => unreachable!(),
             }.to_string();
             targets = vec![replacement];
         }
@@ -1,4 +1,5 @@
 use crate::vir::{ast::*, cfg::CfgMethod};
+use std::collections::HashSet;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Program {
@@ -9,3 +10,191 @@ pub struct Program {
     pub functions: Vec<Function>,
     pub viper_predicates: Vec<Predicate>,
 }
+
+impl Program {
+    /// Split the program into programs that can be verified independently of each other: one
+    /// that contains all the functions and predicates, but no method, and one per method.
+    ///
+    /// The program of a method only contains the functions and predicates that the method
+    /// (transitively) depends on. The functions are verified only once, in the first program,
+    /// so in the programs of the methods their bodies are turned into postconditions, which the
+    /// verifier assumes without checking.
+    /// A program with at most one method is returned unchanged.
+    pub fn split_by_method(self) -> Vec<Program> {
+        if self.methods.len() <= 1 {
+            return vec![self];
+        }
+        let Program {
+            domains,
+            fields,
+            builtin_methods,
+            methods,
+            functions,
+            viper_predicates,
+        } = self;
+        let abstract_functions: Vec<_> = functions.iter().map(abstract_function).collect();
+        let mut programs = vec![Program {
+            domains: domains.clone(),
+            fields: fields.clone(),
+            builtin_methods: builtin_methods.clone(),
+            methods: vec![],
+            functions: functions.clone(),
+            viper_predicates: viper_predicates.clone(),
+        }];
+        for method in methods {
+            let dependencies = Dependencies::of_method(&method, &functions, &viper_predicates);
+            programs.push(Program {
+                domains: domains.clone(),
+                fields: fields.clone(),
+                builtin_methods: builtin_methods.clone(),
+                methods: vec![method],
+                functions: abstract_functions
+                    .iter()
+                    .filter(|function| dependencies.functions.contains(&function.get_identifier()))
+                    .cloned()
+                    .collect(),
+                viper_predicates: viper_predicates
+                    .iter()
+                    .filter(|predicate| dependencies.predicates.contains(predicate.name()))
+                    .cloned()
+                    .collect(),
+            });
+        }
+        programs
+    }
+}
+
+/// Turn the body of a function into a postcondition, so that the function is not verified.
+fn abstract_function(function: &Function) -> Function {
+    let mut function = function.clone();
+    if let Some(body) = function.body.take() {
+        let result: Expr = LocalVar::new("__result", function.return_type.clone()).into();
+        function.posts.push(Expr::eq_cmp(result, body));
+    }
+    function
+}
+
+/// The identifiers of the functions and the names of the predicates used by a method.
+#[derive(Default)]
+struct Dependencies {
+    functions: HashSet<String>,
+    predicates: HashSet<String>,
+}
+
+impl Dependencies {
+    /// Collect the functions and predicates used by the method, and those used by them in turn.
+    fn of_method(method: &CfgMethod, functions: &[Function], predicates: &[Predicate]) -> Self {
+        let mut dependencies = Dependencies::default();
+        method.walk_statements(|stmt| StmtWalker::walk(&mut dependencies, stmt));
+        loop {
+            let function_count = dependencies.functions.len();
+            let predicate_count = dependencies.predicates.len();
+            for function in functions {
+                if dependencies.functions.contains(&function.get_identifier()) {
+                    for expr in function.pres.iter()
+                        .chain(function.posts.iter())
+                        .chain(function.body.iter())
+                    {
+                        ExprWalker::walk(&mut dependencies, expr);
+                    }
+                }
+            }
+            for predicate in predicates {
+                if dependencies.predicates.contains(predicate.name()) {
+                    match predicate {
+                        Predicate::Struct(predicate) => {
+                            for body in predicate.body.iter() {
+                                ExprWalker::walk(&mut dependencies, body);
+                            }
+                        }
+                        Predicate::Enum(predicate) => {
+                            ExprWalker::walk(&mut dependencies, &predicate.body());
+                        }
+                        Predicate::Bodyless(..) => {}
+                    }
+                }
+            }
+            if dependencies.functions.len() == function_count
+                && dependencies.predicates.len() == predicate_count
+            {
+                return dependencies;
+            }
+        }
+    }
+}
+
+impl ExprWalker for Dependencies {
+    fn walk_predicate_access_predicate(
+        &mut self,
+        name: &str,
+        arg: &Expr,
+        _perm_amount: PermAmount,
+        _pos: &Position
+    ) {
+        self.predicates.insert(name.to_string());
+        ExprWalker::walk(self, arg);
+    }
+
+    fn walk_unfolding(
+        &mut self,
+        name: &str,
+        args: &Vec<Expr>,
+        body: &Expr,
+        _perm: PermAmount,
+        _variant: &MaybeEnumVariantIndex,
+        _pos: &Position
+    ) {
+        self.predicates.insert(name.to_string());
+        for arg in args {
+            ExprWalker::walk(self, arg);
+        }
+        ExprWalker::walk(self, body);
+    }
+
+    fn walk_func_app(
+        &mut self,
+        name: &str,
+        args: &Vec<Expr>,
+        formal_args: &Vec<LocalVar>,
+        return_type: &Type,
+        _pos: &Position
+    ) {
+        self.functions.insert(compute_identifier(name, formal_args, return_type));
+        for arg in args {
+            ExprWalker::walk(self, arg);
+        }
+    }
+}
+
+impl StmtWalker for Dependencies {
+    fn walk_expr(&mut self, expr: &Expr) {
+        ExprWalker::walk(self, expr);
+    }
+
+    fn walk_fold(
+        &mut self,
+        predicate_name: &str,
+        args: &Vec<Expr>,
+        _perm: &PermAmount,
+        _variant: &MaybeEnumVariantIndex,
+        _pos: &Position
+    ) {
+        self.predicates.insert(predicate_name.to_string());
+        for arg in args {
+            self.walk_expr(arg);
+        }
+    }
+
+    fn walk_unfold(
+        &mut self,
+        predicate_name: &str,
+        args: &Vec<Expr>,
+        _perm: &PermAmount,
+        _variant: &MaybeEnumVariantIndex,
+    ) {
+        self.predicates.insert(predicate_name.to_string());
+        for arg in args {
+            self.walk_expr(arg);
+        }
+    }
+}
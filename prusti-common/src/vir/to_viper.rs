@@ -78,7 +78,11 @@ impl<'v> ToViper<'v, viper::Type<'v>> for Type {
             Type::TypedRef(_) => ast.ref_type(),
             Type::Domain(ref name) => ast.domain_type(&name, &[], &[]),
             Type::Snapshot(ref name) => ast.domain_type(&format!("Snap${}", name), &[], &[]),
-            Type::Seq(ref elem_ty) => ast.seq_type(elem_ty.to_viper(ast))
+            Type::Seq(ref elem_ty) => ast.seq_type(elem_ty.to_viper(ast)),
+            Type::Set(ref elem_ty) => ast.set_type(elem_ty.to_viper(ast)),
+            Type::Map(ref key_ty, ref val_ty) => {
+                ast.map_type(key_ty.to_viper(ast), val_ty.to_viper(ast))
+            }
         }
     }
 }
@@ -400,16 +404,70 @@ impl<'v> ToViper<'v, viper::Expr<'v>> for Expr {
                     ContainerOpKind::SeqLen => {
                         ast.seq_length(left.to_viper(ast))
                     }
+                    ContainerOpKind::SeqContains => {
+                        ast.seq_contains(right.to_viper(ast), left.to_viper(ast))
+                    }
+                    ContainerOpKind::SetUnion => {
+                        ast.any_set_union(left.to_viper(ast), right.to_viper(ast))
+                    }
+                    ContainerOpKind::SetIntersection => {
+                        ast.any_set_intersection(left.to_viper(ast), right.to_viper(ast))
+                    }
+                    ContainerOpKind::SetMinus => {
+                        ast.any_set_minus(left.to_viper(ast), right.to_viper(ast))
+                    }
+                    ContainerOpKind::SetSubset => {
+                        ast.any_set_subset(left.to_viper(ast), right.to_viper(ast))
+                    }
+                    ContainerOpKind::SetContains => {
+                        ast.any_set_contains(right.to_viper(ast), left.to_viper(ast))
+                    }
+                    ContainerOpKind::SetLen => {
+                        ast.any_set_cardinality(left.to_viper(ast))
+                    }
+                    ContainerOpKind::Maplet => {
+                        ast.maplet(left.to_viper(ast), right.to_viper(ast))
+                    }
+                    ContainerOpKind::MapLookup => {
+                        ast.map_lookup(left.to_viper(ast), right.to_viper(ast))
+                    }
+                    ContainerOpKind::MapContains => {
+                        ast.map_contains(right.to_viper(ast), left.to_viper(ast))
+                    }
+                    ContainerOpKind::MapUpdate => {
+                        if let Expr::ContainerOp(
+                            ContainerOpKind::Maplet, box ref key, box ref value, _
+                        ) = right {
+                            ast.map_update(
+                                left.to_viper(ast),
+                                key.to_viper(ast),
+                                value.to_viper(ast),
+                            )
+                        } else {
+                            unreachable!("map update without maplet: {}", right)
+                        }
+                    }
+                    ContainerOpKind::MapLen => {
+                        ast.map_cardinality(left.to_viper(ast))
+                    }
                 }
             }
             Expr::Seq(ty, elems, _pos) => {
-                let elem_ty = if let Type::Seq(box elem_ty) = ty { elem_ty } else { unreachable!() };
-                let viper_elem_ty = elem_ty.to_viper(ast);
-                if elems.is_empty() {
-                    ast.empty_seq(viper_elem_ty)
-                } else {
-                    let viper_elems = elems.iter().map(|e| e.to_viper(ast)).collect::<Vec<_>>();
-                    ast.explicit_seq(&viper_elems)
+                let viper_elems = elems.iter().map(|e| e.to_viper(ast)).collect::<Vec<_>>();
+                match ty {
+                    Type::Seq(box elem_ty) if elems.is_empty() => {
+                        ast.empty_seq(elem_ty.to_viper(ast))
+                    }
+                    Type::Seq(_) => ast.explicit_seq(&viper_elems),
+                    Type::Set(box elem_ty) if elems.is_empty() => {
+                        ast.empty_set(elem_ty.to_viper(ast))
+                    }
+                    Type::Set(_) => ast.explicit_set(&viper_elems),
+                    Type::Map(box key_ty, box val_ty) if elems.is_empty() => {
+                        ast.empty_map(key_ty.to_viper(ast), val_ty.to_viper(ast))
+                    }
+                    Type::Map(..) => ast.explicit_map(&viper_elems),
+                    _ => unreachable!("invalid container type: {}", ty),
                 }
             }
             Expr::Unfolding(
@@ -533,7 +591,7 @@ impl<'v, 'a, 'b> ToViper<'v, viper::Expr<'v>> for (&'a Const, &'b Position) {
             Const::Bool(false) => ast.false_lit_with_pos(self.1.to_viper(ast)),
             Const::Int(x) => ast.int_lit_with_pos(*x, self.1.to_viper(ast)),
             Const::BigInt(ref x) => ast.int_lit_from_ref_with_pos(x, self.1.to_viper(ast)),
-            Const::FnPtr => ast.null_lit_with_pos(self.1.to_viper(ast)),
+            Const::FnPtr | Const::Null => ast.null_lit_with_pos(self.1.to_viper(ast)),
         }
     }
 }
@@ -5,22 +5,34 @@ use proc_macro::TokenStream;
 use quote::quote_spanned;
 
 #[proc_macro_attribute]
-pub fn requires(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+pub fn requires(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    if cfg!(feature = "runtime-checks") {
+        return prusti_specs::runtime_checks::requires(attr.into(), tokens.into()).into();
+    }
     tokens
 }
 
 #[proc_macro_attribute]
-pub fn ensures(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+pub fn ensures(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    if cfg!(feature = "runtime-checks") {
+        return prusti_specs::runtime_checks::ensures(attr.into(), tokens.into()).into();
+    }
     tokens
 }
 
 #[proc_macro_attribute]
 pub fn after_expiry(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    if cfg!(feature = "runtime-checks") {
+        return prusti_specs::runtime_checks::after_expiry(tokens.into()).into();
+    }
     tokens
 }
 
 #[proc_macro_attribute]
 pub fn after_expiry_if(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    if cfg!(feature = "runtime-checks") {
+        return prusti_specs::runtime_checks::after_expiry(tokens.into()).into();
+    }
     tokens
 }
 
@@ -34,12 +46,60 @@ pub fn trusted(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
     tokens
 }
 
+#[proc_macro_attribute]
+pub fn decreases(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
+#[proc_macro_attribute]
+pub fn invariant(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
 #[proc_macro]
-pub fn body_invariant(_tokens: TokenStream) -> TokenStream {
+pub fn body_invariant(tokens: TokenStream) -> TokenStream {
+    if cfg!(feature = "runtime-checks") {
+        return prusti_specs::runtime_checks::body_invariant(tokens.into()).into();
+    }
     let callsite_span = Span::call_site();
     (quote_spanned!(callsite_span=> ())).into()
 }
 
+#[proc_macro]
+pub fn body_variant(_tokens: TokenStream) -> TokenStream {
+    let callsite_span = Span::call_site();
+    (quote_spanned!(callsite_span=> ())).into()
+}
+
+#[proc_macro]
+pub fn prusti_assert(tokens: TokenStream) -> TokenStream {
+    if cfg!(feature = "runtime-checks") {
+        return prusti_specs::runtime_checks::prusti_assert(tokens.into()).into();
+    }
+    let callsite_span = Span::call_site();
+    (quote_spanned!(callsite_span=> ())).into()
+}
+
+#[proc_macro]
+pub fn prusti_assume(_tokens: TokenStream) -> TokenStream {
+    let callsite_span = Span::call_site();
+    (quote_spanned!(callsite_span=> ())).into()
+}
+
+#[proc_macro]
+pub fn prusti_refute(_tokens: TokenStream) -> TokenStream {
+    let callsite_span = Span::call_site();
+    (quote_spanned!(callsite_span=> ())).into()
+}
+
+#[proc_macro]
+pub fn ghost(tokens: TokenStream) -> TokenStream {
+    if cfg!(feature = "runtime-checks") {
+        return prusti_specs::runtime_checks::ghost(tokens.into()).into();
+    }
+    TokenStream::new()
+}
+
 #[proc_macro]
 pub fn closure(tokens: TokenStream) -> TokenStream {
     prusti_specs::closure(tokens.into(), true).into()
@@ -1,5 +1,7 @@
 extern crate proc_macro;
 
+use std::marker::PhantomData;
+
 #[cfg(not(feature = "prusti"))]
 mod private {
     /// A macro for writing a precondition on a function.
@@ -20,9 +22,33 @@ mod private {
     /// A macro for marking a function as trusted.
     pub use prusti_contracts_impl::trusted;
 
-    /// A macro for writing a loop body invariant.
+    /// A macro for writing a termination measure of a function.
+    pub use prusti_contracts_impl::decreases;
+
+    /// A macro for writing a type invariant on a struct or enum.
+    pub use prusti_contracts_impl::invariant;
+
+    /// A macro for writing a loop body invariant. In a `for` loop, the invariant can name the
+    /// iterator of the loop with a binder, as in `body_invariant!(|it: Range<usize>| ...)`.
     pub use prusti_contracts_impl::body_invariant;
 
+    /// A macro for writing a loop variant.
+    pub use prusti_contracts_impl::body_variant;
+
+    /// A macro for asserting a property inside a function body.
+    pub use prusti_contracts_impl::prusti_assert;
+
+    /// A macro for assuming a property inside a function body.
+    pub use prusti_contracts_impl::prusti_assume;
+
+    /// A macro for refuting a property inside a function body, i.e. checking
+    /// that it does not hold in all executions.
+    pub use prusti_contracts_impl::prusti_refute;
+
+    /// A macro for writing ghost code inside a function body. Ghost code is
+    /// erased by the compiler and can only modify ghost variables.
+    pub use prusti_contracts_impl::ghost;
+
     /// A macro for defining a closure with a specification.
     pub use prusti_contracts_impl::closure;
 
@@ -57,9 +83,33 @@ mod private {
     /// A macro for marking a function as trusted.
     pub use prusti_contracts_internal::trusted;
 
-    /// A macro for writing a loop body invariant.
+    /// A macro for writing a termination measure of a function.
+    pub use prusti_contracts_internal::decreases;
+
+    /// A macro for writing a type invariant on a struct or enum.
+    pub use prusti_contracts_internal::invariant;
+
+    /// A macro for writing a loop body invariant. In a `for` loop, the invariant can name the
+    /// iterator of the loop with a binder, as in `body_invariant!(|it: Range<usize>| ...)`.
     pub use prusti_contracts_internal::body_invariant;
 
+    /// A macro for writing a loop variant.
+    pub use prusti_contracts_internal::body_variant;
+
+    /// A macro for asserting a property inside a function body.
+    pub use prusti_contracts_internal::prusti_assert;
+
+    /// A macro for assuming a property inside a function body.
+    pub use prusti_contracts_internal::prusti_assume;
+
+    /// A macro for refuting a property inside a function body, i.e. checking
+    /// that it does not hold in all executions.
+    pub use prusti_contracts_internal::prusti_refute;
+
+    /// A macro for writing ghost code inside a function body. Ghost code is
+    /// erased by the compiler and can only modify ghost variables.
+    pub use prusti_contracts_internal::ghost;
+
     /// A macro for defining a closure with a specification.
     pub use prusti_contracts_internal::closure;
 
@@ -87,4 +137,178 @@ pub fn old<T>(arg: T) -> T {
     arg
 }
 
+/// This function is used to state ownership of the memory a raw pointer
+/// points to. It can only be used in preconditions and postconditions.
+pub fn own<T>(_ptr: *const T) -> bool {
+    true
+}
+
+/// Marks the beginning of a `ghost!` block. It must not be inlined, because the
+/// compiled code is made to skip the ghost code by jumping over the calls.
+#[doc(hidden)]
+#[inline(never)]
+pub fn ghost_begin() {}
+
+/// Marks the end of a `ghost!` block.
+#[doc(hidden)]
+#[inline(never)]
+pub fn ghost_end() {}
+
+/// A mathematical sequence, which can only be used in specifications. It is
+/// encoded as a Viper `Seq`.
+pub struct Seq<T> {
+    _phantom: PhantomData<T>,
+}
+
+/// A mathematical set, which can only be used in specifications. It is
+/// encoded as a Viper `Set`.
+pub struct Set<T> {
+    _phantom: PhantomData<T>,
+}
+
+/// A mathematical map, which can only be used in specifications. It is
+/// encoded as a Viper `Map`.
+pub struct Map<K, V> {
+    _phantom: PhantomData<(K, V)>,
+}
+
+/// The body of the methods of the ghost collections, which are never executed.
+fn ghost<T>() -> T {
+    unreachable!("ghost collections can only be used in specifications")
+}
+
+impl<T> Seq<T> {
+    pub fn empty() -> Self {
+        ghost()
+    }
+
+    pub fn single(_elem: T) -> Self {
+        ghost()
+    }
+
+    /// The sequence extended with `elem` at the end.
+    pub fn push(self, _elem: T) -> Self {
+        ghost()
+    }
+
+    pub fn concat(self, _other: Self) -> Self {
+        ghost()
+    }
+
+    pub fn lookup(self, _index: usize) -> T {
+        ghost()
+    }
+
+    pub fn contains(self, _elem: T) -> bool {
+        ghost()
+    }
+
+    pub fn len(self) -> usize {
+        ghost()
+    }
+
+    pub fn is_empty(self) -> bool {
+        ghost()
+    }
+}
+
+impl<T> Set<T> {
+    pub fn empty() -> Self {
+        ghost()
+    }
+
+    pub fn single(_elem: T) -> Self {
+        ghost()
+    }
+
+    /// The set extended with `elem`.
+    pub fn insert(self, _elem: T) -> Self {
+        ghost()
+    }
+
+    pub fn union(self, _other: Self) -> Self {
+        ghost()
+    }
+
+    pub fn intersection(self, _other: Self) -> Self {
+        ghost()
+    }
+
+    pub fn difference(self, _other: Self) -> Self {
+        ghost()
+    }
+
+    pub fn is_subset(self, _other: Self) -> bool {
+        ghost()
+    }
+
+    pub fn contains(self, _elem: T) -> bool {
+        ghost()
+    }
+
+    pub fn len(self) -> usize {
+        ghost()
+    }
+
+    pub fn is_empty(self) -> bool {
+        ghost()
+    }
+}
+
+impl<K, V> Map<K, V> {
+    pub fn empty() -> Self {
+        ghost()
+    }
+
+    pub fn single(_key: K, _value: V) -> Self {
+        ghost()
+    }
+
+    /// The map updated to map `key` to `value`.
+    pub fn insert(self, _key: K, _value: V) -> Self {
+        ghost()
+    }
+
+    /// The value of `key`, which must be contained in the map.
+    pub fn lookup(self, _key: K) -> V {
+        ghost()
+    }
+
+    pub fn contains_key(self, _key: K) -> bool {
+        ghost()
+    }
+
+    pub fn len(self) -> usize {
+        ghost()
+    }
+
+    pub fn is_empty(self) -> bool {
+        ghost()
+    }
+}
+
+macro_rules! impl_ghost_traits {
+    ($name:ident < $($param:ident),* >) => {
+        impl<$($param),*> Clone for $name<$($param),*> {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<$($param),*> Copy for $name<$($param),*> {}
+
+        impl<$($param),*> PartialEq for $name<$($param),*> {
+            fn eq(&self, _other: &Self) -> bool {
+                ghost()
+            }
+        }
+
+        impl<$($param),*> Eq for $name<$($param),*> {}
+    };
+}
+
+impl_ghost_traits!(Seq<T>);
+impl_ghost_traits!(Set<T>);
+impl_ghost_traits!(Map<K, V>);
+
 pub use private::*;
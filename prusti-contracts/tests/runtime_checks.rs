@@ -0,0 +1,105 @@
+#![cfg(feature = "runtime-checks")]
+
+use prusti_contracts::*;
+
+#[requires(x > 0)]
+#[ensures(result == x - 1)]
+fn decrement(x: u32) -> u32 {
+    x - 1
+}
+
+#[ensures(result >= x)]
+fn wrong_early_return(x: i32) -> i32 {
+    if x > 10 {
+        return 0;
+    }
+    x
+}
+
+#[ensures(v.len() == old(v.len()) + 1)]
+#[ensures(v[v.len() - 1] == x)]
+fn push(v: &mut Vec<i32>, x: i32) {
+    v.push(x);
+}
+
+#[ensures(v.len() == old(v.len()))]
+fn wrong_push(v: &mut Vec<i32>, x: i32) {
+    v.push(x);
+}
+
+#[ensures(x > 0 ==> result == x)]
+#[ensures(forall(|i: usize| i < 10 ==> i < 11))]
+fn quantifier(x: i32) -> i32 {
+    x
+}
+
+fn count(n: usize, invariant_offset: usize) -> usize {
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i + invariant_offset < n);
+        i += 1;
+    }
+    prusti_assert!(i == n);
+    i
+}
+
+#[ensures(Seq::single(x).len() == 1)]
+#[ensures(result == x)]
+fn ghost_collection(x: i32) -> i32 {
+    x
+}
+
+fn ghost_variable(n: usize) -> usize {
+    let mut i = 0;
+    ghost! {
+        let mut steps = 0;
+    }
+    while i < n {
+        body_invariant!(i < n && steps == i);
+        i += 1;
+        ghost! {
+            steps += 1;
+        }
+    }
+    prusti_assert!(steps == i);
+    i
+}
+
+#[test]
+fn satisfied_contracts() {
+    assert_eq!(decrement(3), 2);
+    let mut v = vec![1];
+    push(&mut v, 2);
+    assert_eq!(quantifier(4), 4);
+    assert_eq!(count(5, 0), 5);
+}
+
+#[test]
+fn skipped_ghost_checks() {
+    assert_eq!(ghost_collection(3), 3);
+    assert_eq!(ghost_variable(5), 5);
+}
+
+#[test]
+#[should_panic(expected = "precondition might not hold")]
+fn failing_precondition() {
+    decrement(0);
+}
+
+#[test]
+#[should_panic(expected = "postcondition might not hold")]
+fn failing_postcondition_on_return() {
+    wrong_early_return(20);
+}
+
+#[test]
+#[should_panic(expected = "postcondition might not hold")]
+fn failing_postcondition_with_old() {
+    wrong_push(&mut vec![], 1);
+}
+
+#[test]
+#[should_panic(expected = "loop invariant might not hold")]
+fn failing_loop_invariant() {
+    count(5, 1);
+}
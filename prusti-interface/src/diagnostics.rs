@@ -0,0 +1,178 @@
+// © 2021, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Machine-readable output of the errors reported by Prusti.
+//!
+//! When the `DIAGNOSTICS_FORMAT` flag is set, every emitted `PrustiError` is also recorded here,
+//! and the recorded diagnostics of a crate are written to a file in `DIAGNOSTICS_DIR`, either as
+//! JSON or as SARIF.
+
+use crate::PrustiErrorKind;
+use prusti_common::config;
+use rustc_span::{source_map::SourceMap, MultiSpan, Span};
+use serde::Serialize;
+use serde_json::json;
+use std::{fs, io, path::PathBuf, sync::Mutex};
+
+lazy_static! {
+    static ref DIAGNOSTICS: Mutex<Vec<Diagnostic>> = Mutex::new(vec![]);
+}
+
+/// An error or warning reported by Prusti, with all spans resolved to source locations.
+#[derive(Clone, Debug, Serialize)]
+pub struct Diagnostic {
+    pub level: DiagnosticLevel,
+    pub kind: PrustiErrorKind,
+    /// The kind of Viper check that failed (e.g. `ExhaleMethodPrecondition`), if any.
+    pub context: Option<String>,
+    pub message: String,
+    pub spans: Vec<DiagnosticSpan>,
+    pub failing_assertion: Vec<DiagnosticSpan>,
+    pub help: Option<String>,
+    pub notes: Vec<DiagnosticNote>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticLevel {
+    Error,
+    Warning,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DiagnosticNote {
+    pub message: String,
+    pub spans: Vec<DiagnosticSpan>,
+}
+
+/// A source range. Lines and columns start at 1, as in the JSON output of rustc.
+#[derive(Clone, Debug, Serialize)]
+pub struct DiagnosticSpan {
+    pub file_name: String,
+    pub line_start: usize,
+    pub column_start: usize,
+    pub line_end: usize,
+    pub column_end: usize,
+}
+
+impl DiagnosticSpan {
+    fn new(source_map: &SourceMap, span: Span) -> Self {
+        let start = source_map.lookup_char_pos(span.lo());
+        let end = source_map.lookup_char_pos(span.hi());
+        DiagnosticSpan {
+            file_name: start.file.name.prefer_local().to_string(),
+            line_start: start.line,
+            column_start: start.col.0 + 1,
+            line_end: end.line,
+            column_end: end.col.0 + 1,
+        }
+    }
+
+    /// The primary spans of `span`.
+    pub fn from_multispan(source_map: &SourceMap, span: &MultiSpan) -> Vec<Self> {
+        span.primary_spans()
+            .iter()
+            .map(|&span| DiagnosticSpan::new(source_map, span))
+            .collect()
+    }
+}
+
+/// Whether the emitted errors should be recorded.
+pub fn is_enabled() -> bool {
+    !config::diagnostics_format().is_empty()
+}
+
+pub fn record(diagnostic: Diagnostic) {
+    DIAGNOSTICS.lock().unwrap().push(diagnostic);
+}
+
+/// Write all the diagnostics recorded so far to the diagnostics file of the crate, replacing
+/// its previous content. This is a noop if the diagnostics output is disabled.
+pub fn write(crate_name: &str) -> io::Result<()> {
+    if !is_enabled() {
+        return Ok(());
+    }
+    let format = config::diagnostics_format();
+    let diagnostics = DIAGNOSTICS.lock().unwrap();
+    let content = match format.as_str() {
+        "json" => serde_json::to_string_pretty(&*diagnostics)?,
+        "sarif" => serde_json::to_string_pretty(&to_sarif(&diagnostics))?,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown diagnostics format {:?}; expected \"json\" or \"sarif\"", format),
+            ));
+        }
+    };
+    let dir = PathBuf::from(config::diagnostics_dir());
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(format!("{}.{}", crate_name, format)), content)
+}
+
+fn to_sarif(diagnostics: &[Diagnostic]) -> serde_json::Value {
+    let locations = |spans: &[DiagnosticSpan]| -> Vec<serde_json::Value> {
+        spans
+            .iter()
+            .map(|span| {
+                json!({
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": span.file_name },
+                        "region": {
+                            "startLine": span.line_start,
+                            "startColumn": span.column_start,
+                            "endLine": span.line_end,
+                            "endColumn": span.column_end,
+                        }
+                    }
+                })
+            })
+            .collect()
+    };
+    let results: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let related_locations: Vec<_> = diagnostic
+                .notes
+                .iter()
+                .flat_map(|note| {
+                    locations(&note.spans).into_iter().map(move |mut location| {
+                        location["message"] = json!({ "text": note.message });
+                        location
+                    })
+                })
+                .collect();
+            json!({
+                "ruleId": format!("prusti/{}", diagnostic.kind.as_str()),
+                "level": match diagnostic.level {
+                    DiagnosticLevel::Error => "error",
+                    DiagnosticLevel::Warning => "warning",
+                },
+                "message": { "text": diagnostic.message },
+                "locations": locations(&diagnostic.spans),
+                "relatedLocations": related_locations,
+                "properties": {
+                    "kind": diagnostic.kind,
+                    "context": diagnostic.context,
+                    "failingAssertion": locations(&diagnostic.failing_assertion),
+                    "help": diagnostic.help,
+                },
+            })
+        })
+        .collect();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "Prusti",
+                    "informationUri": "https://github.com/viperproject/prusti-dev",
+                }
+            },
+            "results": results,
+        }]
+    })
+}
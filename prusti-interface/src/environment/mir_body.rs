@@ -0,0 +1,46 @@
+// © 2021, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir;
+use rustc_middle::ty::{self, TyCtxt};
+use std::cell::Ref;
+use std::ops::Deref;
+
+/// The MIR body of a procedure.
+///
+/// The body of a local procedure is the one computed by `mir_promoted`, which is
+/// the one on which the borrow checker runs. The body of a procedure of another
+/// crate is the optimized one stored in the crate metadata (Prusti compiles all
+/// crates with `-Zalways-encode-mir`), which is what allows the specifications of
+/// dependencies to be encoded.
+pub enum MirBody<'a, 'tcx> {
+    Local(Ref<'a, mir::Body<'tcx>>),
+    External(&'a mir::Body<'tcx>),
+}
+
+impl<'a, 'tcx: 'a> MirBody<'a, 'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>, def_id: DefId) -> Self {
+        if let Some(local_def_id) = def_id.as_local() {
+            MirBody::Local(
+                tcx.mir_promoted(ty::WithOptConstParam::unknown(local_def_id)).0.borrow()
+            )
+        } else {
+            MirBody::External(tcx.optimized_mir(def_id))
+        }
+    }
+}
+
+impl<'a, 'tcx> Deref for MirBody<'a, 'tcx> {
+    type Target = mir::Body<'tcx>;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            MirBody::Local(body) => body,
+            MirBody::External(body) => body,
+        }
+    }
+}
@@ -25,6 +25,7 @@ mod collect_closure_defs_visitor;
 mod dump_borrowck_info;
 mod loops;
 mod loops_utils;
+mod mir_body;
 pub mod mir_analyses;
 pub mod mir_utils;
 pub mod place_set;
@@ -36,6 +37,7 @@ use self::collect_closure_defs_visitor::CollectClosureDefsVisitor;
 use rustc_hir::intravisit::Visitor;
 pub use self::loops::{PlaceAccess, PlaceAccessKind, ProcedureLoops};
 pub use self::loops_utils::*;
+pub use self::mir_body::MirBody;
 pub use self::procedure::{BasicBlockIndex, Procedure};
 // use config;
 use crate::data::ProcedureDefId;
@@ -100,10 +102,10 @@ impl<'tcx> Environment<'tcx> {
     //     self.state.session.span_warn(sp, msg);
     // }
 
-    // /// Emits an error message.
-    // pub fn err(&self, msg: &str) {
-    //     self.state.session.err(msg);
-    // }
+    /// Emits an error message that is not related to a location in the source code.
+    pub fn err(&self, msg: &str) {
+        self.tcx.sess.err(msg);
+    }
 
     // /// Emits an error message.
     // pub fn span_err<S: Into<MultiSpan>>(&self, sp: S, msg: &str) {
@@ -111,38 +113,38 @@ impl<'tcx> Environment<'tcx> {
     // }
 
     /// Emits an error message.
-    pub fn span_err_with_help_and_note<S: Into<MultiSpan> + Clone>(
+    pub fn span_err_with_help_and_notes<S: Into<MultiSpan> + Clone>(
         &self,
         sp: S,
         msg: &str,
         help: &Option<String>,
-        note: &Option<(String, S)>
+        notes: &[(String, S)]
     ) {
         let mut diagnostic = self.tcx.sess.struct_err(msg);
         diagnostic.set_span(sp);
         if let Some(help_msg) = help {
             diagnostic.help(help_msg);
         }
-        if let Some((note_msg, note_sp)) = note {
+        for (note_msg, note_sp) in notes {
             diagnostic.span_note(note_sp.clone(), note_msg);
         }
         diagnostic.emit();
     }
 
     /// Emits an error message.
-    pub fn span_warn_with_help_and_note<S: Into<MultiSpan> + Clone>(
+    pub fn span_warn_with_help_and_notes<S: Into<MultiSpan> + Clone>(
         &self,
         sp: S,
         msg: &str,
         help: &Option<String>,
-        note: &Option<(String, S)>
+        notes: &[(String, S)]
     ) {
         let mut diagnostic = self.tcx.sess.struct_warn(msg);
         diagnostic.set_span(sp);
         if let Some(help_msg) = help {
             diagnostic.help(help_msg);
         }
-        if let Some((note_msg, note_sp)) = note {
+        for (note_msg, note_sp) in notes {
             diagnostic.span_note(note_sp.clone(), note_msg);
         }
         diagnostic.emit();
@@ -191,9 +193,10 @@ impl<'tcx> Environment<'tcx> {
     }
 
     /// Get the span of a definition
-    /// Note: panics on non-local `def_id`
+    /// Note: for a non-local `def_id` this is only the span of the item's header
     pub fn get_item_span(&self, def_id: DefId) -> Span {
-        self.tcx.hir().span_if_local(def_id).unwrap()
+        self.tcx.hir().span_if_local(def_id)
+            .unwrap_or_else(|| self.tcx.def_span(def_id))
     }
 
     pub fn get_absolute_item_name(&self, def_id: DefId) -> String {
@@ -222,6 +225,11 @@ impl<'tcx> Environment<'tcx> {
         self.tcx().optimized_mir(def_id)
     }
 
+    /// Get the MIR body of a local or external procedure.
+    pub fn mir<'a>(&self, def_id: DefId) -> MirBody<'a, 'tcx> {
+        MirBody::new(self.tcx(), def_id)
+    }
+
     /// Get all relevant trait declarations for some type.
     pub fn get_traits_decls_for_type(&self, ty: &ty::Ty<'tcx>) -> HashSet<DefId> {
         let mut res = HashSet::new();
@@ -262,11 +262,12 @@ pub enum PoloniusInfoError {
         loop_head: mir::BasicBlock,
         variable: mir::Local,
     },
-    LoansInNestedLoops(mir::Location, mir::BasicBlock, mir::Location, mir::BasicBlock),
-    ReborrowingDagHasNoMagicWands(mir::Location),
-    /// We currently support only one reborrowing chain per loop
-    MultipleMagicWandsPerLoop(mir::Location),
-    MagicWandHasNoRepresentativeLoan(mir::Location),
+    /// A loan that is carried across the iterations of the loop with the given head expires
+    /// after the loop, but the loop has no magic wand to restore it.
+    ReborrowingDagHasNoMagicWands(mir::BasicBlock),
+    /// The loans carried across the iterations of the loop with the given head form more than
+    /// one reborrowing chain. We currently support only one magic wand per loop.
+    MultipleMagicWandsPerLoop(mir::BasicBlock),
     PlaceRegionsError(PlaceRegionsError, Span),
     LoanInUnsupportedStatement(String, mir::Location),
 }
@@ -1362,12 +1363,10 @@ impl<'a, 'tcx: 'a> PoloniusInfo<'a, 'tcx> {
         )
     }
 
-    /// Get loops in which loans are defined (if any).
-    pub fn get_loan_loops(
-        &self,
-        loans: &[facts::Loan]
-    ) -> Result<Vec<(facts::Loan, mir::BasicBlock)>, PoloniusInfoError> {
-        let pairs: Vec<_> = loans
+    /// Get the innermost loops in which loans are defined (if any). The loans may be defined in
+    /// different, possibly nested, loops.
+    pub fn get_loan_loops(&self, loans: &[facts::Loan]) -> Vec<(facts::Loan, mir::BasicBlock)> {
+        loans
             .iter()
             .flat_map(|loan| {
                 let loan_location = if let Some(location) = self.loan_position.get(loan) {
@@ -1381,22 +1380,24 @@ impl<'a, 'tcx: 'a> PoloniusInfo<'a, 'tcx> {
                     .get_loop_head(loan_location.block)
                     .map(|loop_head| (*loan, loop_head))
             })
-            .collect();
-        for (loan1, loop1) in pairs.iter() {
-            let location1 = self.loan_position[loan1];
-            for (loan2, loop2) in pairs.iter() {
-                let location2 = self.loan_position[loan2];
-                if loop1 != loop2 {
-                    return Err(PoloniusInfoError::LoansInNestedLoops(
-                        location1,
-                        *loop1,
-                        location2,
-                        *loop2
-                    ));
-                }
-            }
-        }
-        Ok(pairs)
+            .collect()
+    }
+
+    /// Is `loan`, which is defined in the loop with head `loop_head`, still alive when the next
+    /// iteration of the loop starts? Only such loans form reborrowing chains of unbounded length,
+    /// which need a loop magic wand. The other loans defined in the loop belong to the last
+    /// iteration and can be restored like loans defined outside of loops.
+    pub fn is_loan_carried_by_loop(&self, loan: facts::Loan, loop_head: mir::BasicBlock) -> bool {
+        let location = mir::Location {
+            block: loop_head,
+            statement_index: 0,
+        };
+        let point = self.get_point(location, facts::PointType::Start);
+        [false, true].iter().any(|&zombie| {
+            self.get_borrow_live_at(zombie)
+                .get(&point)
+                .map_or(false, |loans| loans.contains(&loan))
+        })
     }
 
     /// ``loans`` – all loans, including the zombie loans.
@@ -1422,45 +1423,49 @@ impl<'a, 'tcx: 'a> PoloniusInfo<'a, 'tcx> {
             )
         }).cloned().collect();
 
-        // The representative_loan is a loan that is the root of the
-        // reborrowing in some loop. Since it has no proper
-        // reborrows_direct relation (because of the cycles), it needs
-        // manual treatment in the visit function.
-        let mut representative_loan = None;
+        // The representative_loans are the loans that are the roots of the
+        // reborrowing in some loop, one for each loop magic wand. Since they
+        // have no proper reborrows_direct relation (because of the cycles),
+        // they need manual treatment in the visit function.
+        let mut representative_loans = Vec::new();
         if let Some(loop_head) = self.loops.get_loop_head(location.block) {
             let depth = self.loops.get_loop_head_depth(loop_head);
             debug!("loop_head: {:?} depth: {:?}", loop_head, depth);
             // It is fine to have loans defined in an outer loop that is not `loop_head`, because
             // `return` or panic statements might need to jump out of many loops at once.
         } else {
-            let loan_loops = self.get_loan_loops(&loans)?;
-            if !loan_loops.is_empty() {
-                for (loan, loop_head) in loan_loops.iter() {
+            let carried_loans: Vec<_> = self
+                .get_loan_loops(&loans)
+                .into_iter()
+                .filter(|&(loan, loop_head)| {
                     debug!("loan={:?} loop_head={:?}", loan, loop_head);
-                }
-                let (_, loop_head) = loan_loops[0];
+                    self.is_loan_carried_by_loop(loan, loop_head)
+                })
+                .collect();
+            let mut carrying_loops: Vec<_> = carried_loans
+                .iter()
+                .map(|&(_, loop_head)| loop_head)
+                .collect();
+            carrying_loops.sort();
+            carrying_loops.dedup();
+            for loop_head in carrying_loops {
                 debug!("loop_head = {:?}", loop_head);
-                if self.loop_magic_wands.is_empty() {
-                    return Err(PoloniusInfoError::ReborrowingDagHasNoMagicWands(location));
-                }
-                let loop_magic_wands = &self.loop_magic_wands[&loop_head];
-                if loop_magic_wands.len() != 1 {
-                    return Err(PoloniusInfoError::MultipleMagicWandsPerLoop(location));
-                }
-                let magic_wand = &loop_magic_wands[0];
-                representative_loan = Some(magic_wand.root_loan);
-                if representative_loan.is_none() {
-                    return Err(PoloniusInfoError::MagicWandHasNoRepresentativeLoan(location));
-                }
-                loans = loans
-                    .into_iter()
-                    .filter(|loan| {
-                        !loan_loops.iter().any(|(loop_loan, _)| {
-                            loop_loan == loan && Some(*loan) != representative_loan
-                        })
-                    })
-                    .collect();
+                let magic_wand = match self.loop_magic_wands.get(&loop_head) {
+                    Some(magic_wands) if magic_wands.len() == 1 => &magic_wands[0],
+                    Some(magic_wands) if magic_wands.len() > 1 => {
+                        return Err(PoloniusInfoError::MultipleMagicWandsPerLoop(loop_head));
+                    }
+                    _ => return Err(PoloniusInfoError::ReborrowingDagHasNoMagicWands(loop_head)),
+                };
+                representative_loans.push(magic_wand.root_loan);
             }
+            loans = loans
+                .into_iter()
+                .filter(|loan| {
+                    representative_loans.contains(loan)
+                        || !carried_loans.iter().any(|(carried_loan, _)| carried_loan == loan)
+                })
+                .collect();
         }
 
         // Topologically sort loans.
@@ -1469,7 +1474,7 @@ impl<'a, 'tcx: 'a> PoloniusInfo<'a, 'tcx> {
         let mut temporary_mark = vec![false; loans.len()];
         fn visit(
             this: &PoloniusInfo,
-            representative_loan: &Option<facts::Loan>,
+            representative_loans: &[facts::Loan],
             reborrows_direct: &Vec<(facts::Loan, facts::Loan)>,
             loans: &[facts::Loan],
             current: usize,
@@ -1482,8 +1487,8 @@ impl<'a, 'tcx: 'a> PoloniusInfo<'a, 'tcx> {
             }
             assert!(
                 !temporary_mark[current],
-                "Not a DAG!\nrepresentative_loan: {:?}\nreborrows_direct: {:?}\nloans: {:?}\ncurrent: {:?}\nsorted_loans: {:?}\npermanent_mark: {:?}\ntemporary_mark: {:?}\nloan_location: {:?}",
-                representative_loan,
+                "Not a DAG!\nrepresentative_loans: {:?}\nreborrows_direct: {:?}\nloans: {:?}\ncurrent: {:?}\nsorted_loans: {:?}\npermanent_mark: {:?}\ntemporary_mark: {:?}\nloan_location: {:?}",
+                representative_loans,
                 reborrows_direct,
                 loans,
                 current,
@@ -1494,7 +1499,7 @@ impl<'a, 'tcx: 'a> PoloniusInfo<'a, 'tcx> {
             );
             temporary_mark[current] = true;
             let current_loan = loans[current];
-            if Some(current_loan) == *representative_loan {
+            if representative_loans.contains(&current_loan) {
                 for (new_current, &loan) in loans.iter().enumerate() {
                     if loan == current_loan {
                         // The reborrows relation is reflexive, so we need this check.
@@ -1507,7 +1512,7 @@ impl<'a, 'tcx: 'a> PoloniusInfo<'a, 'tcx> {
                     {
                         visit(
                             this,
-                            representative_loan,
+                            representative_loans,
                             reborrows_direct,
                             loans,
                             new_current,
@@ -1519,7 +1524,7 @@ impl<'a, 'tcx: 'a> PoloniusInfo<'a, 'tcx> {
                 }
             } else {
                 for (new_current, &loan) in loans.iter().enumerate() {
-                    if Some(loan) == *representative_loan {
+                    if representative_loans.contains(&loan) {
                         if this
                             .additional_facts
                             .reborrows
@@ -1527,7 +1532,7 @@ impl<'a, 'tcx: 'a> PoloniusInfo<'a, 'tcx> {
                         {
                             visit(
                                 this,
-                                representative_loan,
+                                representative_loans,
                                 reborrows_direct,
                                 loans,
                                 new_current,
@@ -1540,7 +1545,7 @@ impl<'a, 'tcx: 'a> PoloniusInfo<'a, 'tcx> {
                         if reborrows_direct.contains(&(current_loan, loan)) {
                             visit(
                                 this,
-                                representative_loan,
+                                representative_loans,
                                 reborrows_direct,
                                 loans,
                                 new_current,
@@ -1563,7 +1568,7 @@ impl<'a, 'tcx: 'a> PoloniusInfo<'a, 'tcx> {
             };
             visit(
                 self,
-                &representative_loan,
+                &representative_loans,
                 reborrows_direct,
                 &loans,
                 index,
@@ -1581,7 +1586,7 @@ impl<'a, 'tcx: 'a> PoloniusInfo<'a, 'tcx> {
                 let reborrowed_loans = sorted_loans.iter().cloned()
                     .filter(|&l| self.additional_facts.reborrows_direct.contains(&(loan, l)))
                     .collect::<Vec<_>>();
-                let kind = self.construct_reborrowing_kind(loan, representative_loan);
+                let kind = self.construct_reborrowing_kind(loan, &representative_loans);
                 let zombity = self.construct_reborrowing_zombity(
                     loan, &loans, zombie_loans, location);
                 let incoming_zombies = self.check_incoming_zombies(
@@ -1597,7 +1602,7 @@ impl<'a, 'tcx: 'a> PoloniusInfo<'a, 'tcx> {
     fn construct_reborrowing_kind(
         &self,
         loan: facts::Loan,
-        representative_loan: Option<facts::Loan>,
+        representative_loans: &[facts::Loan],
     ) -> ReborrowingKind {
         if let Some(local) = self.call_magic_wands.get(&loan) {
             let region = self.place_regions.for_local(*local).unwrap();
@@ -1608,7 +1613,7 @@ impl<'a, 'tcx: 'a> PoloniusInfo<'a, 'tcx> {
             }
         } else if self.argument_moves.contains(&loan) {
             ReborrowingKind::ArgumentMove { loan: loan }
-        } else if Some(loan) == representative_loan {
+        } else if representative_loans.contains(&loan) {
             for magic_wands in self.loop_magic_wands.values() {
                 for magic_wand in magic_wands.iter() {
                     if magic_wand.root_loan == loan {
@@ -9,14 +9,14 @@ use crate::data::ProcedureDefId;
 use rustc_middle::mir::{self, Body as Mir, Rvalue, AggregateKind};
 use rustc_middle::mir::{BasicBlock, BasicBlockData, Terminator, TerminatorKind};
 use rustc_middle::ty::{self, Ty, TyCtxt};
-use std::cell::Ref;
-use std::collections::{HashSet, HashMap};
+use std::collections::{HashSet, HashMap, VecDeque};
 use rustc_span::Span;
 use log::{trace, debug};
 use rustc_middle::mir::StatementKind;
 use rustc_hir::def_id;
 use std::iter::FromIterator;
 use crate::environment::mir_utils::RealEdges;
+use crate::environment::MirBody;
 
 /// Index of a Basic Block
 pub type BasicBlockIndex = mir::BasicBlock;
@@ -25,11 +25,13 @@ pub type BasicBlockIndex = mir::BasicBlock;
 pub struct Procedure<'a, 'tcx: 'a> {
     tcx: TyCtxt<'tcx>,
     proc_def_id: ProcedureDefId,
-    mir: Ref<'a, Mir<'tcx>>,
+    mir: MirBody<'a, 'tcx>,
     real_edges: RealEdges,
     loop_info: loops::ProcedureLoops,
     reachable_basic_blocks: HashSet<BasicBlock>,
     nonspec_basic_blocks: HashSet<BasicBlock>,
+    ghost_basic_blocks: HashSet<BasicBlock>,
+    ghost_escapes: HashSet<BasicBlock>,
 }
 
 impl<'a, 'tcx> Procedure<'a, 'tcx> {
@@ -37,11 +39,12 @@ impl<'a, 'tcx> Procedure<'a, 'tcx> {
     /// identifier of a procedure
     pub fn new(tcx: TyCtxt<'tcx>, proc_def_id: ProcedureDefId) -> Self {
         trace!("Encoding procedure {:?}", proc_def_id);
-        let (mir, _) = tcx.mir_promoted(ty::WithOptConstParam::unknown(proc_def_id.expect_local()));
-        let mir = mir.borrow();
+        let mir = MirBody::new(tcx, proc_def_id);
         let real_edges = RealEdges::new(&mir);
         let reachable_basic_blocks = build_reachable_basic_blocks(&mir, &real_edges);
         let nonspec_basic_blocks = build_nonspec_basic_blocks(&mir, &real_edges, &tcx);
+        let (ghost_basic_blocks, ghost_escapes) =
+            build_ghost_basic_blocks(&mir, &real_edges, &tcx);
         let loop_info = loops::ProcedureLoops::new(&mir, &real_edges);
 
         Self {
@@ -52,6 +55,8 @@ impl<'a, 'tcx> Procedure<'a, 'tcx> {
             loop_info,
             reachable_basic_blocks,
             nonspec_basic_blocks,
+            ghost_basic_blocks,
+            ghost_escapes,
         }
     }
 
@@ -144,38 +149,78 @@ impl<'a, 'tcx> Procedure<'a, 'tcx> {
         !self.nonspec_basic_blocks.contains(&bbi)
     }
 
+    /// Get the specification closures that are type-checked in the specification blocks
+    /// reachable from `bbi` without passing through a non-specification block, together with
+    /// the block in which each closure is instantiated.
+    pub fn get_spec_closures(&self, bbi: BasicBlockIndex) -> Vec<(BasicBlockIndex, def_id::DefId)> {
+        let mut closures = vec![];
+        let mut visited: HashSet<BasicBlock> = HashSet::new();
+        let mut to_visit = vec![bbi];
+        while let Some(curr_bb) = to_visit.pop() {
+            if !self.is_spec_block(curr_bb) || !visited.insert(curr_bb) {
+                continue;
+            }
+            for stmt in &self.mir[curr_bb].statements {
+                if let StatementKind::Assign(box (
+                    _,
+                    Rvalue::Aggregate(box AggregateKind::Closure(def_id, _), _),
+                )) = stmt.kind {
+                    if is_spec_closure(def_id, &self.tcx) {
+                        closures.push((curr_bb, def_id));
+                    }
+                }
+            }
+            to_visit.extend(self.real_edges.successors(curr_bb).iter().cloned());
+        }
+        closures
+    }
+
+    /// Check whether the block is used for typechecking a ghost statement, i.e. a
+    /// `prusti_assert!`, `prusti_assume!` or `prusti_refute!`, rather than a loop specification
+    pub fn is_ghost_statement_block(&self, bbi: BasicBlockIndex) -> bool {
+        self.get_spec_closures(bbi).into_iter().any(|(_, def_id)| {
+            crate::utils::has_prusti_attr(self.tcx.get_attrs(def_id), "ghost_statement")
+        })
+    }
+
     /// Check whether the block is reachable
     pub fn is_reachable_block(&self, bbi: BasicBlockIndex) -> bool {
         self.reachable_basic_blocks.contains(&bbi)
     }
 
+    /// Check whether the block belongs to a `ghost!` block of the procedure body
+    pub fn is_ghost_block(&self, bbi: BasicBlockIndex) -> bool {
+        self.ghost_basic_blocks.contains(&bbi)
+    }
+
+    /// Check whether the execution leaves a `ghost!` block at this block other than
+    /// through the end of the ghost block, e.g. because of a `return`, `break`,
+    /// `continue` or `?`, or because it diverges.
+    pub fn is_ghost_escape(&self, bbi: BasicBlockIndex) -> bool {
+        self.ghost_escapes.contains(&bbi)
+    }
+
+    /// Check whether the local is (potentially) used by non-ghost code, i.e. it is
+    /// the return place, an argument, or a local that is not declared in a ghost block.
+    pub fn is_nonghost_local(&self, local: mir::Local) -> bool {
+        if local.index() <= self.mir.arg_count {
+            return true;
+        }
+        self.mir.basic_blocks().iter_enumerated().any(|(bbi, bb_data)| {
+            !self.is_ghost_block(bbi) && bb_data.statements.iter().any(|stmt| {
+                matches!(stmt.kind, StatementKind::StorageLive(live) if live == local)
+            })
+        })
+    }
+
     pub fn is_panic_block(&self, bbi: BasicBlockIndex) -> bool {
-        if let TerminatorKind::Call {
-            args: ref _args,
-            destination: ref _destination,
-            func:
-                mir::Operand::Constant(box mir::Constant {
-                    literal: mir::ConstantKind::Ty(
-                        ty::Const {
-                            ty,
-                            ..
-                        },
-                    ),
-                    ..
-                }),
-            ..
-        } = self.mir[bbi].terminator().kind {
-            if let ty::TyKind::FnDef(def_id, ..) = ty.kind() {
-                // let func_proc_name = self.tcx.absolute_item_path_str(def_id);
-                let func_proc_name = self.tcx.def_path_str(*def_id);
+        match called_function_path(self.mir[bbi].terminator(), &self.tcx) {
+            Some(func_proc_name) => {
                 &func_proc_name == "std::rt::begin_panic"
                     || &func_proc_name == "core::panicking::panic"
                     || &func_proc_name == "core::panicking::panic_fmt"
-            } else {
-                false
             }
-        } else {
-            false
+            None => false,
         }
     }
 
@@ -211,6 +256,92 @@ fn build_reachable_basic_blocks(mir: &Mir, real_edges: &RealEdges) -> HashSet<Ba
     reachable_basic_blocks
 }
 
+/// Returns the path of the function called by the terminator, if it is a call of a
+/// statically known function.
+fn called_function_path(terminator: &Terminator, tcx: &TyCtxt) -> Option<String> {
+    if let TerminatorKind::Call {
+        func:
+            mir::Operand::Constant(box mir::Constant {
+                literal: mir::ConstantKind::Ty(
+                    ty::Const {
+                        ty,
+                        ..
+                    },
+                ),
+                ..
+            }),
+        ..
+    } = terminator.kind {
+        if let ty::TyKind::FnDef(def_id, ..) = ty.kind() {
+            return Some(tcx.def_path_str(*def_id));
+        }
+    }
+    None
+}
+
+/// Returns the set of basic blocks that are executed between the `ghost_begin` and
+/// `ghost_end` markers emitted by the `ghost!` macro, and the ghost blocks from which
+/// the execution leaves a `ghost!` block other than through its end. The blocks that
+/// call `ghost_end` are included, unwinding blocks are not.
+///
+/// For each `ghost!` block, only the first escaping block found by a breadth-first
+/// search is returned, so that each invalid `ghost!` block is reported once.
+fn build_ghost_basic_blocks(
+    mir: &Mir,
+    real_edges: &RealEdges,
+    tcx: &TyCtxt,
+) -> (HashSet<BasicBlock>, HashSet<BasicBlock>) {
+    let calls = |bb: BasicBlock, path: &str| {
+        called_function_path(mir[bb].terminator(), tcx).map_or(false, |p| p == path)
+    };
+    let is_begin = |bb: BasicBlock| calls(bb, "prusti_contracts::ghost_begin");
+    let is_end = |bb: BasicBlock| calls(bb, "prusti_contracts::ghost_end");
+    let mut ghost_basic_blocks: HashSet<BasicBlock> = HashSet::new();
+    let mut ghost_escapes: HashSet<BasicBlock> = HashSet::new();
+    for begin in mir.basic_blocks().indices().filter(|&bb| is_begin(bb)) {
+        // The blocks reachable from the beginning of the ghost block without passing
+        // through its end or through the beginning of a ghost block.
+        let mut region: Vec<BasicBlock> = vec![];
+        let mut to_visit: VecDeque<BasicBlock> =
+            real_edges.successors(begin).iter().cloned().collect();
+        while let Some(bb) = to_visit.pop_front() {
+            if mir[bb].is_cleanup || is_begin(bb) || region.contains(&bb) {
+                continue;
+            }
+            region.push(bb);
+            if !is_end(bb) {
+                to_visit.extend(real_edges.successors(bb).iter().cloned());
+            }
+        }
+
+        // The blocks of the region from which its end is reachable.
+        let mut exiting: HashSet<BasicBlock> = HashSet::new();
+        let mut to_visit: Vec<BasicBlock> =
+            region.iter().cloned().filter(|&bb| is_end(bb)).collect();
+        while let Some(bb) = to_visit.pop() {
+            if region.contains(&bb) && exiting.insert(bb) {
+                to_visit.extend(real_edges.predecessors(bb).iter().cloned());
+            }
+        }
+
+        // The only edge that may leave the region is the one after the end marker, and
+        // every block that is not unreachable must lead to the end marker.
+        let escape = region.iter().cloned().find(|&bb| {
+            let leaves_region = !is_end(bb) && real_edges.successors(bb).iter().any(|succ| {
+                !mir[*succ].is_cleanup && !region.contains(succ)
+            });
+            let diverges = !exiting.contains(&bb)
+                && !matches!(mir[bb].terminator().kind, TerminatorKind::Unreachable);
+            leaves_region || diverges
+        });
+        ghost_escapes.extend(escape);
+        ghost_basic_blocks.extend(region);
+    }
+    debug!("ghost basic blocks: {:#?}", ghost_basic_blocks);
+    debug!("ghost escapes: {:#?}", ghost_escapes);
+    (ghost_basic_blocks, ghost_escapes)
+}
+
 fn is_spec_closure(def_id: def_id::DefId, tcx: &TyCtxt) -> bool {
     crate::utils::has_spec_only_attr(tcx.get_attrs(def_id))
 }
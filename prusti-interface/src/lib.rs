@@ -36,6 +36,7 @@ extern crate rustc_trait_selection;
 extern crate lazy_static;
 
 pub mod data;
+pub mod diagnostics;
 pub mod environment;
 pub mod specs;
 pub mod utils;
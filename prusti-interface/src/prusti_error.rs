@@ -6,6 +6,8 @@
 
 use rustc_span::{Span, MultiSpan};
 use crate::environment::Environment;
+use crate::diagnostics::{self, Diagnostic, DiagnosticLevel, DiagnosticNote, DiagnosticSpan};
+use serde::Serialize;
 use prusti_common::config;
 use ::log::warn;
 
@@ -21,21 +23,49 @@ use ::log::warn;
 #[derive(Clone, Debug)]
 pub struct PrustiError {
     is_error: bool,
+    kind: PrustiErrorKind,
+    /// The kind of Viper check that failed, for errors reported by the verifier.
+    context: Option<String>,
     message: String,
     span: MultiSpan,
+    failing_assertion: Option<MultiSpan>,
     help: Option<String>,
-    note: Option<(String, MultiSpan)>,
+    notes: Vec<(String, MultiSpan)>,
+}
+
+/// The origin of a `PrustiError`, as reported in the machine-readable diagnostics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrustiErrorKind {
+    Verification,
+    Unsupported,
+    Incorrect,
+    Internal,
+}
+
+impl PrustiErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PrustiErrorKind::Verification => "verification",
+            PrustiErrorKind::Unsupported => "unsupported",
+            PrustiErrorKind::Incorrect => "incorrect",
+            PrustiErrorKind::Internal => "internal",
+        }
+    }
 }
 
 impl PrustiError {
     /// Private constructor. Use one of the following methods.
-    fn new(message: String, span: MultiSpan) -> Self {
+    fn new(kind: PrustiErrorKind, message: String, span: MultiSpan) -> Self {
         PrustiError {
             is_error: true,
+            kind,
+            context: None,
             message,
             span,
+            failing_assertion: None,
             help: None,
-            note: None,
+            notes: vec![],
         }
     }
 
@@ -43,6 +73,7 @@ impl PrustiError {
     pub fn verification<S: ToString>(message: S, span: MultiSpan) -> Self {
         check_message(message.to_string());
         PrustiError::new(
+            PrustiErrorKind::Verification,
             format!("[Prusti: verification error] {}", message.to_string()),
             span
         )
@@ -52,6 +83,7 @@ impl PrustiError {
     pub fn unsupported<S: ToString>(message: S, span: MultiSpan) -> Self {
         check_message(message.to_string());
         let mut error = PrustiError::new(
+            PrustiErrorKind::Unsupported,
             format!("[Prusti: unsupported feature] {}", message.to_string()),
             span
         );
@@ -65,6 +97,7 @@ impl PrustiError {
     pub fn incorrect<S: ToString>(message: S, span: MultiSpan) -> Self {
         check_message(message.to_string());
         PrustiError::new(
+            PrustiErrorKind::Incorrect,
             format!("[Prusti: invalid specification] {}", message.to_string()),
             span
         )
@@ -74,6 +107,7 @@ impl PrustiError {
     pub fn internal<S: ToString>(message: S, span: MultiSpan) -> Self {
         check_message(message.to_string());
         PrustiError::new(
+            PrustiErrorKind::Internal,
             format!("[Prusti internal error] {}", message.to_string()),
             span
         )
@@ -88,31 +122,52 @@ impl PrustiError {
         self.is_error
     }
 
+    pub fn kind(&self) -> PrustiErrorKind {
+        self.kind
+    }
+
+    /// Set the kind of Viper check whose failure caused this error
+    pub fn set_context<S: ToString>(mut self, context: S) -> Self {
+        self.context = Some(context.to_string());
+        self
+    }
+
     pub fn set_help<S: ToString>(mut self, message: S) -> Self {
         self.help = Some(message.to_string());
         self
     }
 
     pub fn set_note<S: ToString>(mut self, note: S, note_span: Span) -> Self {
-        self.note = Some((note.to_string(), MultiSpan::from_span(note_span)));
+        self.notes.push((note.to_string(), MultiSpan::from_span(note_span)));
+        self
+    }
+
+    /// Attach the values of a counterexample to the error span, one note per variable.
+    pub fn set_counterexample(mut self, entries: Vec<(String, String)>) -> Self {
+        for (variable, value) in entries {
+            self.notes.push((format!("counterexample: {} = {}", variable, value), self.span.clone()));
+        }
         self
     }
 
     /// Report the encoding error using the compiler's interface
     pub fn emit(self, env: &Environment) {
+        if diagnostics::is_enabled() {
+            diagnostics::record(self.to_diagnostic(env));
+        }
         if self.is_error {
-            env.span_err_with_help_and_note(
+            env.span_err_with_help_and_notes(
                 self.span,
                 &self.message,
                 &self.help,
-                &self.note,
+                &self.notes,
             );
         } else {
-            env.span_warn_with_help_and_note(
+            env.span_warn_with_help_and_notes(
                 self.span,
                 &self.message,
                 &self.help,
-                &self.note,
+                &self.notes,
             );
         }
     }
@@ -122,7 +177,8 @@ impl PrustiError {
     /// Note: this is a noop if `opt_span` is None
     pub fn set_failing_assertion(mut self, opt_span: Option<&MultiSpan>) -> Self {
         if let Some(span) = opt_span {
-            self.note = Some(("the failing assertion is here".to_string(), span.clone()));
+            self.notes.push(("the failing assertion is here".to_string(), span.clone()));
+            self.failing_assertion = Some(span.clone());
         }
         self
     }
@@ -132,11 +188,36 @@ impl PrustiError {
     /// Note: this is a noop if `opt_span` is None
     pub fn push_primary_span(mut self, opt_span: Option<&MultiSpan>) -> Self {
         if let Some(span) = opt_span {
-            self.note = Some(("the error originates here".to_string(), self.span));
+            self.notes.push(("the error originates here".to_string(), self.span));
             self.span = span.clone();
         }
         self
     }
+
+    fn to_diagnostic(&self, env: &Environment) -> Diagnostic {
+        let source_map = env.tcx().sess.source_map();
+        Diagnostic {
+            level: if self.is_error {
+                DiagnosticLevel::Error
+            } else {
+                DiagnosticLevel::Warning
+            },
+            kind: self.kind,
+            context: self.context.clone(),
+            message: self.message.clone(),
+            spans: DiagnosticSpan::from_multispan(source_map, &self.span),
+            failing_assertion: self.failing_assertion.iter()
+                .flat_map(|span| DiagnosticSpan::from_multispan(source_map, span))
+                .collect(),
+            help: self.help.clone(),
+            notes: self.notes.iter()
+                .map(|(message, span)| DiagnosticNote {
+                    message: message.clone(),
+                    spans: DiagnosticSpan::from_multispan(source_map, span),
+                })
+                .collect(),
+        }
+    }
 }
 
 fn check_message(message: String) {
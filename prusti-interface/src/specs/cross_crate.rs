@@ -0,0 +1,211 @@
+//! Export and import of specifications across crates.
+//!
+//! When Prusti compiles a library, the specifications of its functions, methods
+//! and types are written to a `.specs` file next to the metadata of the crate
+//! (e.g. `libfoo-<hash>.specs` next to `libfoo-<hash>.rlib`). When a crate that
+//! depends on the library is verified, the file is loaded, so that the calls into
+//! the library use the contracts written by its author instead of having to be
+//! re-declared with `#[extern_spec]`.
+//!
+//! Assertions are stored in the same JSON format used by the `prusti::assertion`
+//! attributes. Items are identified by their `DefIndex`, which is the same in a
+//! crate and in its metadata, and the closures of the specifications are encoded
+//! from the MIR stored in the metadata.
+
+use super::{
+    build_procedure_spec, build_struct_spec, reconstruct_typed_assertion, typed,
+    ProcedureSpecRef, SpecCollector,
+};
+use crate::environment::Environment;
+use crate::utils::has_extern_spec_attr;
+use log::debug;
+use prusti_specs::specifications::common::{SpecIdRef, SpecificationId};
+use prusti_specs::specifications::json::Assertion as JsonAssertion;
+use rustc_hir::def_id::{CrateNum, DefId, DefIndex, LOCAL_CRATE};
+use rustc_middle::ty::TyCtxt;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// The extension of the file that contains the specifications of a crate.
+pub const SPECS_FILE_EXTENSION: &str = "specs";
+
+/// The specifications of a crate that can be used by other crates.
+#[derive(Serialize, Deserialize)]
+pub struct CrateSpecs {
+    /// The hash of the crate, used to ignore out-of-date files.
+    crate_hash: u64,
+    /// The specifications of procedures, keyed by the index of the procedure.
+    procedures: Vec<(u32, ProcedureSpecRef)>,
+    /// The type invariants, keyed by the index of the type.
+    type_invariants: Vec<(u32, Vec<SpecificationId>)>,
+    /// The assertions referred to by `procedures` and `type_invariants`.
+    assertions: Vec<(SpecificationId, JsonAssertion)>,
+    /// The indices of the closures that encode the expressions of `assertions`,
+    /// keyed by their `prusti::expr_id`.
+    expressions: HashMap<String, u32>,
+}
+
+impl<'tcx> SpecCollector<'tcx> {
+    /// Collects the specifications of the crate that are visible to other crates.
+    /// Loop invariants, ghost statements, specifications of closures and external
+    /// specifications only concern the crate itself, so they are not exported.
+    pub fn export_specs(&self) -> CrateSpecs {
+        let tcx = self.tcx;
+        let mut spec_ids = HashSet::new();
+
+        let mut procedures = vec![];
+        for (local_id, refs) in self.procedure_specs.iter() {
+            let def_id = local_id.to_def_id();
+            if tcx.is_closure(def_id) || has_extern_spec_attr(tcx.get_attrs(def_id)) {
+                continue;
+            }
+            spec_ids.extend(refs.spec_id_refs.iter().flat_map(referenced_spec_ids));
+            procedures.push((local_id.local_def_index.as_u32(), refs.clone()));
+        }
+        procedures.sort_by_key(|(index, _)| *index);
+
+        let mut type_invariants = vec![];
+        for (local_id, invariant_ids) in self.type_invariants.iter() {
+            spec_ids.extend(invariant_ids.iter().cloned());
+            type_invariants.push((local_id.local_def_index.as_u32(), invariant_ids.clone()));
+        }
+        type_invariants.sort_by_key(|(index, _)| *index);
+
+        let mut assertions: Vec<_> = self.spec_items
+            .iter()
+            .filter(|spec_item| spec_ids.contains(&spec_item.spec_id))
+            .map(|spec_item| (spec_item.spec_id, spec_item.specification.clone()))
+            .collect();
+        assertions.sort_by_key(|(spec_id, _)| *spec_id);
+
+        let exported_spec_ids: HashSet<String> = spec_ids
+            .iter()
+            .map(|spec_id| spec_id.to_string())
+            .collect();
+        let expressions = self.typed_expressions
+            .iter()
+            .filter(|(expr_id, _)| {
+                // The identifiers of the expressions have the form `<spec_id>_<expr_id>`.
+                let spec_id = expr_id.split('_').next().unwrap();
+                exported_spec_ids.contains(spec_id)
+            })
+            .map(|(expr_id, def_id)| (expr_id.clone(), def_id.index.as_u32()))
+            .collect();
+
+        CrateSpecs {
+            crate_hash: tcx.crate_hash(LOCAL_CRATE).as_u64(),
+            procedures,
+            type_invariants,
+            assertions,
+            expressions,
+        }
+    }
+}
+
+impl CrateSpecs {
+    /// Returns true if the crate has no specification to export.
+    pub fn is_empty(&self) -> bool {
+        self.procedures.is_empty() && self.type_invariants.is_empty()
+    }
+
+    /// Writes the specifications to the file at `path`.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let file = io::BufWriter::new(fs::File::create(path)?);
+        serde_json::to_writer(file, self)?;
+        Ok(())
+    }
+
+    /// Reads the specifications from the file at `path`.
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let file = io::BufReader::new(fs::File::open(path)?);
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    /// Adds the specifications, which come from the crate `krate`, to `def_spec`.
+    fn import_into<'tcx>(
+        self,
+        tcx: TyCtxt<'tcx>,
+        krate: CrateNum,
+        def_spec: &mut typed::DefSpecificationMap<'tcx>,
+    ) {
+        let def_id = |index: u32| DefId { krate, index: DefIndex::from_u32(index) };
+        let typed_expressions: HashMap<String, DefId> = self.expressions
+            .into_iter()
+            .map(|(expr_id, index)| (expr_id, def_id(index)))
+            .collect();
+        let typed_specs: typed::SpecificationMap<'tcx> = self.assertions
+            .into_iter()
+            .map(|(spec_id, assertion)| {
+                (spec_id, reconstruct_typed_assertion(assertion, &typed_expressions, tcx))
+            })
+            .collect();
+        for (index, refs) in self.procedures.iter() {
+            def_spec.imported_specs.insert(
+                def_id(*index),
+                typed::SpecificationSet::Procedure(build_procedure_spec(refs, &typed_specs)),
+            );
+        }
+        for (index, spec_ids) in self.type_invariants.iter() {
+            def_spec.imported_specs.insert(
+                def_id(*index),
+                typed::SpecificationSet::Struct(build_struct_spec(spec_ids, &typed_specs)),
+            );
+        }
+    }
+}
+
+/// Loads the specifications of the dependencies of the crate that have been
+/// compiled by Prusti.
+pub fn import_specs<'tcx>(
+    env: &Environment<'tcx>,
+    def_spec: &mut typed::DefSpecificationMap<'tcx>,
+) {
+    let tcx = env.tcx();
+    for &krate in tcx.crates(()).iter() {
+        let path = if let Some(path) = find_specs_file(tcx, krate) {
+            path
+        } else {
+            continue;
+        };
+        let crate_name = tcx.crate_name(krate);
+        match CrateSpecs::read(&path) {
+            Ok(crate_specs) => {
+                if crate_specs.crate_hash != tcx.crate_hash(krate).as_u64() {
+                    debug!("Ignoring the out-of-date specifications of {} in {:?}", crate_name, path);
+                    continue;
+                }
+                debug!("Importing the specifications of {} from {:?}", crate_name, path);
+                crate_specs.import_into(tcx, krate, def_spec);
+            }
+            Err(error) => {
+                tcx.sess.warn(&format!(
+                    "could not load the Prusti specifications of crate `{}` from {}: {}",
+                    crate_name,
+                    path.display(),
+                    error,
+                ));
+            }
+        }
+    }
+}
+
+/// Returns the path of the specifications file next to the metadata of `krate`,
+/// if it exists.
+fn find_specs_file(tcx: TyCtxt, krate: CrateNum) -> Option<PathBuf> {
+    tcx.used_crate_source(krate)
+        .paths()
+        .map(|path| path.with_extension(SPECS_FILE_EXTENSION))
+        .find(|path| path.exists())
+}
+
+fn referenced_spec_ids(spec_id_ref: &SpecIdRef) -> Vec<SpecificationId> {
+    match *spec_id_ref {
+        SpecIdRef::Precondition(spec_id)
+        | SpecIdRef::Postcondition(spec_id)
+        | SpecIdRef::Predicate(spec_id)
+        | SpecIdRef::Termination(spec_id) => vec![spec_id],
+        SpecIdRef::Pledge { lhs, rhs } => lhs.into_iter().chain(Some(rhs)).collect(),
+    }
+}
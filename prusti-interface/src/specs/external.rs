@@ -1,13 +1,17 @@
 use rustc_hir::intravisit::{self, Visitor};
 use rustc_hir::def_id::DefId;
 use rustc_middle::hir::map::Map;
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::{self, TyCtxt};
 use rustc_span::{Span, MultiSpan};
 
 use std::collections::HashMap;
 use crate::environment::Environment;
 use crate::PrustiError;
 
+/// The name of the module in which the driver adds the specifications of the
+/// standard library that are bundled with Prusti.
+pub const STD_SPECS_MODULE: &str = "prusti_std_specs";
+
 /// This struct is used to build a mapping of external functions to their
 /// Prusti specifications (see `extern_fn_map`).
 pub struct ExternSpecResolver<'tcx> {
@@ -39,7 +43,8 @@ impl<'tcx> ExternSpecResolver<'tcx> {
     ///
     /// In case of duplicates, the function is added to `spec_duplicates`, and
     /// will later (in `check_duplicates`) be reported as an error. Otherwise,
-    /// the function is added to `extern_fn_map`.
+    /// the function is added to `extern_fn_map`. A specification written by the
+    /// user silently replaces a bundled specification of the standard library.
     pub fn add_extern_fn(
         &mut self,
         fn_kind: intravisit::FnKind<'tcx>,
@@ -56,6 +61,13 @@ impl<'tcx> ExternSpecResolver<'tcx> {
         let current_def_id = self.tcx.hir().local_def_id(id).to_def_id();
         if let Some((def_id, impl_ty, span)) = visitor.spec_found {
             match self.extern_fn_map.get(&def_id) {
+                Some((existing_impl_ty, existing_spec))
+                    if existing_impl_ty == &impl_ty
+                        && self.is_std_spec(*existing_spec) != self.is_std_spec(current_def_id) => {
+                    if self.is_std_spec(*existing_spec) {
+                        self.extern_fn_map.insert(def_id, (impl_ty, current_def_id));
+                    }
+                }
                 Some((existing_impl_ty, _)) if existing_impl_ty == &impl_ty => {
                     match self.spec_duplicates.get_mut(&def_id) {
                         Some(dups) => {
@@ -74,6 +86,12 @@ impl<'tcx> ExternSpecResolver<'tcx> {
         }
     }
 
+    /// Checks whether the specification function is one of the bundled
+    /// specifications of the standard library.
+    fn is_std_spec(&self, spec_def_id: DefId) -> bool {
+        self.tcx.def_path_str(spec_def_id).starts_with(&format!("{}::", STD_SPECS_MODULE))
+    }
+
     /// Report errors for duplicate specifications found during specification
     /// collection.
     pub fn check_duplicates(&self, env: &Environment<'tcx>) {
@@ -114,6 +132,24 @@ fn get_impl_type<'tcx>(qself: &rustc_hir::QPath<'tcx>) -> Option<DefId> {
     return None;
 }
 
+impl<'tcx> ExternSpecVisitor<'tcx> {
+    /// Resolves a call of a trait method on a concrete type to the method of
+    /// the implementation, so that the specifications of different
+    /// implementations of the same trait method do not clash.
+    fn resolve_trait_method(&self, callee_expr: &rustc_hir::Expr<'tcx>, def_id: DefId) -> DefId {
+        if self.tcx.trait_of_item(def_id).is_none() {
+            return def_id;
+        }
+        let owner = callee_expr.hir_id.owner;
+        let substs = self.tcx.typeck(owner).node_substs(callee_expr.hir_id);
+        let param_env = self.tcx.param_env(owner.to_def_id());
+        match ty::Instance::resolve(self.tcx, param_env, def_id, substs) {
+            Ok(Some(instance)) => instance.def_id(),
+            _ => def_id,
+        }
+    }
+}
+
 impl<'tcx> Visitor<'tcx> for ExternSpecVisitor<'tcx> {
     type Map = Map<'tcx>;
 
@@ -130,6 +166,7 @@ impl<'tcx> Visitor<'tcx> for ExternSpecVisitor<'tcx> {
             if let rustc_hir::ExprKind::Path(ref qself) = callee_expr.kind {
                 let res = self.tcx.typeck(callee_expr.hir_id.owner).qpath_res(qself, callee_expr.hir_id);
                 if let rustc_hir::def::Res::Def(_, def_id) = res {
+                    let def_id = self.resolve_trait_method(callee_expr, def_id);
                     self.spec_found = Some((def_id, get_impl_type(qself), ex.span));
                     return;
                 }
@@ -14,16 +14,18 @@ use crate::utils::{
     has_spec_only_attr, has_extern_spec_attr, read_prusti_attr, read_prusti_attrs, has_prusti_attr
 };
 use log::debug;
+use serde::{Deserialize, Serialize};
 
 pub mod external;
 pub mod typed;
 pub mod checker;
+pub mod cross_crate;
 
 use typed::StructuralToTyped;
 use typed::SpecIdRef;
 use std::fmt;
 use crate::specs::external::ExternSpecResolver;
-use prusti_specs::specifications::common::SpecificationId;
+use prusti_specs::specifications::common::{GhostStatementKind, SpecificationId};
 
 struct SpecItem {
     spec_id: typed::SpecificationId,
@@ -40,6 +42,7 @@ impl fmt::Debug for SpecItem {
     }
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 struct ProcedureSpecRef {
     spec_id_refs: Vec<prusti_specs::specifications::common::SpecIdRef>,
     pure: bool,
@@ -57,7 +60,7 @@ pub struct SpecCollector<'tcx> {
     /// Collected assertions before deserialisation.
     spec_items: Vec<SpecItem>,
 
-    typed_expressions: HashMap<String, LocalDefId>,
+    typed_expressions: HashMap<String, DefId>,
 
     /// Collected, deserialised assertions, keyed by their specification id.
     typed_specs: typed::SpecificationMap<'tcx>,
@@ -65,6 +68,9 @@ pub struct SpecCollector<'tcx> {
     /// Resolved specifications.
     procedure_specs: HashMap<LocalDefId, ProcedureSpecRef>,
     loop_specs: HashMap<LocalDefId, Vec<SpecificationId>>,
+    loop_variants: HashMap<LocalDefId, SpecificationId>,
+    ghost_statements: HashMap<LocalDefId, (GhostStatementKind, SpecificationId)>,
+    type_invariants: HashMap<LocalDefId, Vec<SpecificationId>>,
 }
 
 impl<'tcx> SpecCollector<'tcx> {
@@ -75,6 +81,9 @@ impl<'tcx> SpecCollector<'tcx> {
             typed_specs: HashMap::new(),
             procedure_specs: HashMap::new(),
             loop_specs: HashMap::new(),
+            loop_variants: HashMap::new(),
+            ghost_statements: HashMap::new(),
+            type_invariants: HashMap::new(),
             typed_expressions: HashMap::new(),
             extern_resolver: ExternSpecResolver::new(tcx),
         }
@@ -102,7 +111,9 @@ impl<'tcx> SpecCollector<'tcx> {
         self.determine_procedure_specs(&mut def_spec);
         self.determine_extern_specs(&mut def_spec, env);
         self.determine_loop_specs(&mut def_spec);
+        self.determine_ghost_statements(&mut def_spec);
         self.determine_struct_specs(&mut def_spec);
+        cross_crate::import_specs(env, &mut def_spec);
         def_spec
     }
 
@@ -127,40 +138,9 @@ impl<'tcx> SpecCollector<'tcx> {
 
     fn determine_procedure_specs(&self, def_spec: &mut typed::DefSpecificationMap<'tcx>) {
         for (local_id, refs) in self.procedure_specs.iter() {
-            let mut pres = Vec::new();
-            let mut posts = Vec::new();
-            let mut pledges = Vec::new();
-            let mut predicate_body = None;
-            for spec_id_ref in &refs.spec_id_refs {
-                match spec_id_ref {
-                    SpecIdRef::Precondition(spec_id) => {
-                        pres.push(self.typed_specs.get(&spec_id).unwrap().clone());
-                    }
-                    SpecIdRef::Postcondition(spec_id) => {
-                        posts.push(self.typed_specs.get(&spec_id).unwrap().clone());
-                    }
-                    SpecIdRef::Pledge{ lhs, rhs } => {
-                        pledges.push(typed::Pledge {
-                            reference: None,    // FIXME: Currently only `result` is supported.
-                            lhs: lhs.map(|spec_id| self.typed_specs.get(&spec_id).unwrap().clone()),
-                            rhs: self.typed_specs.get(&rhs).unwrap().clone(),
-                        })
-                    }
-                    SpecIdRef::Predicate(spec_id) => {
-                        predicate_body = Some(self.typed_specs.get(&spec_id).unwrap().clone());
-                    }
-                }
-            }
             def_spec.specs.insert(
                 *local_id,
-                typed::SpecificationSet::Procedure(typed::ProcedureSpecification {
-                    pres,
-                    posts,
-                    pledges,
-                    predicate_body,
-                    pure: refs.pure,
-                    trusted: refs.trusted,
-                })
+                typed::SpecificationSet::Procedure(build_procedure_spec(refs, &self.typed_specs))
             );
         }
     }
@@ -171,13 +151,95 @@ impl<'tcx> SpecCollector<'tcx> {
                 .map(|spec_id| self.typed_specs.get(&spec_id).unwrap().clone())
                 .collect();
             def_spec.specs.insert(*local_id, typed::SpecificationSet::Loop(typed::LoopSpecification {
-                invariant: specs
+                invariant: specs,
+                variant: None,
+            }));
+        }
+        for (local_id, spec_id) in self.loop_variants.iter() {
+            let variant = self.typed_specs.get(&spec_id).unwrap().clone();
+            def_spec.specs.insert(*local_id, typed::SpecificationSet::Loop(typed::LoopSpecification {
+                invariant: vec![],
+                variant: Some(variant),
             }));
         }
     }
 
-    // TODO: struct specs
-    fn determine_struct_specs(&self, _def_spec: &mut typed::DefSpecificationMap<'tcx>) {}
+    fn determine_ghost_statements(&self, def_spec: &mut typed::DefSpecificationMap<'tcx>) {
+        for (local_id, (kind, spec_id)) in self.ghost_statements.iter() {
+            let assertion = self.typed_specs.get(&spec_id).unwrap().clone();
+            def_spec.specs.insert(
+                *local_id,
+                typed::SpecificationSet::GhostStatement(typed::GhostStatement {
+                    kind: *kind,
+                    assertion,
+                })
+            );
+        }
+    }
+
+    fn determine_struct_specs(&self, def_spec: &mut typed::DefSpecificationMap<'tcx>) {
+        for (local_id, spec_ids) in self.type_invariants.iter() {
+            def_spec.specs.insert(
+                *local_id,
+                typed::SpecificationSet::Struct(build_struct_spec(spec_ids, &self.typed_specs))
+            );
+        }
+    }
+}
+
+fn build_procedure_spec<'tcx>(
+    refs: &ProcedureSpecRef,
+    typed_specs: &typed::SpecificationMap<'tcx>,
+) -> typed::ProcedureSpecification<'tcx> {
+    let mut pres = Vec::new();
+    let mut posts = Vec::new();
+    let mut pledges = Vec::new();
+    let mut predicate_body = None;
+    let mut decreases = None;
+    for spec_id_ref in &refs.spec_id_refs {
+        match spec_id_ref {
+            SpecIdRef::Precondition(spec_id) => {
+                pres.push(typed_specs.get(&spec_id).unwrap().clone());
+            }
+            SpecIdRef::Postcondition(spec_id) => {
+                posts.push(typed_specs.get(&spec_id).unwrap().clone());
+            }
+            SpecIdRef::Pledge{ lhs, rhs } => {
+                pledges.push(typed::Pledge {
+                    reference: None,    // FIXME: Currently only `result` is supported.
+                    lhs: lhs.map(|spec_id| typed_specs.get(&spec_id).unwrap().clone()),
+                    rhs: typed_specs.get(&rhs).unwrap().clone(),
+                })
+            }
+            SpecIdRef::Predicate(spec_id) => {
+                predicate_body = Some(typed_specs.get(&spec_id).unwrap().clone());
+            }
+            SpecIdRef::Termination(spec_id) => {
+                decreases = Some(typed_specs.get(&spec_id).unwrap().clone());
+            }
+        }
+    }
+    typed::ProcedureSpecification {
+        pres,
+        posts,
+        pledges,
+        predicate_body,
+        decreases,
+        pure: refs.pure,
+        trusted: refs.trusted,
+    }
+}
+
+fn build_struct_spec<'tcx>(
+    spec_ids: &[SpecificationId],
+    typed_specs: &typed::SpecificationMap<'tcx>,
+) -> Vec<typed::Specification<'tcx>> {
+    spec_ids.iter()
+        .map(|spec_id| typed::Specification {
+            typ: SpecType::Invariant,
+            assertion: typed_specs.get(&spec_id).unwrap().clone(),
+        })
+        .collect()
 }
 
 fn get_procedure_spec_ids(def_id: DefId, attrs: &[ast::Attribute]) -> Option<ProcedureSpecRef> {
@@ -220,6 +282,11 @@ fn get_procedure_spec_ids(def_id: DefId, attrs: &[ast::Attribute]) -> Option<Pro
             |raw_spec_id| SpecIdRef::Predicate(parse_spec_id(raw_spec_id))
         )
     );
+    spec_id_refs.extend(
+        read_prusti_attr("decreases_spec_id_ref", attrs).map(
+            |raw_spec_id| SpecIdRef::Termination(parse_spec_id(raw_spec_id))
+        )
+    );
     debug!("Function {:?} has specification ids {:?}", def_id, spec_id_refs);
 
     let pure = has_prusti_attr(attrs, "pure");
@@ -238,7 +305,7 @@ fn get_procedure_spec_ids(def_id: DefId, attrs: &[ast::Attribute]) -> Option<Pro
 
 fn reconstruct_typed_assertion<'tcx>(
     assertion: JsonAssertion,
-    typed_expressions: &HashMap<String, LocalDefId>,
+    typed_expressions: &HashMap<String, DefId>,
     tcx: TyCtxt<'tcx>
 ) -> typed::Assertion<'tcx> {
     assertion.to_typed(typed_expressions, tcx)
@@ -258,6 +325,27 @@ impl<'tcx> intravisit::Visitor<'tcx> for SpecCollector<'tcx> {
         intravisit::NestedVisitorMap::All(map)
     }
 
+    fn visit_item(
+        &mut self,
+        item: &'tcx rustc_hir::Item,
+    ) {
+        intravisit::walk_item(self, item);
+
+        // Collect type invariants
+        if let ItemKind::Struct(..) | ItemKind::Enum(..) = item.kind {
+            let attrs = self.tcx.hir().attrs(item.hir_id());
+            let spec_ids: Vec<SpecificationId> = read_prusti_attrs("inv_spec_id_ref", attrs)
+                .into_iter()
+                .map(|raw_spec_id| raw_spec_id.try_into().expect(
+                    &format!("cannot parse the spec_id attached to {:?}", item.def_id)
+                ))
+                .collect();
+            if !spec_ids.is_empty() {
+                self.type_invariants.insert(item.def_id, spec_ids);
+            }
+        }
+    }
+
     fn visit_trait_item(
         &mut self,
         ti: &'tcx rustc_hir::TraitItem,
@@ -301,7 +389,7 @@ impl<'tcx> intravisit::Visitor<'tcx> for SpecCollector<'tcx> {
 
         // Collect a typed expression
         if let Some(expr_id) = read_prusti_attr("expr_id", attrs) {
-            self.typed_expressions.insert(expr_id, local_id);
+            self.typed_expressions.insert(expr_id, def_id);
         }
 
         // Collect a specification id and its assertion
@@ -318,13 +406,18 @@ impl<'tcx> intravisit::Visitor<'tcx> for SpecCollector<'tcx> {
             // for postconditions and invariants.
             let spec_type = if has_prusti_attr(attrs, "loop_body_invariant_spec") {
                 SpecType::Invariant
+            } else if has_prusti_attr(attrs, "loop_body_variant_spec") {
+                SpecType::Termination
+            } else if has_prusti_attr(attrs, "ghost_statement") {
+                SpecType::GhostStatement
             } else {
                 let fn_name = match fn_kind {
                     intravisit::FnKind::ItemFn(ref ident, ..) |
                     intravisit::FnKind::Method(ref ident, ..) => ident.name.to_ident_string(),
                     intravisit::FnKind::Closure => unreachable!(
                         "a closure is annotated with prusti::spec_id but not with \
-                        prusti::loop_body_invariant_spec"
+                        prusti::loop_body_invariant_spec, prusti::loop_body_variant_spec \
+                        or prusti::ghost_statement"
                     ),
                 };
                 if fn_name.starts_with("prusti_pre_item_")
@@ -335,6 +428,10 @@ impl<'tcx> intravisit::Visitor<'tcx> for SpecCollector<'tcx> {
                     SpecType::Postcondition
                 } else if fn_name.starts_with("prusti_pred_item_") {
                     SpecType::Predicate
+                } else if fn_name.starts_with("prusti_term_item_") {
+                    SpecType::Termination
+                } else if fn_name.starts_with("prusti_inv_item_") {
+                    SpecType::Invariant
                 } else {
                     unreachable!()
                 }
@@ -344,12 +441,24 @@ impl<'tcx> intravisit::Visitor<'tcx> for SpecCollector<'tcx> {
             self.spec_items.push(spec_item);
 
             // Collect loop invariant
-            if spec_type == SpecType::Invariant {
+            if has_prusti_attr(attrs, "loop_body_invariant_spec") {
                 self.loop_specs
                     .entry(local_id)
                     .or_insert(vec![])
                     .push(spec_id);
             }
+
+            // Collect loop variant
+            if has_prusti_attr(attrs, "loop_body_variant_spec") {
+                self.loop_variants.insert(local_id, spec_id);
+            }
+
+            // Collect ghost statement
+            if let Some(raw_kind) = read_prusti_attr("ghost_statement", attrs) {
+                let kind: GhostStatementKind = raw_kind.as_str().try_into()
+                    .expect("failed conversion to GhostStatementKind");
+                self.ghost_statements.insert(local_id, (kind, spec_id));
+            }
         }
     }
 
@@ -6,41 +6,48 @@ use rustc_middle::{mir, ty::{self, TyCtxt}};
 use rustc_span::Span;
 use std::collections::HashMap;
 
-pub use common::{ExpressionId, SpecType, SpecificationId, SpecIdRef};
+pub use common::{ExpressionId, GhostStatementKind, SpecType, SpecificationId, SpecIdRef};
 use crate::data::ProcedureDefId;
+use crate::environment::MirBody;
 
 // FIXME: these comments are not terribly useful and are a copy of the untyped ones...
 /// A specification that has no types associated with it.
-pub type Specification<'tcx> = common::Specification<ExpressionId, LocalDefId, (mir::Local, ty::Ty<'tcx>)>;
+pub type Specification<'tcx> = common::Specification<ExpressionId, DefId, (mir::Local, ty::Ty<'tcx>)>;
 /// A set of untyped specifications associated with a single element.
-pub type SpecificationSet<'tcx> = common::SpecificationSet<ExpressionId, LocalDefId, (mir::Local, ty::Ty<'tcx>)>;
+pub type SpecificationSet<'tcx> = common::SpecificationSet<ExpressionId, DefId, (mir::Local, ty::Ty<'tcx>)>;
 /// A set of untyped specifications associated with a loop.
-pub type LoopSpecification<'tcx> = common::LoopSpecification<ExpressionId, LocalDefId, (mir::Local, ty::Ty<'tcx>)>;
+pub type LoopSpecification<'tcx> = common::LoopSpecification<ExpressionId, DefId, (mir::Local, ty::Ty<'tcx>)>;
+/// A ghost statement written inside a procedure body.
+pub type GhostStatement<'tcx> = common::GhostStatement<ExpressionId, DefId, (mir::Local, ty::Ty<'tcx>)>;
 /// A set of untyped specifications associated with a procedure.
-pub type ProcedureSpecification<'tcx> = common::ProcedureSpecification<ExpressionId, LocalDefId, (mir::Local, ty::Ty<'tcx>)>;
+pub type ProcedureSpecification<'tcx> = common::ProcedureSpecification<ExpressionId, DefId, (mir::Local, ty::Ty<'tcx>)>;
 /// A map of untyped specifications for a specific crate.
 pub type SpecificationMap<'tcx> = HashMap<common::SpecificationId, Assertion<'tcx>>;
 /// An assertion that has no types associated with it.
-pub type Assertion<'tcx> = common::Assertion<ExpressionId, LocalDefId, (mir::Local, ty::Ty<'tcx>)>;
+pub type Assertion<'tcx> = common::Assertion<ExpressionId, DefId, (mir::Local, ty::Ty<'tcx>)>;
 /// An assertion kind that has no types associated with it.
-pub type AssertionKind<'tcx> = common::AssertionKind<ExpressionId, LocalDefId, (mir::Local, ty::Ty<'tcx>)>;
+pub type AssertionKind<'tcx> = common::AssertionKind<ExpressionId, DefId, (mir::Local, ty::Ty<'tcx>)>;
 /// An expression that has no types associated with it.
-pub type Expression = common::Expression<ExpressionId, LocalDefId>;
+pub type Expression = common::Expression<ExpressionId, DefId>;
 /// A trigger set that has no types associated with it.
-pub type TriggerSet = common::TriggerSet<ExpressionId, LocalDefId>;
+pub type TriggerSet = common::TriggerSet<ExpressionId, DefId>;
 /// Quantifier variables that have no types associated with it.
 pub type QuantifierVars<'tcx> = common::QuantifierVars<ExpressionId, (mir::Local, ty::Ty<'tcx>)>;
 /// Specification entailment variables that have no types associated.
 pub type SpecEntailmentVars<'tcx> = common::SpecEntailmentVars<ExpressionId, (mir::Local, ty::Ty<'tcx>)>;
 /// A trigger that has no types associated with it.
-pub type Trigger = common::Trigger<ExpressionId, LocalDefId>;
+pub type Trigger = common::Trigger<ExpressionId, DefId>;
 /// A pledge in the postcondition.
-pub type Pledge<'tcx> = common::Pledge<ExpressionId, LocalDefId, (mir::Local, ty::Ty<'tcx>)>;
+pub type Pledge<'tcx> = common::Pledge<ExpressionId, DefId, (mir::Local, ty::Ty<'tcx>)>;
 
-/// A map of specifications keyed by crate-local DefIds.
+/// A map of specifications keyed by crate-local DefIds, together with the
+/// specifications imported from the dependencies of the crate.
 pub struct DefSpecificationMap<'tcx> {
     pub specs: HashMap<LocalDefId, SpecificationSet<'tcx>>,
     pub extern_specs: HashMap<DefId, LocalDefId>,
+    /// Specifications written in other crates, keyed by the DefId of the
+    /// specified item (see `specs::cross_crate`).
+    pub imported_specs: HashMap<DefId, SpecificationSet<'tcx>>,
 }
 
 impl<'tcx> DefSpecificationMap<'tcx> {
@@ -48,13 +55,16 @@ impl<'tcx> DefSpecificationMap<'tcx> {
         Self {
             specs: HashMap::new(),
             extern_specs: HashMap::new(),
+            imported_specs: HashMap::new(),
         }
     }
     pub fn get(&self, def_id: &DefId) -> Option<&SpecificationSet<'tcx>> {
         let id = if let Some(spec_id) = self.extern_specs.get(def_id) {
             *spec_id
+        } else if let Some(local_id) = def_id.as_local() {
+            local_id
         } else {
-            def_id.as_local()?
+            return self.imported_specs.get(def_id);
         };
         self.specs.get(&id)
     }
@@ -140,22 +150,22 @@ impl<'tcx> Spanned<'tcx> for Assertion<'tcx> {
 }
 
 pub trait StructuralToTyped<'tcx, Target> {
-    fn to_typed(self, typed_expressions: &HashMap<String, LocalDefId>, tcx: TyCtxt<'tcx>) -> Target;
+    fn to_typed(self, typed_expressions: &HashMap<String, DefId>, tcx: TyCtxt<'tcx>) -> Target;
 }
 
 impl<'tcx> StructuralToTyped<'tcx, Expression> for json::Expression {
-    fn to_typed(self, typed_expressions: &HashMap<String, LocalDefId>, _tcx: TyCtxt<'tcx>) -> Expression {
-        let local_id = typed_expressions[&format!("{}_{}", self.spec_id, self.expr_id)];
+    fn to_typed(self, typed_expressions: &HashMap<String, DefId>, _tcx: TyCtxt<'tcx>) -> Expression {
+        let def_id = typed_expressions[&format!("{}_{}", self.spec_id, self.expr_id)];
         Expression {
             spec_id: self.spec_id,
             id: self.expr_id,
-            expr: local_id,
+            expr: def_id,
         }
     }
 }
 
 impl<'tcx> StructuralToTyped<'tcx, TriggerSet> for json::TriggerSet {
-    fn to_typed(self, typed_expressions: &HashMap<String, LocalDefId>, tcx: TyCtxt<'tcx>) -> TriggerSet {
+    fn to_typed(self, typed_expressions: &HashMap<String, DefId>, tcx: TyCtxt<'tcx>) -> TriggerSet {
         common::TriggerSet(
             self.0
                 .into_iter()
@@ -166,7 +176,7 @@ impl<'tcx> StructuralToTyped<'tcx, TriggerSet> for json::TriggerSet {
 }
 
 impl<'tcx> StructuralToTyped<'tcx, Trigger> for json::Trigger {
-    fn to_typed(self, typed_expressions: &HashMap<String, LocalDefId>, tcx: TyCtxt<'tcx>) -> Trigger {
+    fn to_typed(self, typed_expressions: &HashMap<String, DefId>, tcx: TyCtxt<'tcx>) -> Trigger {
         common::Trigger(
             self.0
                 .into_iter()
@@ -177,10 +187,9 @@ impl<'tcx> StructuralToTyped<'tcx, Trigger> for json::Trigger {
 }
 
 impl<'tcx> StructuralToTyped<'tcx, QuantifierVars<'tcx>> for json::QuantifierVars {
-    fn to_typed(self, typed_expressions: &HashMap<String, LocalDefId>, tcx: TyCtxt<'tcx>) -> QuantifierVars<'tcx> {
-        let local_id = typed_expressions[&format!("{}_{}", self.spec_id, self.expr_id)];
-        let (body, _) = tcx.mir_promoted(ty::WithOptConstParam::unknown(local_id));
-        let body = body.borrow();
+    fn to_typed(self, typed_expressions: &HashMap<String, DefId>, tcx: TyCtxt<'tcx>) -> QuantifierVars<'tcx> {
+        let def_id = typed_expressions[&format!("{}_{}", self.spec_id, self.expr_id)];
+        let body = MirBody::new(tcx, def_id);
 
         // the first argument to the node is the closure itself and the
         // following ones are the variables; therefore, we need to skip
@@ -205,13 +214,11 @@ impl<'tcx> StructuralToTyped<'tcx, QuantifierVars<'tcx>> for json::QuantifierVar
 }
 
 impl<'tcx> StructuralToTyped<'tcx, SpecEntailmentVars<'tcx>> for json::SpecEntailmentVars {
-    fn to_typed(self, typed_expressions: &HashMap<String, LocalDefId>, tcx: TyCtxt<'tcx>) -> SpecEntailmentVars<'tcx> {
-        let local_pre_id = typed_expressions[&format!("{}_{}", self.spec_id, self.pre_expr_id)];
-        let local_post_id = typed_expressions[&format!("{}_{}", self.spec_id, self.post_expr_id)];
-        let (pre_body, _) = tcx.mir_promoted(ty::WithOptConstParam::unknown(local_pre_id));
-        let (post_body, _) = tcx.mir_promoted(ty::WithOptConstParam::unknown(local_post_id));
-        let pre_body = pre_body.borrow();
-        let post_body = post_body.borrow();
+    fn to_typed(self, typed_expressions: &HashMap<String, DefId>, tcx: TyCtxt<'tcx>) -> SpecEntailmentVars<'tcx> {
+        let pre_def_id = typed_expressions[&format!("{}_{}", self.spec_id, self.pre_expr_id)];
+        let post_def_id = typed_expressions[&format!("{}_{}", self.spec_id, self.post_expr_id)];
+        let pre_body = MirBody::new(tcx, pre_def_id);
+        let post_body = MirBody::new(tcx, post_def_id);
 
         let pre_args: Vec<(mir::Local, ty::Ty)> = pre_body
             .args_iter()
@@ -245,7 +252,7 @@ impl<'tcx> StructuralToTyped<'tcx, SpecEntailmentVars<'tcx>> for json::SpecEntai
 }
 
 impl<'tcx> StructuralToTyped<'tcx, AssertionKind<'tcx>> for json::AssertionKind {
-    fn to_typed(self, typed_expressions: &HashMap<String, LocalDefId>, tcx: TyCtxt<'tcx>) -> AssertionKind<'tcx> {
+    fn to_typed(self, typed_expressions: &HashMap<String, DefId>, tcx: TyCtxt<'tcx>) -> AssertionKind<'tcx> {
         use json::AssertionKind::*;
         match self {
             Expr(expr) => AssertionKind::Expr(expr.to_typed(typed_expressions, tcx)),
@@ -283,7 +290,7 @@ impl<'tcx> StructuralToTyped<'tcx, AssertionKind<'tcx>> for json::AssertionKind
 }
 
 impl<'tcx> StructuralToTyped<'tcx, Assertion<'tcx>> for json::Assertion {
-    fn to_typed(self, typed_expressions: &HashMap<String, LocalDefId>, tcx: TyCtxt<'tcx>) -> Assertion<'tcx> {
+    fn to_typed(self, typed_expressions: &HashMap<String, DefId>, tcx: TyCtxt<'tcx>) -> Assertion<'tcx> {
         Assertion {
             kind: box self.kind.to_typed(typed_expressions, tcx),
         }
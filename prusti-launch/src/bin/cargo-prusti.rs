@@ -26,19 +26,53 @@ fn process<I>(args: I) -> Result<(), i32>
 
     // Remove the leading "prusti" argument when `cargo-prusti` is invocated
     // as `cargo prusti` (note the space)
-    let clean_args = args.skip_while(|x| x == "prusti");
+    let mut args = args.skip_while(|x| x == "prusti");
+
+    // Extract the flags that are handled by Prusti instead of Cargo
+    let mut clean_args = vec![];
+    let mut diagnostics_format = None;
+    let mut verify_only = vec![];
+    while let Some(arg) = args.next() {
+        if arg == "--diagnostics-format" {
+            diagnostics_format = Some(args.next().ok_or_else(|| {
+                eprintln!("error: the flag --diagnostics-format requires a value (json or sarif)");
+                1
+            })?);
+        } else if let Some(format) = arg.strip_prefix("--diagnostics-format=") {
+            diagnostics_format = Some(format.to_string());
+        } else if arg == "--verify-only" {
+            verify_only.push(args.next().ok_or_else(|| {
+                eprintln!("error: the flag --verify-only requires a path pattern");
+                1
+            })?);
+        } else if let Some(pattern) = arg.strip_prefix("--verify-only=") {
+            verify_only.push(pattern.to_string());
+        } else {
+            clean_args.push(arg);
+        }
+    }
 
     let cargo_path = std::env::var("CARGO_PATH").unwrap_or("cargo".to_string());
 
-    let exit_status = Command::new(cargo_path)
+    let mut cargo = Command::new(cargo_path);
+    cargo
         .arg("check")
         .args(clean_args)
         .env("RUST_TOOLCHAIN", get_rust_toolchain_channel())
         .env("PRUSTI_QUIET", "true")
         .env("PRUSTI_FULL_COMPILATION", "true")
-        .env("RUSTC_WRAPPER", prusti_rustc_path)
-        .status()
-        .expect("could not run cargo");
+        .env("RUSTC_WRAPPER", prusti_rustc_path);
+    if let Some(format) = diagnostics_format {
+        if format != "json" && format != "sarif" {
+            eprintln!("error: unknown diagnostics format {:?}; expected json or sarif", format);
+            return Err(1);
+        }
+        cargo.env("PRUSTI_DIAGNOSTICS_FORMAT", format);
+    }
+    if !verify_only.is_empty() {
+        cargo.env("PRUSTI_VERIFY_ONLY", verify_only.join(","));
+    }
+    let exit_status = cargo.status().expect("could not run cargo");
 
     if exit_status.success() {
         Ok(())
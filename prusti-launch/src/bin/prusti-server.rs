@@ -4,10 +4,17 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use std::{env, path::PathBuf, process::Command};
+use std::{
+    env,
+    fs,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
 use prusti_launch::{find_viper_home, find_z3_exe, sigint_handler};
 #[cfg(target_family = "unix")]
 use nix::unistd::{setpgid, Pid};
+use serde_json::{json, Value};
 
 fn main() {
     if let Err(code) = process(std::env::args().skip(1).collect()) {
@@ -22,6 +29,31 @@ fn process(args: Vec<String>) -> Result<(), i32> {
         .expect("failed to obtain the folder of the current executable")
         .to_path_buf();
 
+    let mut cmd = server_driver_command(current_executable_dir);
+
+    // Move process to group leader if it isn't. The only applicable error should be EPERM which
+    // can be thrown when the process is already the group leader. Thus, we ignore it.
+    #[cfg(target_family = "unix")]
+    let _ = setpgid(Pid::this(), Pid::this());
+    // Register the SIGINT handler; CTRL_C_EVENT or CTRL_BREAK_EVENT on Windows
+    ctrlc::set_handler(sigint_handler).expect("Error setting Ctrl-C handler");
+
+    if args.first().map(String::as_str) == Some("verify-viper") {
+        return verify_viper(&args[1..], cmd);
+    }
+
+    cmd.args(args);
+    let exit_status = cmd.status().expect("could not run prusti-server-driver");
+
+    if exit_status.success() {
+        Ok(())
+    } else {
+        Err(exit_status.code().unwrap_or(-1))
+    }
+}
+
+/// Build the command that runs `prusti-server-driver` with the JVM, Viper and Z3 it needs.
+fn server_driver_command(current_executable_dir: PathBuf) -> Command {
     let mut prusti_server_driver_path = current_executable_dir.join("prusti-server-driver");
     if cfg!(windows) {
         prusti_server_driver_path.set_extension("exe");
@@ -34,7 +66,6 @@ fn process(args: Vec<String>) -> Result<(), i32> {
     };
 
     let mut cmd = Command::new(&prusti_server_driver_path);
-    cmd.args(args);
 
     let libjvm_path = prusti_launch::find_libjvm(&java_home)
         .expect("Failed to find JVM library. Check JAVA_HOME");
@@ -64,18 +95,118 @@ fn process(args: Vec<String>) -> Result<(), i32> {
         }
     };
 
-    // Move process to group leader if it isn't. The only applicable error should be EPERM which
-    // can be thrown when the process is already the group leader. Thus, we ignore it.
-    #[cfg(target_family = "unix")]
-    let _ = setpgid(Pid::this(), Pid::this());
-    // Register the SIGINT handler; CTRL_C_EVENT or CTRL_BREAK_EVENT on Windows
-    ctrlc::set_handler(sigint_handler).expect("Error setting Ctrl-C handler");
+    cmd
+}
 
-    let exit_status = cmd.status().expect("could not run prusti-server-driver");
+/// Handle `prusti-server verify-viper <FILE> [--server <ADDRESS>]`: verify a Viper program,
+/// e.g. one dumped with `DUMP_VIPER_PROGRAM`, print the result and exit.
+///
+/// Without `--server`, a server is started on a free port just for this request.
+fn verify_viper(args: &[String], mut server_cmd: Command) -> Result<(), i32> {
+    let (file, server_address) = match args {
+        [file] => (file, None),
+        [file, flag, address] | [flag, address, file] if flag == "--server" || flag == "-s" => {
+            (file, Some(address.clone()))
+        }
+        _ => {
+            eprintln!("Usage: prusti-server verify-viper <FILE> [--server <ADDRESS>]");
+            return Err(2);
+        }
+    };
+    let path = Path::new(file);
+    let source = fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("Could not read {}: {}", path.display(), err));
+    let program_name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "program".to_string());
 
-    if exit_status.success() {
-        Ok(())
+    let mut server = None;
+    let server_address = match server_address {
+        Some(address) => address,
+        None => {
+            let mut child = server_cmd
+                .args(&["--port", "0"])
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("could not run prusti-server-driver");
+            let port = read_server_port(child.stdout.as_mut().unwrap());
+            server = Some(child);
+            format!("127.0.0.1:{}", port)
+        }
+    };
+
+    // The server answers with a `Result<viper::VerificationResult, _>`; the request omits the
+    // backend configuration, so the server uses its own.
+    let request = json!({ "source": source, "program_name": program_name });
+    let response = send_viper_source(&server_address, &request);
+    if let Some(mut server) = server {
+        let _ = server.kill();
+    }
+
+    let response =
+        response.unwrap_or_else(|err| panic!("Verification request to server failed: {}", err));
+    match response.get("Ok") {
+        Some(result) => print_verification_result(result),
+        None => panic!("Server panicked while processing request!"),
+    }
+}
+
+/// Wait for the server to report the port it is listening on.
+fn read_server_port<R: std::io::Read>(stdout: R) -> u16 {
+    BufReader::new(stdout)
+        .lines()
+        .map(|line| line.expect("could not read the output of prusti-server-driver"))
+        .find_map(|line| line.strip_prefix("port: ").and_then(|port| port.trim().parse().ok()))
+        .expect("prusti-server-driver did not report its port")
+}
+
+fn send_viper_source(server_address: &str, request: &Value) -> reqwest::Result<Value> {
+    let mut address = server_address.to_string();
+    if !address.starts_with("http") {
+        address = format!("http://{}", address);
+    }
+    let url = reqwest::Url::parse(&address)
+        .and_then(|url| url.join("json/verify-viper/"))
+        .unwrap_or_else(|err| panic!("Could not parse server address ({}) due to {:?}", address, err));
+    reqwest::Client::builder()
+        .timeout(None)
+        .build()?
+        .post(url)
+        .json(request)
+        .send()?
+        .error_for_status()?
+        .json()
+}
+
+/// Print a JSON-encoded `viper::VerificationResult`.
+fn print_verification_result(result: &Value) -> Result<(), i32> {
+    if result.get("Success").is_some() {
+        println!("Verification successful");
+        return Ok(());
+    }
+    if let Some(errors) = result.get("Failure").and_then(Value::as_array) {
+        for error in errors {
+            println!(
+                "[{}] {}",
+                error["full_id"].as_str().unwrap_or_default(),
+                error["message"].as_str().unwrap_or_default(),
+            );
+        }
+        println!("Verification failed with {} errors", errors.len());
+    } else if let Some(errors) = result.get("ConsistencyErrors").and_then(Value::as_array) {
+        for error in errors {
+            println!("{}", error.as_str().unwrap_or_default());
+        }
+        println!("The program is not well-formed");
+    } else if let Some(exception) = result.get("JavaException") {
+        println!(
+            "{}\n{}",
+            exception["message"].as_str().unwrap_or_default(),
+            exception["stack_trace"].as_str().unwrap_or_default(),
+        );
     } else {
-        Err(exit_status.code().unwrap_or(-1))
+        panic!("unexpected verification result: {}", result);
     }
+    Err(1)
 }
@@ -9,9 +9,11 @@
 extern crate clap;
 extern crate env_logger;
 extern crate log;
+extern crate prusti_common;
 extern crate prusti_server;
 
 use clap::{App, Arg};
+use prusti_common::config;
 use prusti_server::ServerSideService;
 
 fn main() {
@@ -37,6 +39,11 @@ fn main() {
         .parse()
         .expect("Invalid port provided");
 
+    if let Err(message) = config::check_concurrency() {
+        eprintln!("Invalid configuration: {}", message);
+        std::process::exit(2);
+    }
+
     let service = ServerSideService::new();
     service.listen_on_port(port);
 }
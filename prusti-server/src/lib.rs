@@ -13,13 +13,14 @@ extern crate warp;
 extern crate log;
 extern crate bincode;
 extern crate futures;
-extern crate num_cpus;
 extern crate prusti_common;
+extern crate siphasher;
 extern crate tokio;
 #[macro_use]
 extern crate serde;
 
 mod service;
+mod verification_cache;
 mod verifier_runner;
 mod verifier_thread;
 
@@ -28,8 +29,9 @@ use prusti_common::{verification_context::VerifierBuilder, verification_service:
 pub use service::*;
 use std::{
     collections::VecDeque,
-    sync::{Arc, RwLock},
+    sync::{Arc, Condvar, Mutex, RwLock},
 };
+pub use verification_cache::*;
 pub use verifier_runner::*;
 use verifier_thread::*;
 use viper::VerificationResult;
@@ -42,10 +44,26 @@ pub struct PrustiServer {
     verifier_builder: Arc<VerifierBuilder>,
     threads: RwLock<VecDeque<VerifierThread>>,
     cache_size: usize,
+    max_concurrency: usize,
+    /// The number of verifications that are currently running.
+    running: Mutex<usize>,
+    verification_finished: Condvar,
+}
+
+/// Permission to run one verification; it is given back to the server when dropped.
+struct VerificationSlot<'a> {
+    server: &'a PrustiServer,
+}
+
+impl Drop for VerificationSlot<'_> {
+    fn drop(&mut self) {
+        *self.server.running.lock().unwrap() -= 1;
+        self.server.verification_finished.notify_one();
+    }
 }
 
 impl PrustiServer {
-    pub fn new(cache_size: usize) -> PrustiServer {
+    pub fn new(max_concurrency: usize, cache_size: usize) -> PrustiServer {
         let stopwatch = Stopwatch::start("prusti-server", "JVM startup");
         let verifier_builder = Arc::new(VerifierBuilder::new());
         stopwatch.finish();
@@ -54,31 +72,72 @@ impl PrustiServer {
             verifier_builder,
             threads: RwLock::new(VecDeque::with_capacity(cache_size)),
             cache_size,
+            max_concurrency,
+            running: Mutex::new(0),
+            verification_finished: Condvar::new(),
         }
     }
 
+    /// Block until fewer than `max_concurrency` verifications are running.
+    fn acquire_slot(&self) -> VerificationSlot {
+        let mut running = self.running.lock().unwrap();
+        while *running >= self.max_concurrency {
+            running = self.verification_finished.wait(running).unwrap();
+        }
+        *running += 1;
+        VerificationSlot { server: self }
+    }
+
     pub fn run_verifier(&self, request: VerificationRequest) -> RemoteVerificationResult {
+        let VerificationRequest {
+            program,
+            program_name,
+            backend_config,
+        } = request;
+        self.run_on_thread(backend_config, program_name, |thread, program_name| {
+            thread.verify(program, program_name)
+        })
+    }
+
+    pub fn run_source_verifier(&self, request: ViperSourceRequest) -> RemoteVerificationResult {
+        let ViperSourceRequest {
+            source,
+            program_name,
+            backend_config,
+        } = request;
+        self.run_on_thread(backend_config, program_name, |thread, program_name| {
+            thread.verify_source(source, program_name)
+        })
+    }
+
+    fn run_on_thread<F>(
+        &self,
+        backend_config: ViperBackendConfig,
+        program_name: String,
+        verify: F,
+    ) -> RemoteVerificationResult
+    where
+        F: FnOnce(&VerifierThread, String) -> FutVerificationResult,
+    {
+        // each verification runs on its own verifier thread, so that independent requests
+        // are verified in parallel
+        let _slot = self.acquire_slot();
+
         // try to find and take out an existing threads from our cache
         let existing_thread = {
             let mut threads = self.threads.write().unwrap();
             let index = threads
                 .iter()
-                .position(|thread| thread.backend_config == request.backend_config);
+                .position(|thread| thread.backend_config == backend_config);
             index.map(|index| threads.remove(index).unwrap())
         };
 
         // if no thread found, create a new one
         let thread = existing_thread.unwrap_or_else(|| {
-            VerifierThread::new(
-                self.verifier_builder.clone(),
-                request.backend_config.clone(),
-            )
+            VerifierThread::new(self.verifier_builder.clone(), backend_config)
         });
 
-        match thread
-            .verify(request.program, request.program_name.clone())
-            .wait()
-        {
+        match verify(&thread, program_name.clone()).wait() {
             Ok(result) => {
                 // put back the thread for later reuse
                 let mut threads = self.threads.write().unwrap();
@@ -93,7 +152,7 @@ impl PrustiServer {
                 // canceled—the verifier thread panicked
                 error!(
                     "Panic while handling verification request {}",
-                    request.program_name
+                    program_name
                 );
                 Err(VerifierPanicked)
             }
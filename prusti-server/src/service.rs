@@ -6,10 +6,10 @@
 
 use super::{PrustiServer, RemoteVerificationResult};
 use prusti_common::{config, verification_service::*};
+use serde::Serialize;
 
 use bincode;
 use futures::{self};
-use num_cpus;
 use reqwest::{self, Client, Url, UrlError};
 use std::{
     net::{Ipv4Addr, SocketAddr},
@@ -28,10 +28,9 @@ pub struct ServerSideService {
 
 impl ServerSideService {
     pub fn new() -> Self {
-        // FIXME: since viper seems to dislike using verifiers in parallel, this is what we're doing to ensure correctness for now.
-        // Eventually, we should lock only specific parts, instantiate multiple JVMs, or even address the root cause.
-        let _max_concurrency = config::server_max_concurrency().unwrap_or_else(num_cpus::get);
-        let max_concurrency = 1;
+        // Viper does not support running verifiers in parallel in the same JVM, so the
+        // configuration only allows one request at a time (see `config::check_concurrency`).
+        let max_concurrency = config::server_max_concurrency().unwrap_or(1);
 
         let cache_size = config::server_max_stored_verifiers().unwrap_or(max_concurrency);
         if cache_size < max_concurrency {
@@ -40,7 +39,7 @@ impl ServerSideService {
 
         Self {
             max_concurrency,
-            server: Arc::new(PrustiServer::new(cache_size)),
+            server: Arc::new(PrustiServer::new(max_concurrency, cache_size)),
         }
     }
 
@@ -99,7 +98,36 @@ impl ServerSideService {
                 )
             });
 
-        let endpoints = json_verify.or(bincode_verify);
+        let clone = self.clone();
+        let json_verify_viper = warp::path("json")
+            .and(warp::path("verify-viper"))
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .map(move |request: ViperSourceRequest| clone.verify_viper_source(request))
+            .map(|response| warp::reply::json(&response));
+
+        let clone = self.clone();
+        let bincode_verify_viper = warp::path("bincode")
+            .and(warp::path("verify-viper"))
+            .and(warp::path::end())
+            .and(warp::body::concat())
+            .and_then(|buf: warp::body::FullBody| {
+                bincode::deserialize(&buf.bytes()).map_err(|err| {
+                    info!("request bincode body error: {}", err);
+                    warp::reject::custom(err)
+                })
+            })
+            .map(move |request: ViperSourceRequest| clone.verify_viper_source(request))
+            .map(|result| {
+                warp::http::Response::new(
+                    bincode::serialize(&result).expect("could not encode verification result"),
+                )
+            });
+
+        let endpoints = json_verify
+            .or(bincode_verify)
+            .or(json_verify_viper)
+            .or(bincode_verify_viper);
 
         info!("Prusti Server binding to port {}", port);
         let (address, server_handle) =
@@ -127,6 +155,11 @@ impl ServerSideService {
         info!("Handling verification request for {}", request.program_name);
         self.server.run_verifier(request)
     }
+
+    fn verify_viper_source(&self, request: ViperSourceRequest) -> RemoteVerificationResult {
+        info!("Handling Viper verification request for {}", request.program_name);
+        self.server.run_source_verifier(request)
+    }
 }
 
 pub struct PrustiServerConnection {
@@ -149,20 +182,42 @@ impl PrustiServerConnection {
     pub fn verify_checked(
         &self,
         request: VerificationRequest,
+    ) -> reqwest::Result<RemoteVerificationResult> {
+        self.post("verify/", &request)
+    }
+
+    pub fn verify_viper_source_checked(
+        &self,
+        request: ViperSourceRequest,
+    ) -> reqwest::Result<RemoteVerificationResult> {
+        self.post("verify-viper/", &request)
+    }
+
+    /// panics if the verification request fails
+    pub fn verify_viper_source(&self, request: ViperSourceRequest) -> VerificationResult {
+        self.verify_viper_source_checked(request)
+            .expect("Verification request to server failed!")
+            .expect("Server panicked while processing request!")
+    }
+
+    fn post<R: Serialize>(
+        &self,
+        endpoint: &str,
+        request: &R,
     ) -> reqwest::Result<RemoteVerificationResult> {
         let use_json = config::json_communication();
         let base = self.client.post(
             self.server_url
                 .join(if use_json { "json/" } else { "bincode/" })
                 .unwrap()
-                .join("verify/")
+                .join(endpoint)
                 .unwrap(),
         );
         let response = if use_json {
-            base.json(&request).send()?.error_for_status()?.json()?
+            base.json(request).send()?.error_for_status()?.json()?
         } else {
             let raw = base
-                .body(bincode::serialize(&request).expect("error encoding verification request"))
+                .body(bincode::serialize(request).expect("error encoding verification request"))
                 .send()?
                 .error_for_status()?;
             bincode::deserialize_from(raw).expect("error decoding verification result")
@@ -0,0 +1,214 @@
+// © 2021, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use prusti_common::{
+    verification_service::VerificationRequest,
+    vir::{self, ExprFolder, StmtFolder},
+};
+use siphasher::sip::SipHasher;
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    hash::Hasher,
+    io::{BufReader, BufWriter},
+    path::PathBuf,
+};
+use viper::VerificationResult;
+
+/// The keys of the hash function of the cache. They are fixed, so that the keys of the
+/// requests are the same in every run.
+const HASH_KEYS: (u64, u64) = (0x7072_7573_7469_2d63, 0x6163_6865_2d6b_6579);
+
+/// A cache of verification results that is stored on disk, so that requests that have already
+/// been verified in an earlier run are answered without running the verifier.
+pub struct PersistentCache {
+    path: PathBuf,
+    results: HashMap<u64, VerificationResult>,
+    updated: bool,
+}
+
+impl PersistentCache {
+    /// Load the cache stored at `path`. A missing or unreadable file gives an empty cache.
+    pub fn load<P: Into<PathBuf>>(path: P) -> Self {
+        let path = path.into();
+        let results = match File::open(&path) {
+            Ok(file) => bincode::deserialize_from(BufReader::new(file)).unwrap_or_else(|err| {
+                warn!("Ignoring the corrupted verification cache {:?}: {}", path, err);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        info!("Loaded {} cached verification results from {:?}", results.len(), path);
+        Self {
+            path,
+            results,
+            updated: false,
+        }
+    }
+
+    /// The key under which the result of `request` is cached. It covers the program, which
+    /// contains one method and the functions and predicates that it depends on, and the
+    /// backend configuration. The name of the program and the positions used to report errors
+    /// are left out, so that unrelated changes to the crate do not invalidate the result.
+    pub fn request_key(request: &VerificationRequest) -> u64 {
+        let program = erase_positions(request.program.clone());
+        let bytes = bincode::serialize(&(&program, &request.backend_config))
+            .expect("could not encode verification request");
+        let mut hasher = SipHasher::new_with_keys(HASH_KEYS.0, HASH_KEYS.1);
+        hasher.write(&bytes);
+        hasher.finish()
+    }
+
+    pub fn get(&self, key: u64) -> Option<VerificationResult> {
+        self.results.get(&key).cloned()
+    }
+
+    /// Store the result of a request. Only successful verifications are stored: the errors of
+    /// a failed verification refer to positions that are not part of the key, so the request
+    /// is verified again to report them at the right place.
+    pub fn insert(&mut self, key: u64, result: &VerificationResult) {
+        match result {
+            VerificationResult::Success() => {
+                self.results.insert(key, result.clone());
+                self.updated = true;
+            }
+            VerificationResult::Failure(_)
+            | VerificationResult::ConsistencyErrors(_)
+            | VerificationResult::JavaException(_) => {}
+        }
+    }
+
+    /// Write the cache back to disk, if it changed.
+    pub fn save(&self) {
+        if !self.updated {
+            return;
+        }
+        if let Some(parent) = self.path.parent() {
+            if let Err(err) = fs::create_dir_all(parent) {
+                warn!("Could not create the directory of the verification cache: {}", err);
+                return;
+            }
+        }
+        let result = File::create(&self.path)
+            .map_err(|err| err.to_string())
+            .and_then(|file| {
+                bincode::serialize_into(BufWriter::new(file), &self.results)
+                    .map_err(|err| err.to_string())
+            });
+        if let Err(err) = result {
+            warn!("Could not store the verification cache {:?}: {}", self.path, err);
+        }
+    }
+}
+
+/// Replace all the positions of a program by the default position.
+fn erase_positions(mut program: vir::Program) -> vir::Program {
+    let mut eraser = PositionEraser;
+    program.methods = program.methods
+        .into_iter()
+        .map(|method| {
+            method.patch_statements(|stmt| Ok::<_, ()>(StmtFolder::fold(&mut eraser, stmt)))
+                .unwrap()
+        })
+        .collect();
+    for function in program.functions.iter_mut() {
+        for expr in function.pres.iter_mut()
+            .chain(function.posts.iter_mut())
+            .chain(function.body.iter_mut())
+        {
+            *expr = ExprFolder::fold(&mut eraser, expr.clone());
+        }
+    }
+    for predicate in program.viper_predicates.iter_mut() {
+        match predicate {
+            vir::Predicate::Struct(predicate) => {
+                eraser.erase_struct_predicate(predicate);
+            }
+            vir::Predicate::Enum(predicate) => {
+                predicate.discriminant_bounds =
+                    ExprFolder::fold(&mut eraser, predicate.discriminant_bounds.clone());
+                for (guard, _, variant) in predicate.variants.iter_mut() {
+                    *guard = ExprFolder::fold(&mut eraser, guard.clone());
+                    eraser.erase_struct_predicate(variant);
+                }
+            }
+            vir::Predicate::Bodyless(..) => {}
+        }
+    }
+    program
+}
+
+struct PositionEraser;
+
+impl PositionEraser {
+    fn erase_struct_predicate(&mut self, predicate: &mut vir::StructPredicate) {
+        if let Some(body) = predicate.body.as_mut() {
+            *body = ExprFolder::fold(self, body.clone());
+        }
+    }
+}
+
+impl ExprFolder for PositionEraser {
+    fn fold(&mut self, expr: vir::Expr) -> vir::Expr {
+        vir::default_fold_expr(self, expr).set_pos(vir::Position::default())
+    }
+}
+
+impl StmtFolder for PositionEraser {
+    fn fold_expr(&mut self, expr: vir::Expr) -> vir::Expr {
+        ExprFolder::fold(self, expr)
+    }
+
+    fn fold_exhale(&mut self, expr: vir::Expr, _pos: vir::Position) -> vir::Stmt {
+        vir::Stmt::Exhale(self.fold_expr(expr), vir::Position::default())
+    }
+
+    fn fold_assert(&mut self, expr: vir::Expr, _pos: vir::Position) -> vir::Stmt {
+        vir::Stmt::Assert(self.fold_expr(expr), vir::Position::default())
+    }
+
+    fn fold_fold(
+        &mut self,
+        predicate_name: String,
+        args: Vec<vir::Expr>,
+        perm_amount: vir::PermAmount,
+        variant: vir::MaybeEnumVariantIndex,
+        _pos: vir::Position,
+    ) -> vir::Stmt {
+        vir::Stmt::Fold(
+            predicate_name,
+            args.into_iter().map(|arg| self.fold_expr(arg)).collect(),
+            perm_amount,
+            variant,
+            vir::Position::default(),
+        )
+    }
+
+    fn fold_obtain(&mut self, expr: vir::Expr, _pos: vir::Position) -> vir::Stmt {
+        vir::Stmt::Obtain(self.fold_expr(expr), vir::Position::default())
+    }
+
+    fn fold_package_magic_wand(
+        &mut self,
+        wand: vir::Expr,
+        body: Vec<vir::Stmt>,
+        label: String,
+        vars: Vec<vir::LocalVar>,
+        _pos: vir::Position,
+    ) -> vir::Stmt {
+        vir::Stmt::PackageMagicWand(
+            self.fold_expr(wand),
+            body.into_iter().map(|stmt| StmtFolder::fold(self, stmt)).collect(),
+            label,
+            vars,
+            vir::Position::default(),
+        )
+    }
+
+    fn fold_apply_magic_wand(&mut self, wand: vir::Expr, _pos: vir::Position) -> vir::Stmt {
+        vir::Stmt::ApplyMagicWand(self.fold_expr(wand), vir::Position::default())
+    }
+}
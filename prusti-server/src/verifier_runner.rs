@@ -67,6 +67,23 @@ impl<'v> VerifierRunner<'v> {
         self.verifier.verify(viper_program)
     }
 
+    /// Verify a program given as Viper source code. Errors reported while parsing or type
+    /// checking the source are returned as consistency errors.
+    pub fn verify_source(&self, source: &str, program_name: &str) -> VerificationResult {
+        let mut stopwatch = Stopwatch::start("prusti-server", "parsing of Viper source");
+        let viper_program = match self.ast_utils.parse_program(source) {
+            Ok(viper_program) => viper_program,
+            Err(errors) => return VerificationResult::ConsistencyErrors(errors),
+        };
+        if config::dump_viper_program() {
+            stopwatch.start_next("dumping viper program");
+            self.dump(viper_program, program_name);
+        }
+
+        stopwatch.start_next("verification");
+        self.verifier.verify(viper_program)
+    }
+
     fn dump(&self, program: viper::Program, program_name: &str) {
         let namespace = "viper_program";
         let filename = format!("{}.vpr", program_name);
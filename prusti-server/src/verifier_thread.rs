@@ -17,8 +17,14 @@ use viper::VerificationResult;
 
 pub type FutVerificationResult = Box<dyn Future<Item = VerificationResult, Error = Canceled>>;
 
+/// What a verifier thread verifies: either an encoded program, or Viper source code.
+enum VerifierInput {
+    Program(Program),
+    Source(String),
+}
+
 struct VerificationRequest {
-    pub program: Program,
+    pub input: VerifierInput,
     pub program_name: String,
     pub sender: oneshot::Sender<VerificationResult>,
 }
@@ -57,7 +63,14 @@ impl VerifierThread {
         request_receiver: mpsc::Receiver<VerificationRequest>,
     ) {
         while let Ok(request) = request_receiver.recv() {
-            let result = runner.verify(request.program, request.program_name.as_str());
+            let result = match request.input {
+                VerifierInput::Program(program) => {
+                    runner.verify(program, request.program_name.as_str())
+                }
+                VerifierInput::Source(source) => {
+                    runner.verify_source(&source, request.program_name.as_str())
+                }
+            };
             request.sender.send(result).unwrap_or_else(|err| {
                 error!(
                     "verifier thread attempting to send result to dropped receiver: {:?}",
@@ -68,12 +81,20 @@ impl VerifierThread {
     }
 
     pub fn verify(&self, program: Program, program_name: String) -> FutVerificationResult {
+        self.send(VerifierInput::Program(program), program_name)
+    }
+
+    pub fn verify_source(&self, source: String, program_name: String) -> FutVerificationResult {
+        self.send(VerifierInput::Source(source), program_name)
+    }
+
+    fn send(&self, input: VerifierInput, program_name: String) -> FutVerificationResult {
         let (tx, rx) = oneshot::channel();
         self.request_sender
             .lock()
             .unwrap()
             .send(VerificationRequest {
-                program,
+                input,
                 program_name,
                 sender: tx,
             })
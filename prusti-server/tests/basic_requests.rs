@@ -5,7 +5,7 @@ extern crate viper;
 extern crate lazy_static;
 
 use prusti_common::{
-    verification_service::{VerificationRequest, VerificationService},
+    verification_service::{VerificationRequest, VerificationService, ViperSourceRequest},
     vir::*,
 };
 use prusti_server::{PrustiServerConnection, ServerSideService};
@@ -26,7 +26,14 @@ fn consistency_error() {
     });
 
     match result {
-        VerificationResult::ConsistencyErrors(errors) => println!("errors: {:?}", errors),
+        VerificationResult::ConsistencyErrors(errors) => {
+            assert_eq!(errors.len(), 1, "unexpected errors: {:?}", errors);
+            assert!(
+                errors[0].contains("Parse error") && errors[0].contains("source.vpr@1."),
+                "expected a parse error on the first line, instead found {:?}",
+                errors[0]
+            );
+        }
         other => panic!(
             "consistency errors not identified, instead found {:?}",
             other
@@ -47,6 +54,82 @@ fn empty_program() {
     }
 }
 
+#[test]
+fn concurrent_requests() {
+    let handles: Vec<_> = (0..4)
+        .map(|_| std::thread::spawn(|| process_program(|_| ())))
+        .collect();
+
+    for handle in handles {
+        match handle.join().expect("request thread panicked") {
+            VerificationResult::Success() => (),
+            other => panic!(
+                "concurrent empty programs not verified successfully, instead found {:?}",
+                other
+            ),
+        }
+    }
+}
+
+#[test]
+fn viper_source_success() {
+    let result = process_viper_source("method m(x: Int) requires x > 0 { assert x >= 1 }");
+
+    match result {
+        VerificationResult::Success() => (),
+        other => panic!(
+            "correct Viper program not verified successfully, instead found {:?}",
+            other
+        ),
+    }
+}
+
+#[test]
+fn viper_source_failure() {
+    let result = process_viper_source("method m(x: Int) { assert x >= 1 }");
+
+    match result {
+        VerificationResult::Failure(errors) => assert_eq!(errors.len(), 1),
+        other => panic!(
+            "incorrect Viper program not identified, instead found {:?}",
+            other
+        ),
+    }
+}
+
+#[test]
+fn viper_source_parse_error() {
+    let result = process_viper_source("method m( { }");
+
+    match result {
+        VerificationResult::ConsistencyErrors(errors) => {
+            assert_eq!(errors.len(), 1, "unexpected errors: {:?}", errors);
+            assert!(
+                errors[0].contains("Parse error") && errors[0].contains("source.vpr@1."),
+                "expected a parse error on the first line, instead found {:?}",
+                errors[0]
+            );
+        }
+        other => panic!(
+            "parse errors not identified, instead found {:?}",
+            other
+        ),
+    }
+}
+
+fn process_viper_source(source: &str) -> VerificationResult {
+    let service =
+        PrustiServerConnection::new(SERVER_ADDRESS.clone()).expect("Could not connect to server!");
+
+    let request = ViperSourceRequest {
+        source: source.to_string(),
+        program_name: "dummy".to_string(),
+        backend_config: Default::default(),
+    };
+
+    service.verify_viper_source(request)
+}
+
 fn process_program<F>(configure: F) -> VerificationResult
 where
     F: FnOnce(&mut Program),
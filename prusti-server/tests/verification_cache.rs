@@ -0,0 +1,102 @@
+extern crate prusti_common;
+extern crate prusti_server;
+extern crate viper;
+
+use prusti_common::{verification_service::VerificationRequest, vir::*};
+use prusti_server::PersistentCache;
+use viper::{JavaException, VerificationResult};
+
+#[test]
+fn results_survive_reload() {
+    let path = std::env::temp_dir().join(format!("prusti_cache_test_{}.bin", std::process::id()));
+    let request = dummy_request(vec![]);
+    let key = PersistentCache::request_key(&request);
+
+    let mut cache = PersistentCache::load(&path);
+    assert!(cache.get(key).is_none());
+    cache.insert(key, &VerificationResult::Success());
+    cache.save();
+
+    let cache = PersistentCache::load(&path);
+    assert_eq!(cache.get(key), Some(VerificationResult::Success()));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn key_depends_on_program() {
+    let empty = dummy_request(vec![]);
+    let mut renamed = dummy_request(vec![]);
+    renamed.program_name = "other".to_string();
+    let with_field = dummy_request(vec![Field {
+        name: "f".to_string(),
+        typ: Type::Int,
+    }]);
+
+    assert_eq!(
+        PersistentCache::request_key(&empty),
+        PersistentCache::request_key(&renamed)
+    );
+    assert_ne!(
+        PersistentCache::request_key(&empty),
+        PersistentCache::request_key(&with_field)
+    );
+}
+
+#[test]
+fn key_ignores_positions() {
+    let with_position = |position| {
+        let mut request = dummy_request(vec![]);
+        request.program.functions.push(Function {
+            name: "f".to_string(),
+            formal_args: vec![],
+            return_type: Type::Bool,
+            pres: vec![],
+            posts: vec![Expr::Const(Const::Bool(true), position)],
+            body: None,
+        });
+        request
+    };
+
+    assert_eq!(
+        PersistentCache::request_key(&with_position(Position::new(1, 2, 3))),
+        PersistentCache::request_key(&with_position(Position::new(4, 5, 6)))
+    );
+}
+
+#[test]
+fn failures_are_not_cached() {
+    let path = std::env::temp_dir().join(format!("prusti_cache_fail_{}.bin", std::process::id()));
+    let key = PersistentCache::request_key(&dummy_request(vec![]));
+
+    let mut cache = PersistentCache::load(&path);
+    cache.insert(key, &VerificationResult::Failure(vec![]));
+    assert!(cache.get(key).is_none());
+}
+
+#[test]
+fn exceptions_are_not_cached() {
+    let path = std::env::temp_dir().join(format!("prusti_cache_exc_{}.bin", std::process::id()));
+    let key = PersistentCache::request_key(&dummy_request(vec![]));
+
+    let mut cache = PersistentCache::load(&path);
+    cache.insert(key, &VerificationResult::JavaException(JavaException::new(
+        "message".to_string(),
+        "stack trace".to_string(),
+    )));
+    assert!(cache.get(key).is_none());
+}
+
+fn dummy_request(fields: Vec<Field>) -> VerificationRequest {
+    VerificationRequest {
+        program: Program {
+            domains: vec![],
+            fields,
+            builtin_methods: vec![],
+            methods: vec![],
+            functions: vec![],
+            viper_predicates: vec![],
+        },
+        program_name: "dummy".to_string(),
+        backend_config: Default::default(),
+    }
+}
@@ -114,8 +114,15 @@ pub fn rewrite_impl(
                 method.attrs.push(parse_quote_spanned!(item_span=> #[prusti::extern_spec]));
                 method.attrs.push(parse_quote_spanned!(item_span=> #[trusted]));
 
-                let mut method_path: syn::ExprPath = parse_quote_spanned! {ident.span()=>
-                    #item_ty :: #ident
+                // Types that are not paths, like slices, need to be wrapped in angle brackets
+                let mut method_path: syn::ExprPath = if let syn::Type::Path(_) = item_ty.as_ref() {
+                    parse_quote_spanned! {ident.span()=>
+                        #item_ty :: #ident
+                    }
+                } else {
+                    parse_quote_spanned! {ident.span()=>
+                        < #item_ty > :: #ident
+                    }
                 };
 
                 // Fix the span
@@ -228,10 +235,18 @@ pub fn generate_new_struct(item: &syn::ItemImpl) -> syn::Result<syn::ItemStruct>
 
     let mut fields_str: String = String::new();
 
-    // Add `PhantomData` markers for each type parameter to silence errors
-    // about unused type parameters.
+    // Add `PhantomData` markers for each type and lifetime parameter to
+    // silence errors about unused parameters.
     for param in generics.params.iter() {
-        let field = format!("std::marker::PhantomData<{}>,", param.to_token_stream().to_string());
+        let field = match param {
+            syn::GenericParam::Type(type_param) => {
+                format!("std::marker::PhantomData<{}>,", type_param.ident)
+            }
+            syn::GenericParam::Lifetime(lifetime_def) => {
+                format!("std::marker::PhantomData<&{} ()>,", lifetime_def.lifetime)
+            }
+            syn::GenericParam::Const(_) => continue,
+        };
         fields_str.push_str(&field);
     }
 
@@ -1,13 +1,24 @@
 #![deny(unused_must_use)]
 
+macro_rules! handle_result {
+    ($parse_result: expr) => {
+        match $parse_result {
+            Ok(data) => data,
+            Err(err) => return err.to_compile_error(),
+        };
+    };
+}
+
 #[macro_use]
 mod parse_quote_spanned;
 mod span_overrider;
 mod extern_spec_rewriter;
 mod rewriter;
 mod parse_closure_macro;
+mod parse_loop_invariant;
 mod spec_attribute_kind;
 pub mod specifications;
+pub mod runtime_checks;
 
 use proc_macro2::{Span, TokenStream, TokenTree};
 use quote::{quote, quote_spanned, ToTokens};
@@ -15,18 +26,11 @@ use syn::spanned::Spanned;
 use std::convert::TryInto;
 
 use specifications::untyped;
+use specifications::common::GhostStatementKind;
 use parse_closure_macro::ClosureWithSpec;
+use parse_loop_invariant::LoopInvariant;
 pub use spec_attribute_kind::SpecAttributeKind;
 
-macro_rules! handle_result {
-    ($parse_result: expr) => {
-        match $parse_result {
-            Ok(data) => data,
-            Err(err) => return err.to_compile_error(),
-        };
-    };
-}
-
 fn extract_prusti_attributes(
     item: &mut untyped::AnyFnItem
 ) -> Vec<(SpecAttributeKind, TokenStream)> {
@@ -39,7 +43,8 @@ fn extract_prusti_attributes(
                     SpecAttributeKind::Requires
                     | SpecAttributeKind::Ensures
                     | SpecAttributeKind::AfterExpiry
-                    | SpecAttributeKind::AfterExpiryIf => {
+                    | SpecAttributeKind::AfterExpiryIf
+                    | SpecAttributeKind::Decreases => {
                         // We need to drop the surrounding parenthesis to make the
                         // tokens identical to the ones passed by the native procedural
                         // macro call.
@@ -131,6 +136,7 @@ fn generate_spec_and_assertions(
             SpecAttributeKind::AfterExpiryIf => generate_for_after_expiry_if(attr_tokens, item),
             SpecAttributeKind::Pure => generate_for_pure(attr_tokens, item),
             SpecAttributeKind::Trusted => generate_for_trusted(attr_tokens, item),
+            SpecAttributeKind::Decreases => generate_for_decreases(attr_tokens, item),
             // Predicates are handled separately below; the entry in the SpecAttributeKind enum
             // only exists so we successfully parse it and emit an error in
             // `check_incompatible_attrs`; so we'll never reach here.
@@ -184,6 +190,26 @@ fn generate_for_ensures(attr: TokenStream, item: &untyped::AnyFnItem) -> Generat
     ))
 }
 
+/// Generate spec items and attributes to typecheck and later retrieve "decreases" annotations.
+fn generate_for_decreases(attr: TokenStream, item: &untyped::AnyFnItem) -> GeneratedResult {
+    let mut rewriter = rewriter::AstRewriter::new();
+    let spec_id = rewriter.generate_spec_id();
+    let spec_id_str = spec_id.to_string();
+    let measure = rewriter.parse_measure(spec_id, attr)?;
+    let spec_item = rewriter.generate_spec_item_fn(
+        rewriter::SpecItemType::Termination,
+        spec_id,
+        measure,
+        &item
+    )?;
+    Ok((
+        vec![spec_item],
+        vec![parse_quote_spanned! {item.span()=>
+            #[prusti::decreases_spec_id_ref = #spec_id_str]
+        }],
+    ))
+}
+
 /// Check if the given expression is identifier `result`.
 fn check_is_result(reference: &Option<untyped::Expression>) -> syn::Result<()> {
     if let Some(untyped::Expression { expr, ..}) = reference {
@@ -290,11 +316,55 @@ fn generate_for_trusted(attr: TokenStream, item: &untyped::AnyFnItem) -> Generat
     ))
 }
 
+/// Generate the spec item and the attribute to typecheck and later retrieve an "invariant"
+/// annotation of a struct or enum.
+pub fn invariant(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    let item: syn::DeriveInput = handle_result!(syn::parse2(tokens));
+    let item_span = item.span();
+    if let syn::Data::Union(_) = item.data {
+        return syn::Error::new(
+            item_span,
+            "the `#[invariant]` attribute can only be used on structs and enums",
+        ).to_compile_error();
+    }
+
+    let mut rewriter = rewriter::AstRewriter::new();
+    let spec_id = rewriter.generate_spec_id();
+    let spec_id_str = spec_id.to_string();
+    let assertion = handle_result!(rewriter.parse_assertion(spec_id, attr));
+    let spec_item = rewriter.generate_spec_item_type_invariant(spec_id, assertion, &item);
+
+    let item_ident = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+    quote_spanned! {item_span=>
+        #[prusti::inv_spec_id_ref = #spec_id_str]
+        #item
+        impl #impl_generics #item_ident #ty_generics #where_clause {
+            #spec_item
+        }
+    }
+}
+
 pub fn body_invariant(tokens: TokenStream) -> TokenStream {
     let mut rewriter = rewriter::AstRewriter::new();
     let spec_id = rewriter.generate_spec_id();
-    let invariant = handle_result!(rewriter.parse_assertion(spec_id, tokens));
-    let check = rewriter.generate_spec_loop(spec_id, invariant);
+    let loop_invariant: LoopInvariant = handle_result!(syn::parse2(tokens));
+    let invariant = handle_result!(rewriter.parse_assertion(spec_id, loop_invariant.assertion));
+    let check = rewriter.generate_spec_loop(spec_id, invariant, loop_invariant.iterator);
+    let callsite_span = Span::call_site();
+    quote_spanned! {callsite_span=>
+        #[allow(unused_must_use, unused_variables)]
+        if false {
+            #check
+        }
+    }
+}
+
+pub fn body_variant(tokens: TokenStream) -> TokenStream {
+    let mut rewriter = rewriter::AstRewriter::new();
+    let spec_id = rewriter.generate_spec_id();
+    let variant = handle_result!(rewriter.parse_measure(spec_id, tokens));
+    let check = rewriter.generate_spec_loop_variant(spec_id, variant);
     let callsite_span = Span::call_site();
     quote_spanned! {callsite_span=>
         #[allow(unused_must_use, unused_variables)]
@@ -304,6 +374,44 @@ pub fn body_invariant(tokens: TokenStream) -> TokenStream {
     }
 }
 
+pub fn prusti_assert(tokens: TokenStream) -> TokenStream {
+    ghost_statement(GhostStatementKind::Assert, tokens)
+}
+
+pub fn prusti_assume(tokens: TokenStream) -> TokenStream {
+    ghost_statement(GhostStatementKind::Assume, tokens)
+}
+
+pub fn prusti_refute(tokens: TokenStream) -> TokenStream {
+    ghost_statement(GhostStatementKind::Refute, tokens)
+}
+
+fn ghost_statement(kind: GhostStatementKind, tokens: TokenStream) -> TokenStream {
+    let mut rewriter = rewriter::AstRewriter::new();
+    let spec_id = rewriter.generate_spec_id();
+    let assertion = handle_result!(rewriter.parse_assertion(spec_id, tokens));
+    let check = rewriter.generate_spec_ghost_statement(kind, spec_id, assertion);
+    let callsite_span = Span::call_site();
+    quote_spanned! {callsite_span=>
+        #[allow(unused_must_use, unused_variables)]
+        if false {
+            #check
+        }
+    }
+}
+
+/// Ghost code is kept in the enclosing scope, so that its `let` bindings can be
+/// used by later ghost code and specifications, and is delimited by calls of
+/// two marker functions that Prusti uses to identify the ghost blocks.
+pub fn ghost(tokens: TokenStream) -> TokenStream {
+    let callsite_span = Span::call_site();
+    quote_spanned! {callsite_span=>
+        ::prusti_contracts::ghost_begin();
+        #tokens
+        ::prusti_contracts::ghost_end();
+    }
+}
+
 /// Unlike the functions above, which are only called from
 /// prusti-contracts-internal, this function also needs to be called
 /// from prusti-contracts-impl, because we still need to parse the
@@ -0,0 +1,28 @@
+use proc_macro2::TokenStream;
+use syn::parse::{Parse, ParseStream};
+
+/// The content of a `body_invariant!`: an assertion, optionally preceded by a binder
+/// `|iter: Type|` that names the iterator of the enclosing `for` loop.
+pub(crate) struct LoopInvariant {
+    pub iterator: Option<(syn::Ident, syn::Type)>,
+    pub assertion: TokenStream,
+}
+
+impl Parse for LoopInvariant {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut iterator = None;
+        if input.peek(syn::Token![|]) && !input.peek(syn::Token![||]) {
+            input.parse::<syn::Token![|]>()?;
+            let ident: syn::Ident = input.parse()?;
+            input.parse::<syn::Token![:]>()?;
+            let ty: syn::Type = input.parse()?;
+            input.parse::<syn::Token![|]>()?;
+            iterator = Some((ident, ty));
+        }
+
+        Ok(LoopInvariant {
+            iterator,
+            assertion: input.parse()?,
+        })
+    }
+}
@@ -1,4 +1,6 @@
-use crate::specifications::common::{ExpressionIdGenerator, SpecificationIdGenerator};
+use crate::specifications::common::{
+    ExpressionIdGenerator, GhostStatementKind, SpecificationIdGenerator,
+};
 use crate::specifications::untyped::{self, EncodeTypeCheck};
 use proc_macro2::{Span, TokenStream};
 use quote::{quote_spanned, format_ident};
@@ -15,6 +17,8 @@ pub enum SpecItemType {
     Precondition,
     Postcondition,
     Predicate,
+    Termination,
+    Invariant,
 }
 
 impl std::fmt::Display for SpecItemType {
@@ -23,6 +27,8 @@ impl std::fmt::Display for SpecItemType {
             SpecItemType::Precondition => write!(f, "pre"),
             SpecItemType::Postcondition => write!(f, "post"),
             SpecItemType::Predicate => write!(f, "pred"),
+            SpecItemType::Termination => write!(f, "term"),
+            SpecItemType::Invariant => write!(f, "inv"),
         }
     }
 }
@@ -48,6 +54,15 @@ impl AstRewriter {
         untyped::Assertion::parse(tokens, spec_id, &mut self.expr_id_generator)
     }
 
+    /// Parse a termination measure.
+    pub fn parse_measure(
+        &mut self,
+        spec_id: untyped::SpecificationId,
+        tokens: TokenStream,
+    ) -> syn::Result<untyped::Assertion> {
+        untyped::Assertion::parse_measure(tokens, spec_id, &mut self.expr_id_generator)
+    }
+
     /// Parse a pledge.
     pub fn parse_pledge(
         &mut self,
@@ -95,9 +110,10 @@ impl AstRewriter {
         fn_arg
     }
 
-    /// Generate a dummy function for checking the given precondition, postcondition or predicate.
+    /// Generate a dummy function for checking the given precondition, postcondition, predicate
+    /// or termination measure.
     ///
-    /// `spec_type` should be either `"pre"`, `"post"` or `"pred"`.
+    /// `spec_type` should be either `"pre"`, `"post"`, `"pred"` or `"term"`.
     pub fn generate_spec_item_fn(
         &mut self,
         spec_type: SpecItemType,
@@ -117,7 +133,11 @@ impl AstRewriter {
             item_span,
         );
         let mut statements = TokenStream::new();
-        assertion.encode_type_check(&mut statements);
+        if spec_type == SpecItemType::Termination {
+            assertion.encode_measure_type_check(&mut statements);
+        } else {
+            assertion.encode_type_check(&mut statements);
+        }
         let spec_id_str = spec_id.to_string();
         let assertion_json = crate::specifications::json::to_json_string(&assertion);
 
@@ -139,17 +159,56 @@ impl AstRewriter {
         Ok(syn::Item::Fn(spec_item))
     }
 
+    /// Generate a dummy method for checking the given type invariant of the struct or enum `item`.
+    ///
+    /// The method has to be placed in an inherent `impl` block of `item`, so that the
+    /// invariant can refer to `self`.
+    pub fn generate_spec_item_type_invariant(
+        &mut self,
+        spec_id: untyped::SpecificationId,
+        assertion: untyped::Assertion,
+        item: &syn::DeriveInput,
+    ) -> syn::ImplItemMethod {
+        let item_span = item.span();
+        let item_name = syn::Ident::new(
+            &format!("prusti_{}_item_{}_{}", SpecItemType::Invariant, item.ident, spec_id),
+            item_span,
+        );
+        let mut statements = TokenStream::new();
+        assertion.encode_type_check(&mut statements);
+        let spec_id_str = spec_id.to_string();
+        let assertion_json = crate::specifications::json::to_json_string(&assertion);
+
+        parse_quote_spanned! {item_span=>
+            #[allow(unused_must_use, unused_variables, dead_code, non_snake_case)]
+            #[prusti::spec_only]
+            #[prusti::spec_id = #spec_id_str]
+            #[prusti::assertion = #assertion_json]
+            fn #item_name(self) {
+                #statements
+            }
+        }
+    }
+
     /// Generate statements for checking the given loop invariant.
+    ///
+    /// If `iterator` is given, the closure takes the iterator of the enclosing `for` loop as an
+    /// argument with the given name and type.
     pub fn generate_spec_loop(
         &mut self,
         spec_id: untyped::SpecificationId,
         assertion: untyped::Assertion,
+        iterator: Option<(syn::Ident, syn::Type)>,
     ) -> TokenStream {
         let mut statements = TokenStream::new();
         assertion.encode_type_check(&mut statements);
         let spec_id_str = spec_id.to_string();
         let assertion_json = crate::specifications::json::to_json_string(&assertion);
         let callsite_span = Span::call_site();
+        let args = match iterator {
+            Some((ident, ty)) => quote_spanned! {callsite_span=> #ident: #ty },
+            None => TokenStream::new(),
+        };
         quote_spanned! {callsite_span=>
             #[allow(unused_must_use, unused_variables)]
             {
@@ -157,6 +216,58 @@ impl AstRewriter {
                 #[prusti::loop_body_invariant_spec]
                 #[prusti::spec_id = #spec_id_str]
                 #[prusti::assertion = #assertion_json]
+                |#args| {
+                    #statements
+                };
+            }
+        }
+    }
+
+    /// Generate statements for checking the given loop variant.
+    pub fn generate_spec_loop_variant(
+        &mut self,
+        spec_id: untyped::SpecificationId,
+        measure: untyped::Assertion,
+    ) -> TokenStream {
+        let mut statements = TokenStream::new();
+        measure.encode_measure_type_check(&mut statements);
+        let spec_id_str = spec_id.to_string();
+        let assertion_json = crate::specifications::json::to_json_string(&measure);
+        let callsite_span = Span::call_site();
+        quote_spanned! {callsite_span=>
+            #[allow(unused_must_use, unused_variables)]
+            {
+                #[prusti::spec_only]
+                #[prusti::loop_body_variant_spec]
+                #[prusti::spec_id = #spec_id_str]
+                #[prusti::assertion = #assertion_json]
+                || {
+                    #statements
+                };
+            }
+        }
+    }
+
+    /// Generate statements for checking the given ghost statement.
+    pub fn generate_spec_ghost_statement(
+        &mut self,
+        kind: GhostStatementKind,
+        spec_id: untyped::SpecificationId,
+        assertion: untyped::Assertion,
+    ) -> TokenStream {
+        let mut statements = TokenStream::new();
+        assertion.encode_type_check(&mut statements);
+        let spec_id_str = spec_id.to_string();
+        let kind_str = kind.to_string();
+        let assertion_json = crate::specifications::json::to_json_string(&assertion);
+        let callsite_span = Span::call_site();
+        quote_spanned! {callsite_span=>
+            #[allow(unused_must_use, unused_variables)]
+            {
+                #[prusti::spec_only]
+                #[prusti::ghost_statement = #kind_str]
+                #[prusti::spec_id = #spec_id_str]
+                #[prusti::assertion = #assertion_json]
                 || {
                     #statements
                 };
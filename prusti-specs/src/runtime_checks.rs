@@ -0,0 +1,319 @@
+//! Translation of specifications to runtime checks, used by `prusti-contracts-impl` when
+//! the `runtime-checks` feature is enabled. The executable parts of the specifications
+//! are compiled to `debug_assert!`s, while quantifiers, specification entailments,
+//! pledges and the parts that use ghost collections or ghost variables are skipped
+//! with a warning. Ghost code is erased, as without the feature.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned, format_ident};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use syn::spanned::Spanned;
+use syn::visit_mut::VisitMut;
+
+use crate::parse_loop_invariant::LoopInvariant;
+use crate::specifications::common::AssertionKind;
+use crate::specifications::preparser::{AssertionWithoutId, Parser};
+use crate::specifications::untyped::AnyFnItem;
+
+pub fn requires(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    let mut item: AnyFnItem = handle_result!(syn::parse2(tokens));
+    let assertion = handle_result!(Parser::from_token_stream(attr.clone()).extract_assertion());
+    let mut translator = AssertionTranslator::default();
+    let check = translator.translate_check(&assertion, "precondition might not hold", &attr);
+    if item.block().is_none() {
+        return without_body_error(item);
+    }
+    if let Some(block) = item.block_mut() {
+        let body = block.clone();
+        *block = syn::parse_quote! {{
+            #check
+            #body
+        }};
+    }
+    quote! { #item }
+}
+
+pub fn ensures(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    let mut item: AnyFnItem = handle_result!(syn::parse2(tokens));
+    let assertion = handle_result!(Parser::from_token_stream(attr.clone()).extract_assertion());
+    let mut translator = AssertionTranslator::default();
+    let check = translator.translate_check(&assertion, "postcondition might not hold", &attr);
+    if item.block().is_none() {
+        return without_body_error(item);
+    }
+    if item.sig().asyncness.is_some() {
+        let warning = skipped_check_warning("postconditions of async functions");
+        if let Some(block) = item.block_mut() {
+            let body = block.clone();
+            *block = syn::parse_quote! {{
+                #warning
+                #body
+            }};
+        }
+        return quote! { #item };
+    }
+    // The closure makes sure that `return` statements of the body do not skip the check.
+    let closure_output = match &item.sig().output {
+        syn::ReturnType::Type(_, ty) if matches!(**ty, syn::Type::ImplTrait(_)) => quote! {},
+        syn::ReturnType::Type(_, ty) => quote! { -> #ty },
+        syn::ReturnType::Default => quote! { -> () },
+    };
+    let old_values = translator.old_values.iter().enumerate().map(|(i, expr)| {
+        let ident = old_value_ident(i);
+        quote! { let #ident = (#expr).clone(); }
+    });
+    let old_values: Vec<_> = old_values.collect();
+    if let Some(block) = item.block_mut() {
+        let body = block.clone();
+        *block = syn::parse_quote! {{
+            #(#old_values)*
+            #[allow(clippy::redundant_closure_call)]
+            let result = (|| #closure_output #body)();
+            #check
+            result
+        }};
+    }
+    quote! { #item }
+}
+
+/// Pledges cannot be checked at runtime, because they talk about the state in which
+/// a borrow expires.
+pub fn after_expiry(tokens: TokenStream) -> TokenStream {
+    let mut item: AnyFnItem = handle_result!(syn::parse2(tokens));
+    let warning = skipped_check_warning("pledges");
+    if let Some(block) = item.block_mut() {
+        let body = block.clone();
+        *block = syn::parse_quote! {{
+            #warning
+            #body
+        }};
+    }
+    quote! { #item }
+}
+
+pub fn body_invariant(tokens: TokenStream) -> TokenStream {
+    let loop_invariant: LoopInvariant = handle_result!(syn::parse2(tokens));
+    if loop_invariant.iterator.is_some() {
+        return skipped_check_warning("loop invariants about the iterator of a `for` loop");
+    }
+    let assertion = handle_result!(
+        Parser::from_token_stream(loop_invariant.assertion.clone()).extract_assertion()
+    );
+    let mut translator = AssertionTranslator::default();
+    translator.translate_check(
+        &assertion,
+        "loop invariant might not hold",
+        &loop_invariant.assertion,
+    )
+}
+
+pub fn prusti_assert(tokens: TokenStream) -> TokenStream {
+    let assertion = handle_result!(Parser::from_token_stream(tokens.clone()).extract_assertion());
+    let mut translator = AssertionTranslator::default();
+    translator.translate_check(&assertion, "the asserted expression might not hold", &tokens)
+}
+
+thread_local! {
+    /// The names of the variables declared in the `ghost!` blocks expanded so far. Macros
+    /// are expanded in the order of the source code, so the ghost variables of a function
+    /// are known when the specifications in its body are expanded.
+    static GHOST_VARIABLES: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+/// Ghost code is erased, but its variables are remembered, so that the checks that
+/// mention them are skipped instead of failing to compile.
+pub fn ghost(tokens: TokenStream) -> TokenStream {
+    let stmts = handle_result!(syn::parse::Parser::parse2(syn::Block::parse_within, tokens));
+    GHOST_VARIABLES.with(|ghost_variables| {
+        let mut ghost_variables = ghost_variables.borrow_mut();
+        for stmt in &stmts {
+            if let syn::Stmt::Local(local) = stmt {
+                collect_pattern_idents(&local.pat, &mut ghost_variables);
+            }
+        }
+    });
+    TokenStream::new()
+}
+
+fn collect_pattern_idents(pat: &syn::Pat, idents: &mut HashSet<String>) {
+    match pat {
+        syn::Pat::Ident(pat_ident) => {
+            idents.insert(pat_ident.ident.to_string());
+        }
+        syn::Pat::Type(pat_type) => collect_pattern_idents(&pat_type.pat, idents),
+        syn::Pat::Reference(pat_ref) => collect_pattern_idents(&pat_ref.pat, idents),
+        syn::Pat::Tuple(pat_tuple) => {
+            for elem in &pat_tuple.elems {
+                collect_pattern_idents(elem, idents);
+            }
+        }
+        syn::Pat::TupleStruct(pat_tuple_struct) => {
+            for elem in &pat_tuple_struct.pat.elems {
+                collect_pattern_idents(elem, idents);
+            }
+        }
+        syn::Pat::Struct(pat_struct) => {
+            for field in &pat_struct.fields {
+                collect_pattern_idents(&field.pat, idents);
+            }
+        }
+        syn::Pat::Slice(pat_slice) => {
+            for elem in &pat_slice.elems {
+                collect_pattern_idents(elem, idents);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The checks of a function without a body, like a trait method without a default
+/// implementation, would have to be done by the callers, which the macros cannot
+/// modify, so the specification is rejected instead of being silently ignored.
+fn without_body_error(item: AnyFnItem) -> TokenStream {
+    let error = syn::Error::new(
+        item.sig().span(),
+        "the preconditions and postconditions of a function without a body cannot be \
+        checked at runtime; disable the `runtime-checks` feature of `prusti-contracts` \
+        or move the specification to the implementations of the function",
+    ).to_compile_error();
+    quote! {
+        #item
+        #error
+    }
+}
+
+fn old_value_ident(index: usize) -> syn::Ident {
+    format_ident!("prusti_old_value_{}", index)
+}
+
+/// Generates code that triggers a (deprecation) warning, which is the only way for a
+/// procedural macro to emit a warning on stable Rust.
+fn skipped_check_warning(what: &str) -> TokenStream {
+    let note = format!("{} cannot be checked at runtime, the check is skipped", what);
+    let callsite_span = Span::call_site();
+    quote_spanned! {callsite_span=>
+        {
+            #[deprecated(note = #note)]
+            #[allow(non_camel_case_types)]
+            struct prusti_runtime_check_skipped;
+            let _ = prusti_runtime_check_skipped;
+        }
+    }
+}
+
+#[derive(Default)]
+struct AssertionTranslator {
+    /// The expressions passed to `old`, which are evaluated at the beginning of the function.
+    old_values: Vec<syn::Expr>,
+    /// The kinds of the subassertions that cannot be checked at runtime.
+    skipped: Vec<&'static str>,
+}
+
+impl AssertionTranslator {
+    /// Translates the assertion to a `debug_assert!`, followed by the warnings about the
+    /// parts of the assertion that were skipped.
+    fn translate_check(
+        &mut self,
+        assertion: &AssertionWithoutId,
+        message: &str,
+        spec_tokens: &TokenStream,
+    ) -> TokenStream {
+        let condition = self.translate(assertion);
+        let message = format!("{}: {}", message, spec_tokens);
+        let warnings = self.skipped.iter().map(|what| skipped_check_warning(what));
+        let callsite_span = Span::call_site();
+        quote_spanned! {callsite_span=>
+            debug_assert!(#condition, "{}", #message);
+            #(#warnings)*
+        }
+    }
+
+    /// Translates the assertion to a boolean Rust expression. The subassertions that
+    /// cannot be executed are replaced by `true`.
+    fn translate(&mut self, assertion: &AssertionWithoutId) -> TokenStream {
+        match &*assertion.kind {
+            AssertionKind::Expr(expression) => {
+                let mut expr = expression.expr.clone();
+                if let Some(what) = non_executable_part(&expr) {
+                    self.skipped.push(what);
+                    return quote! { true };
+                }
+                self.visit_expr_mut(&mut expr);
+                quote! { (#expr) }
+            }
+            AssertionKind::And(conjuncts) => {
+                if conjuncts.is_empty() {
+                    return quote! { true };
+                }
+                let conjuncts = conjuncts.iter().map(|conjunct| self.translate(conjunct));
+                let conjuncts: Vec<_> = conjuncts.collect();
+                quote! { (#(#conjuncts)&&*) }
+            }
+            AssertionKind::Implies(lhs, rhs) => {
+                let lhs = self.translate(lhs);
+                let rhs = self.translate(rhs);
+                quote! { (!#lhs || #rhs) }
+            }
+            AssertionKind::ForAll(..)
+            | AssertionKind::Exists(..)
+            | AssertionKind::TypeCond(..) => {
+                self.skipped.push("quantifiers");
+                quote! { true }
+            }
+            AssertionKind::SpecEntailment { .. } => {
+                self.skipped.push("specification entailments");
+                quote! { true }
+            }
+        }
+    }
+}
+
+impl VisitMut for AssertionTranslator {
+    /// Replaces `old(e)` with a variable that holds a copy of `e` taken at the
+    /// beginning of the function.
+    fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+        if let syn::Expr::Call(call) = expr {
+            if let syn::Expr::Path(syn::ExprPath { path, .. }) = &*call.func {
+                if path.is_ident("old") && call.args.len() == 1 {
+                    let ident = old_value_ident(self.old_values.len());
+                    self.old_values.push(call.args[0].clone());
+                    *expr = syn::parse_quote! { #ident };
+                    return;
+                }
+            }
+        }
+        syn::visit_mut::visit_expr_mut(self, expr);
+    }
+}
+
+/// Returns the kind of the parts of the expression that cannot be executed: the ghost
+/// collections, whose operations panic, and the ghost variables, which are erased.
+fn non_executable_part(expr: &syn::Expr) -> Option<&'static str> {
+    let mut finder = NonExecutableFinder::default();
+    syn::visit::Visit::visit_expr(&mut finder, expr);
+    finder.found
+}
+
+#[derive(Default)]
+struct NonExecutableFinder {
+    found: Option<&'static str>,
+}
+
+impl<'ast> syn::visit::Visit<'ast> for NonExecutableFinder {
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        let is_ghost_collection = path.segments.iter()
+            .any(|segment| matches!(segment.ident.to_string().as_str(), "Seq" | "Set" | "Map"));
+        let is_ghost_variable = path.get_ident().map_or(false, |ident| {
+            GHOST_VARIABLES.with(|ghost_variables| {
+                ghost_variables.borrow().contains(&ident.to_string())
+            })
+        });
+        if self.found.is_none() && is_ghost_collection {
+            self.found = Some("specifications about ghost collections");
+        } else if self.found.is_none() && is_ghost_variable {
+            self.found = Some("specifications about ghost variables");
+        }
+        syn::visit::visit_path(self, path);
+    }
+}
@@ -19,6 +19,10 @@ pub enum SpecType {
     Invariant,
     /// Predicate
     Predicate,
+    /// Termination measure of a procedure or variant of a loop
+    Termination,
+    /// Assertion, assumption or refutation inside a procedure body
+    GhostStatement,
 }
 
 #[derive(Debug)]
@@ -38,6 +42,7 @@ impl<'a> TryFrom<&'a str> for SpecType {
             "ensures" => Ok(SpecType::Postcondition),
             "invariant" => Ok(SpecType::Invariant),
             "predicate" => Ok(SpecType::Predicate),
+            "decreases" => Ok(SpecType::Termination),
             _ => Err(TryFromStringError::UnknownSpecificationType),
         }
     }
@@ -51,7 +56,7 @@ impl<'a> TryFrom<&'a str> for SpecType {
 pub struct SpecificationId(Uuid);
 
 /// A reference to a procedure specification.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum SpecIdRef {
     Precondition(SpecificationId),
     Postcondition(SpecificationId),
@@ -60,6 +65,7 @@ pub enum SpecIdRef {
         rhs: SpecificationId,
     },
     Predicate(SpecificationId),
+    Termination(SpecificationId),
 }
 
 impl Display for SpecificationId {
@@ -139,8 +145,11 @@ impl NameGenerator {
                     path_str.push_str(&seg.ident.to_string());
                 }
             }
+            syn::Type::Slice(_) => {
+                path_str.push_str("Slice");
+            }
             _ => {
-                return Err("expected a path".to_string());
+                return Err("expected a path or a slice type".to_string());
             }
         };
         let uuid = Uuid::new_v4().to_simple();
@@ -301,20 +310,67 @@ pub struct Specification<EID, ET, AT> {
 pub struct LoopSpecification<EID, ET, AT> {
     /// Loop invariant.
     pub invariant: Vec<Assertion<EID, ET, AT>>,
+    /// Loop variant, an integer expression that has to decrease in every
+    /// iteration while staying non-negative.
+    pub variant: Option<Assertion<EID, ET, AT>>,
 }
 
 impl<EID, ET, AT> LoopSpecification<EID, ET, AT> {
-    pub fn new(invariant: Vec<Assertion<EID, ET, AT>>) -> Self {
-        Self { invariant }
+    pub fn new(
+        invariant: Vec<Assertion<EID, ET, AT>>,
+        variant: Option<Assertion<EID, ET, AT>>,
+    ) -> Self {
+        Self { invariant, variant }
     }
     pub fn empty() -> Self {
-        Self::new(Vec::new())
+        Self::new(Vec::new(), None)
     }
     pub fn is_empty(&self) -> bool {
-        self.invariant.is_empty()
+        self.invariant.is_empty() && self.variant.is_none()
+    }
+}
+
+/// The kind of a ghost statement written inside a procedure body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GhostStatementKind {
+    /// `prusti_assert!(..)`: the assertion has to hold.
+    Assert,
+    /// `prusti_assume!(..)`: the assertion is assumed to hold.
+    Assume,
+    /// `prusti_refute!(..)`: the assertion must not hold in all executions.
+    Refute,
+}
+
+impl<'a> TryFrom<&'a str> for GhostStatementKind {
+    type Error = TryFromStringError;
+
+    fn try_from(kind: &str) -> Result<GhostStatementKind, TryFromStringError> {
+        match kind {
+            "assert" => Ok(GhostStatementKind::Assert),
+            "assume" => Ok(GhostStatementKind::Assume),
+            "refute" => Ok(GhostStatementKind::Refute),
+            _ => Err(TryFromStringError::UnknownSpecificationType),
+        }
+    }
+}
+
+impl Display for GhostStatementKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GhostStatementKind::Assert => write!(f, "assert"),
+            GhostStatementKind::Assume => write!(f, "assume"),
+            GhostStatementKind::Refute => write!(f, "refute"),
+        }
     }
 }
 
+/// Specification of a ghost statement written inside a procedure body.
+#[derive(Debug, Clone)]
+pub struct GhostStatement<EID, ET, AT> {
+    pub kind: GhostStatementKind,
+    pub assertion: Assertion<EID, ET, AT>,
+}
+
 /// Specification of a procedure.
 #[derive(Debug, Clone)]
 pub struct ProcedureSpecification<EID, ET, AT> {
@@ -330,6 +386,11 @@ pub struct ProcedureSpecification<EID, ET, AT> {
     /// otherwise.
     pub predicate_body: Option<Assertion<EID, ET, AT>>,
 
+    /// Termination measure given with `#[decreases(..)]`. If present, every
+    /// recursive call has to strictly decrease it while keeping it
+    /// non-negative.
+    pub decreases: Option<Assertion<EID, ET, AT>>,
+
     pub pure: bool,
     pub trusted: bool,
 }
@@ -340,22 +401,27 @@ impl<EID, ET, AT> ProcedureSpecification<EID, ET, AT> {
         posts: Vec<Assertion<EID, ET, AT>>,
         pledges: Vec<Pledge<EID, ET, AT>>,
         predicate_body: Option<Assertion<EID, ET, AT>>,
+        decreases: Option<Assertion<EID, ET, AT>>,
     ) -> Self {
         Self {
             pres,
             posts,
             pledges,
             predicate_body,
+            decreases,
             pure: false,
             trusted: false,
         }
     }
     pub fn empty() -> Self {
-        Self::new(Vec::new(), Vec::new(), Vec::new(), None)
+        Self::new(Vec::new(), Vec::new(), Vec::new(), None, None)
     }
     pub fn is_empty(&self) -> bool {
         // TODO: should pledges be here as well?
-        self.pres.is_empty() && self.posts.is_empty() && self.predicate_body.is_none()
+        self.pres.is_empty()
+            && self.posts.is_empty()
+            && self.predicate_body.is_none()
+            && self.decreases.is_none()
     }
 }
 
@@ -388,11 +454,17 @@ impl<EID: Clone + Debug, ET: Clone + Debug, AT: Clone + Debug> ProcedureSpecific
         } else {
             other.predicate_body.clone()
         };
+        let decreases = if other.decreases.is_none() {
+            self.decreases.clone()
+        } else {
+            other.decreases.clone()
+        };
         Self {
             pres,
             posts,
             pledges,
             predicate_body,
+            decreases,
             pure: other.pure,
             trusted: other.trusted,
         }
@@ -408,6 +480,8 @@ pub enum SpecificationSet<EID, ET, AT> {
     Loop(LoopSpecification<EID, ET, AT>),
     /// Struct invariant.
     Struct(Vec<Specification<EID, ET, AT>>),
+    /// Assertion, assumption or refutation inside a procedure body.
+    GhostStatement(GhostStatement<EID, ET, AT>),
 }
 
 impl<EID, ET, AT> SpecificationSet<EID, ET, AT> {
@@ -416,6 +490,7 @@ impl<EID, ET, AT> SpecificationSet<EID, ET, AT> {
             SpecificationSet::Procedure(spec) => spec.is_empty(),
             SpecificationSet::Loop(ref invs) => invs.is_empty(),
             SpecificationSet::Struct(ref invs) => invs.is_empty(),
+            SpecificationSet::GhostStatement(_) => false,
         }
     }
 }
@@ -445,6 +520,14 @@ impl<EID: Clone + Debug, ET: Clone + Debug, AT: Clone + Debug> SpecificationSet<
         unreachable!("expected Loop: {:?}", self);
     }
 
+    #[track_caller]
+    pub fn expect_ghost_statement(&self) -> &GhostStatement<EID, ET, AT> {
+        if let SpecificationSet::GhostStatement(spec) = self {
+            return spec;
+        }
+        unreachable!("expected GhostStatement: {:?}", self);
+    }
+
     #[track_caller]
     pub fn expect_struct(&self) -> &Vec<Specification<EID, ET, AT>> {
         if let SpecificationSet::Struct(spec) = self {
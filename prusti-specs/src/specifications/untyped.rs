@@ -81,6 +81,14 @@ impl AnyFnItem {
             AnyFnItem::TraitMethod(item) => item.default.as_ref(),
         }
     }
+
+    pub fn block_mut(&mut self) -> Option<&mut syn::Block> {
+        match self {
+            AnyFnItem::Fn(item) => Some(&mut item.block),
+            AnyFnItem::ImplMethod(item) => Some(&mut item.block),
+            AnyFnItem::TraitMethod(item) => item.default.as_mut(),
+        }
+    }
 }
 
 impl ToTokens for AnyFnItem {
@@ -105,6 +113,62 @@ impl Assertion {
     }
 }
 
+impl Assertion {
+    /// Parse a termination measure. A measure is a plain Rust expression,
+    /// which is wrapped into an assertion so that it can be handled like the
+    /// other specifications.
+    pub(crate) fn parse_measure(
+        tokens: TokenStream,
+        spec_id: SpecificationId,
+        id_generator: &mut ExpressionIdGenerator,
+    ) -> syn::Result<Self> {
+        let expression: common::Expression<(), syn::Expr> = syn::parse2(tokens)?;
+        Ok(Assertion {
+            kind: Box::new(AssertionKind::Expr(expression.assign_id(spec_id, id_generator))),
+        })
+    }
+
+    /// Encode the statements for type-checking a termination measure. Unlike
+    /// the expressions of other assertions, a measure is not a boolean but an
+    /// integer, which the second closure checks; it is not encoded.
+    pub fn encode_measure_type_check(&self, tokens: &mut TokenStream) {
+        if let AssertionKind::Expr(expression) = &*self.kind {
+            let span = expression.expr.span();
+            let expr = &expression.expr;
+            let identifier = format!("{}_{}", expression.spec_id, expression.id);
+            let typeck_call = quote_spanned! { span =>
+                #[prusti::spec_only]
+                #[prusti::expr_id = #identifier]
+                || {
+                    #expr
+                };
+                #[prusti::spec_only]
+                || {
+                    trait IntegerMeasure {}
+                    impl IntegerMeasure for i8 {}
+                    impl IntegerMeasure for i16 {}
+                    impl IntegerMeasure for i32 {}
+                    impl IntegerMeasure for i64 {}
+                    impl IntegerMeasure for i128 {}
+                    impl IntegerMeasure for isize {}
+                    impl IntegerMeasure for u8 {}
+                    impl IntegerMeasure for u16 {}
+                    impl IntegerMeasure for u32 {}
+                    impl IntegerMeasure for u64 {}
+                    impl IntegerMeasure for u128 {}
+                    impl IntegerMeasure for usize {}
+                    #[prusti::spec_only]
+                    fn integer_measure<T: IntegerMeasure>(_measure: T) {}
+                    integer_measure(#expr);
+                };
+            };
+            tokens.extend(typeck_call);
+        } else {
+            unreachable!("a termination measure must be an expression: {:?}", self);
+        }
+    }
+}
+
 impl Parse for common::Expression<(), syn::Expr> {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         Ok(Self {
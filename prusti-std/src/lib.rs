@@ -0,0 +1,48 @@
+//! Specifications of the standard library that are bundled with Prusti.
+//!
+//! The specifications are written with `#[extern_spec]`. They are opt-in: Prusti
+//! adds them to the verified crate when `PRUSTI_STD_SPECS` is enabled, so that they
+//! do not have to be re-declared by each project. Specifications written by the user
+//! for the same functions take precedence over the bundled ones.
+//!
+//! `Box::new` does not need a specification, because Prusti encodes it directly.
+//!
+//! The specifications in `CORE_SPECIFICATIONS` describe types that Prusti encodes
+//! natively, like strings, and are always added.
+
+// These feature flags are only needed when type-checking the specifications,
+// which requires the macros of `prusti-contracts-internal`.
+#![cfg_attr(feature = "prusti", feature(register_tool))]
+#![cfg_attr(feature = "prusti", register_tool(prusti))]
+
+/// The source code of the specification modules of natively encoded types, keyed by
+/// their name.
+pub const CORE_SPECIFICATIONS: &[(&str, &str)] = &[
+    ("string", include_str!("specs/string.rs")),
+];
+
+/// The source code of the specification modules, keyed by their name.
+pub const SPECIFICATIONS: &[(&str, &str)] = &[
+    ("vec", include_str!("specs/vec.rs")),
+    ("option", include_str!("specs/option.rs")),
+    ("result", include_str!("specs/result.rs")),
+    ("mem", include_str!("specs/mem.rs")),
+    ("range", include_str!("specs/range.rs")),
+    ("slice_iter", include_str!("specs/slice_iter.rs")),
+    ("vec_into_iter", include_str!("specs/vec_into_iter.rs")),
+];
+
+/// The specification modules, which are only compiled to check that they type-check,
+/// because function stubs are not valid Rust code once the macros are erased.
+#[cfg(feature = "prusti")]
+#[allow(dead_code)]
+mod specs {
+    mod string;
+    mod vec;
+    mod option;
+    mod result;
+    mod mem;
+    mod range;
+    mod slice_iter;
+    mod vec_into_iter;
+}
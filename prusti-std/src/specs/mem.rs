@@ -0,0 +1,16 @@
+//! Specifications of the functions of `std::mem`.
+
+use prusti_contracts::*;
+
+#[extern_spec]
+mod std {
+    mod mem {
+        use prusti_contracts::*;
+
+        #[ensures(*a == old(*b) && *b == old(*a))]
+        pub fn swap<T: std::cmp::PartialEq>(a: &mut T, b: &mut T);
+
+        #[ensures(*dest == src && result == old(*dest))]
+        pub fn replace<T: std::cmp::PartialEq>(dest: &mut T, src: T) -> T;
+    }
+}
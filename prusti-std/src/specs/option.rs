@@ -0,0 +1,40 @@
+//! Specifications of `std::option::Option`.
+
+use prusti_contracts::*;
+
+#[extern_spec]
+impl<T> std::option::Option<T> {
+    #[pure]
+    #[ensures(matches!(*self, Some(_)) == result)]
+    pub fn is_some(&self) -> bool;
+
+    #[pure]
+    #[ensures(self.is_some() == !result)]
+    pub fn is_none(&self) -> bool;
+
+    #[requires(self.is_some())]
+    pub fn unwrap(self) -> T;
+
+    #[requires(self.is_some())]
+    pub fn expect(self, msg: &str) -> T;
+
+    #[ensures(result.is_some() == self.is_some())]
+    pub fn as_ref(&self) -> std::option::Option<&T>;
+
+    #[ensures(result.is_some() == old(self.is_some()))]
+    #[ensures(self.is_none())]
+    pub fn take(&mut self) -> std::option::Option<T>;
+
+    #[ensures(result.is_some() == old(self.is_some()))]
+    #[ensures(self.is_some())]
+    pub fn replace(&mut self, value: T) -> std::option::Option<T>;
+
+    #[ensures(result.is_ok() == self.is_some())]
+    pub fn ok_or<E>(self, err: E) -> std::result::Result<T, E>;
+
+    #[ensures(result.is_some() == (self.is_some() && optb.is_some()))]
+    pub fn and<U>(self, optb: std::option::Option<U>) -> std::option::Option<U>;
+
+    #[ensures(result.is_some() == (self.is_some() || optb.is_some()))]
+    pub fn or(self, optb: std::option::Option<T>) -> std::option::Option<T>;
+}
@@ -0,0 +1,30 @@
+//! Specifications of `std::ops::Range` as an iterator.
+//!
+//! The state of a range is its own model: it has produced the numbers below
+//! `start` and will produce `start..end`. A loop that needs the visited numbers
+//! as a sequence can collect them in a ghost `Seq`:
+//!
+//! ```ignore
+//! ghost! { let mut visited = Seq::empty(); }
+//! for i in 0..n {
+//!     body_invariant!(|it: Range<usize>| it.start == i + 1 && it.end == n);
+//!     body_invariant!(visited.len() == i);
+//!     ghost! { visited = visited.push(i); }
+//! }
+//! ```
+//!
+//! The specification of `next` applies to all the element types of `Range`,
+//! because they share the same implementation, so only `usize` ranges are
+//! specified.
+
+use prusti_contracts::*;
+
+#[extern_spec]
+impl std::ops::Range<usize> {
+    #[ensures(self.end == old(self.end))]
+    #[ensures(old(self.start < self.end) ==>
+        matches!(result, Some(x) if x == old(self.start)) && self.start == old(self.start) + 1)]
+    #[ensures(old(self.start >= self.end) ==>
+        matches!(result, None) && self.start == old(self.start))]
+    pub fn next(&mut self) -> std::option::Option<usize>;
+}
@@ -0,0 +1,44 @@
+//! Specifications of `std::result::Result`.
+
+use prusti_contracts::*;
+
+#[extern_spec]
+impl<T, E> std::result::Result<T, E> {
+    #[pure]
+    #[ensures(matches!(*self, Ok(_)) == result)]
+    pub fn is_ok(&self) -> bool;
+
+    #[pure]
+    #[ensures(self.is_ok() == !result)]
+    pub fn is_err(&self) -> bool;
+
+    #[ensures(result.is_some() == self.is_ok())]
+    pub fn ok(self) -> std::option::Option<T>;
+
+    #[ensures(result.is_some() == self.is_err())]
+    pub fn err(self) -> std::option::Option<E>;
+
+    #[ensures(result.is_ok() == self.is_ok())]
+    pub fn as_ref(&self) -> std::result::Result<&T, &E>;
+
+    #[ensures(result.is_ok() == (self.is_ok() && res.is_ok()))]
+    pub fn and<U>(self, res: std::result::Result<U, E>) -> std::result::Result<U, E>;
+
+    #[ensures(result.is_ok() == (self.is_ok() || res.is_ok()))]
+    pub fn or<F>(self, res: std::result::Result<T, F>) -> std::result::Result<T, F>;
+}
+
+#[extern_spec]
+impl<T, E: std::fmt::Debug> std::result::Result<T, E> {
+    #[requires(self.is_ok())]
+    pub fn unwrap(self) -> T;
+
+    #[requires(self.is_ok())]
+    pub fn expect(self, msg: &str) -> T;
+}
+
+#[extern_spec]
+impl<T: std::fmt::Debug, E> std::result::Result<T, E> {
+    #[requires(self.is_err())]
+    pub fn unwrap_err(self) -> E;
+}
@@ -0,0 +1,33 @@
+//! Specifications of `std::slice::Iter`.
+//!
+//! An iterator over a slice `s` is modelled by the number of elements it has not
+//! produced yet, `len()`: it has visited the first `s.len() - len()` elements of
+//! `s` and will visit the remaining `len()` ones. A loop that needs the visited
+//! elements as a sequence can collect them in a ghost `Seq`:
+//!
+//! ```ignore
+//! ghost! { let mut visited = Seq::empty(); }
+//! for x in s.iter() {
+//!     body_invariant!(|it: Iter<i32>| visited.len() + it.len() + 1 == s.len());
+//!     ghost! { visited = visited.push(*x); }
+//! }
+//! ```
+
+use prusti_contracts::*;
+
+#[extern_spec]
+impl<T> [T] {
+    #[ensures(result.len() == self.len())]
+    pub fn iter(&self) -> std::slice::Iter<T>;
+}
+
+#[extern_spec]
+impl<'a, T> std::slice::Iter<'a, T> {
+    #[pure]
+    pub fn len(&self) -> usize;
+
+    #[ensures(old(self.len()) > 0 ==>
+        matches!(result, Some(_)) && self.len() == old(self.len()) - 1)]
+    #[ensures(old(self.len()) == 0 ==> matches!(result, None) && self.len() == 0)]
+    pub fn next(&mut self) -> std::option::Option<&'a T>;
+}
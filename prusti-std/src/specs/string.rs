@@ -0,0 +1,28 @@
+//! Specifications of `str` and `std::string::String`.
+//!
+//! Prusti encodes both types as the sequence of their UTF-8 bytes, so `as_bytes`
+//! and `as_str` are the identity and do not need a specification.
+
+use prusti_contracts::*;
+
+#[extern_spec]
+impl str {
+    #[pure]
+    #[ensures(result == self.as_bytes().len())]
+    pub fn len(&self) -> usize;
+
+    #[pure]
+    #[ensures(result == (self.len() == 0))]
+    pub fn is_empty(&self) -> bool;
+}
+
+#[extern_spec]
+impl std::string::String {
+    #[pure]
+    #[ensures(result == self.as_bytes().len())]
+    pub fn len(&self) -> usize;
+
+    #[pure]
+    #[ensures(result == (self.len() == 0))]
+    pub fn is_empty(&self) -> bool;
+}
@@ -0,0 +1,52 @@
+//! Specifications of `std::vec::Vec`.
+
+use prusti_contracts::*;
+
+#[extern_spec]
+impl<T> std::vec::Vec<T> {
+    #[ensures(result.len() == 0)]
+    pub fn new() -> std::vec::Vec::<T>;
+
+    #[ensures(result.len() == 0)]
+    pub fn with_capacity(capacity: usize) -> std::vec::Vec::<T>;
+
+    #[pure]
+    pub fn len(&self) -> usize;
+
+    #[pure]
+    #[ensures(result == (self.len() == 0))]
+    pub fn is_empty(&self) -> bool;
+
+    #[ensures(self.len() == old(self.len()) + 1)]
+    pub fn push(&mut self, value: T);
+
+    #[ensures(old(self.len()) == 0 ==> self.len() == 0)]
+    #[ensures(old(self.len()) > 0 ==> self.len() == old(self.len()) - 1)]
+    pub fn pop(&mut self) -> std::option::Option<T>;
+
+    #[requires(index <= self.len())]
+    #[ensures(self.len() == old(self.len()) + 1)]
+    pub fn insert(&mut self, index: usize, element: T);
+
+    #[requires(index < self.len())]
+    #[ensures(self.len() == old(self.len()) - 1)]
+    pub fn remove(&mut self, index: usize) -> T;
+
+    #[requires(index < self.len())]
+    #[ensures(self.len() == old(self.len()) - 1)]
+    pub fn swap_remove(&mut self, index: usize) -> T;
+
+    #[ensures(len <= old(self.len()) ==> self.len() == len)]
+    #[ensures(len > old(self.len()) ==> self.len() == old(self.len()))]
+    pub fn truncate(&mut self, len: usize);
+
+    #[ensures(self.len() == old(self.len()) + old(other.len()))]
+    #[ensures(other.len() == 0)]
+    pub fn append(&mut self, other: &mut std::vec::Vec<T>);
+
+    #[ensures(self.len() == 0)]
+    pub fn clear(&mut self);
+
+    #[ensures(result.len() == old(self.len()))]
+    pub fn into_iter(self) -> std::vec::IntoIter<T>;
+}
@@ -0,0 +1,19 @@
+//! Specifications of `std::vec::IntoIter`.
+//!
+//! Like the iterators over slices, an iterator that consumes a vector is
+//! modelled by the number of elements it still owns, `len()`. The elements it
+//! has produced can be collected in a ghost `Seq`, as for `std::slice::Iter`.
+//! `Vec::into_iter` is specified together with the other methods of `Vec`.
+
+use prusti_contracts::*;
+
+#[extern_spec]
+impl<T> std::vec::IntoIter<T> {
+    #[pure]
+    pub fn len(&self) -> usize;
+
+    #[ensures(old(self.len()) > 0 ==>
+        matches!(result, Some(_)) && self.len() == old(self.len()) - 1)]
+    #[ensures(old(self.len()) == 0 ==> matches!(result, None) && self.len() == 0)]
+    pub fn next(&mut self) -> std::option::Option<T>;
+}
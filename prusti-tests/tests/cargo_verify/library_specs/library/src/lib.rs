@@ -0,0 +1,29 @@
+use prusti_contracts::*;
+
+pub struct Counter {
+    value: u32,
+}
+
+impl Counter {
+    #[ensures(result.value() == 0)]
+    pub fn new() -> Self {
+        Counter { value: 0 }
+    }
+
+    #[pure]
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    #[requires(self.value() < 100)]
+    #[ensures(self.value() == old(self.value()) + 1)]
+    pub fn increment(&mut self) {
+        self.value += 1;
+    }
+}
+
+#[requires(divisor != 0)]
+#[ensures(result <= dividend)]
+pub fn divide(dividend: u32, divisor: u32) -> u32 {
+    dividend / divisor
+}
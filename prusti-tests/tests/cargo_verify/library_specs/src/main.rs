@@ -0,0 +1,18 @@
+use library::{divide, Counter};
+
+fn count_twice() {
+    let mut counter = Counter::new();
+    counter.increment();
+    counter.increment();
+    assert!(counter.value() == 2);
+}
+
+fn bad_client(x: u32) -> u32 {
+    divide(x, 0)
+}
+
+fn main() {
+    count_twice();
+    assert!(divide(10, 3) <= 10);
+    bad_client(10);
+}
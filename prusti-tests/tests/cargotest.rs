@@ -71,6 +71,38 @@ error: could not compile `foo` due to previous error
         .run();
 }
 
+#[cargo_test]
+fn json_diagnostics() {
+    let p = project()
+        .file("src/main.rs", "fn main() { assert!(false); }")
+        .build();
+    p.process(cargo_prusti_path())
+        .arg("--diagnostics-format=json")
+        .with_status(101)
+        .run();
+    let diagnostics = fs::read_to_string(p.root().join("prusti-diagnostics/foo.json"))
+        .expect("the JSON diagnostics were not written");
+    assert!(diagnostics.contains(r#""kind": "verification""#), "{}", diagnostics);
+    assert!(diagnostics.contains(r#""context": "Panic""#), "{}", diagnostics);
+    assert!(diagnostics.contains(r#""file_name": "src/main.rs""#), "{}", diagnostics);
+}
+
+#[cargo_test]
+fn sarif_diagnostics() {
+    let p = project()
+        .file("src/main.rs", "fn main() { assert!(false); }")
+        .build();
+    p.process(cargo_prusti_path())
+        .arg("--diagnostics-format")
+        .arg("sarif")
+        .with_status(101)
+        .run();
+    let diagnostics = fs::read_to_string(p.root().join("prusti-diagnostics/foo.sarif"))
+        .expect("the SARIF diagnostics were not written");
+    assert!(diagnostics.contains(r#""version": "2.1.0""#), "{}", diagnostics);
+    assert!(diagnostics.contains(r#""ruleId": "prusti/verification""#), "{}", diagnostics);
+}
+
 /// Test `cargo-prusti` on one of the crates in `test/cargo_verify`.
 ///
 /// Special files and folders in the root of the test crate:
@@ -155,6 +187,11 @@ fn test_failing_crate() {
     test_local_project("failing_crate");
 }
 
+#[cargo_test]
+fn test_library_specs() {
+    test_local_project("library_specs");
+}
+
 #[cargo_test]
 fn test_prusti_toml() {
     test_local_project("prusti_toml");
@@ -144,6 +144,14 @@ fn run_verification_overflow(group_name: &str, filter: &Option<String>) {
     run_verification(group_name, filter);
 }
 
+fn run_verification_std_specs(group_name: &str, filter: &Option<String>) {
+    let _temporary_env_vars = (
+        TemporaryEnvVar::set("PRUSTI_STD_SPECS", "true"),
+    );
+
+    run_verification(group_name, filter);
+}
+
 fn run_verification_core_proof(group_name: &str, filter: &Option<String>) {
     let _temporary_env_vars = (
         TemporaryEnvVar::set("PRUSTI_CHECK_PANICS", "false"),
@@ -176,6 +184,10 @@ fn test_runner(_tests: &[&()]) {
     println!("[verify_overflow]");
     run_verification_overflow("verify_overflow", &filter);
 
+    // Test the verifier with the bundled specifications of the standard library.
+    println!("[verify_std]");
+    run_verification_std_specs("verify_std", &filter);
+
     // Test the verifier with panic checks disabled (i.e. verify only the core proof).
     println!("[core_proof]");
     run_verification_core_proof("core_proof", &filter);
@@ -0,0 +1,20 @@
+use prusti_contracts::*;
+
+fn main() {}
+
+#[requires(s.len() > 1)]
+fn set_first(s: &mut [i32]) {
+    s[0] = 1;
+    assert!(s[1] == 1);  //~ ERROR the asserted expression might not hold
+}
+
+fn write_unchecked(s: &mut [i32]) {
+    s[0] = 1;  //~ ERROR the array or slice index may be out of bounds
+}
+
+#[requires(s.len() > 2)]
+fn subslice_shifted(s: &[i32]) {
+    if let [_, rest @ ..] = s {
+        assert!(rest[0] == s[0]);  //~ ERROR the asserted expression might not hold
+    }
+}
@@ -0,0 +1,15 @@
+use prusti_contracts::*;
+
+fn main() {}
+
+fn out_of_bounds(s: &[i32]) -> usize {
+    let (left, _) = s.split_at(3); //~ ERROR statement might panic
+    left.len()
+}
+
+#[requires(s.len() > 3)]
+#[ensures(result == s[1])] //~ ERROR postcondition might not hold
+fn wrong_element(s: &[i32]) -> i32 {
+    let (_, right) = s.split_at(1);
+    right[1]
+}
@@ -0,0 +1,30 @@
+use prusti_contracts::*;
+
+#[requires(f |= |x: i32| [requires(x >= 0), ensures(result > x)])]
+fn apply_negative<F: Fn(i32) -> i32>(f: F) -> i32 {
+    f(-1) //~ ERROR precondition might not hold
+}
+
+#[requires(f |= |x: i32| [requires(x >= 0), ensures(result > x)])]
+#[ensures(result > 6)] //~ ERROR postcondition might not hold
+fn apply_weak<F: Fn(i32) -> i32>(f: F) -> i32 {
+    f(5)
+}
+
+#[requires(f |= |x: i32| [ensures(result == x)])]
+fn apply_fn_ptr(f: fn(i32) -> i32) -> i32 {
+    let r = f(3);
+    assert!(r == 4); //~ ERROR the asserted expression might not hold
+    r
+}
+
+#[ensures(result == x + 1)]
+fn inc(x: i32) -> i32 {
+    x + 1
+}
+
+fn pass_wrong_function() {
+    apply_fn_ptr(inc); //~ ERROR precondition might not hold
+}
+
+fn main() {}
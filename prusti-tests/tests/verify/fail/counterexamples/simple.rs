@@ -0,0 +1,19 @@
+// compile-flags: -Pcounterexample=true
+
+fn check_not_forty_two(x: i32) {
+    let y = x + 1;
+    assert!(y != 43); //~ ERROR the asserted expression might not hold
+    //~| NOTE counterexample: x = 42
+    //~| NOTE counterexample: y = 43
+}
+
+fn check_through_reference(x: i32) {
+    let mut y = 0;
+    let r = &mut y;
+    *r = x + 1;
+    assert!(y != 43); //~ ERROR the asserted expression might not hold
+    //~| NOTE counterexample: x = 42
+    //~| NOTE counterexample: y = 43
+}
+
+fn main() {}
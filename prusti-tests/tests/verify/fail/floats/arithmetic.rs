@@ -0,0 +1,18 @@
+use prusti_contracts::*;
+
+fn nan_is_not_equal_to_itself(x: f64) {
+    assert!(x == x); //~ ERROR the asserted expression might not hold
+}
+
+#[ensures(result < x)] //~ ERROR postcondition might not hold
+fn decrement(x: f32) -> f32 {
+    x - 1.0
+}
+
+fn rounding() {
+    let x = 0.1f64;
+    let y = 0.2f64;
+    assert!(x + y == 0.3); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
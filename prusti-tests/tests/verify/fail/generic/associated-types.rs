@@ -0,0 +1,40 @@
+use prusti_contracts::*;
+
+trait Container {
+    type Item: Copy;
+
+    #[pure]
+    fn get(&self) -> Self::Item;
+}
+
+struct Counter {
+    count: u32,
+}
+
+impl Container for Counter {
+    type Item = u32;
+
+    #[pure]
+    fn get(&self) -> u32 {
+        self.count
+    }
+}
+
+#[ensures(result == c.get())] //~ ERROR postcondition might not hold
+fn get_other<C: Container>(c: &C, other: &C) -> C::Item {
+    other.get()
+}
+
+#[requires(c.count < 100)]
+#[ensures(result == c.count + 1)]
+fn next(c: &Counter) -> <Counter as Container>::Item {
+    c.count + 1
+}
+
+fn client() {
+    let c = Counter { count: 3 };
+    let x = next(&c);
+    assert!(x == 3); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
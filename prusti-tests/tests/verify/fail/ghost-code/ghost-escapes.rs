@@ -0,0 +1,40 @@
+use prusti_contracts::*;
+
+fn ghost_return() -> usize {
+    ghost! {
+        return 0; //~ ERROR ghost code must terminate and cannot leave its ghost block
+    }
+    1
+}
+
+fn ghost_break(n: usize) {
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i < n);
+        ghost! {
+            if i == 0 {
+                break; //~ ERROR ghost code must terminate and cannot leave its ghost block
+            }
+        }
+        i += 1;
+    }
+}
+
+fn ghost_continue(n: usize) {
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i < n);
+        i += 1;
+        ghost! {
+            continue; //~ ERROR ghost code must terminate and cannot leave its ghost block
+        }
+    }
+}
+
+fn ghost_divergence() {
+    ghost! {
+        loop {} //~ ERROR ghost code must terminate and cannot leave its ghost block
+    }
+}
+
+fn main() {}
@@ -0,0 +1,46 @@
+use prusti_contracts::*;
+
+fn modify_nonghost() -> i32 {
+    let mut x = 0;
+    ghost! {
+        x = 1; //~ ERROR ghost code cannot modify non-ghost state
+    }
+    x
+}
+
+fn impure_call() {
+    ghost! {
+        let v: Vec<i32> = Vec::new(); //~ ERROR ghost code can only call pure functions
+    }
+}
+
+fn wrong_invariant(n: usize) {
+    let mut i = 0;
+    ghost! {
+        let mut visited = Seq::empty();
+    }
+    while i < n {
+        body_invariant!(visited.len() == i + 1); //~ ERROR loop invariant might not hold in the first loop iteration
+        ghost! {
+            visited = visited.push(i);
+        }
+        i += 1;
+    }
+}
+
+fn wrong_assert(n: usize) {
+    let mut i = 0;
+    ghost! {
+        let mut steps = 0;
+    }
+    while i < n {
+        body_invariant!(steps == i);
+        i += 1;
+        ghost! {
+            steps += 1;
+        }
+    }
+    prusti_assert!(steps == i + 1); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use prusti_contracts::*;
+
+fn build() -> Seq<i32> {
+    Seq::empty() //~ ERROR ghost collections can only be used in specifications and ghost code
+}
+
+fn main() {}
@@ -0,0 +1,48 @@
+use prusti_contracts::*;
+
+#[ensures(Seq::single(x).push(y).lookup(0) == y)] //~ ERROR postcondition might not hold
+fn wrong_index(x: i32, y: i32) {}
+
+#[ensures(Seq::single(x).concat(Seq::single(y)) == Seq::single(y).push(x))] //~ ERROR postcondition might not hold
+fn not_commutative(x: i32, y: i32) {}
+
+struct VecWrapper {
+    v: Vec<i32>,
+}
+
+impl VecWrapper {
+    #[trusted]
+    #[ensures(result.model() == Seq::empty())]
+    fn new() -> Self {
+        VecWrapper { v: Vec::new() }
+    }
+
+    #[trusted]
+    #[pure]
+    fn model(&self) -> Seq<i32> {
+        unimplemented!()
+    }
+
+    #[trusted]
+    #[ensures(self.model() == old(self.model()).push(value))]
+    fn push(&mut self, value: i32) {
+        self.v.push(value);
+    }
+
+    #[trusted]
+    #[pure]
+    #[requires(index < self.model().len())]
+    #[ensures(result == self.model().lookup(index))]
+    fn lookup(&self, index: usize) -> i32 {
+        self.v[index]
+    }
+}
+
+fn client() {
+    let mut w = VecWrapper::new();
+    w.push(1);
+    w.push(2);
+    assert!(w.lookup(0) == 2); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use prusti_contracts::*;
+
+#[ensures(Set::single(x).insert(y).len() == 2)] //~ ERROR postcondition might not hold
+fn duplicates(x: u32, y: u32) {}
+
+#[ensures(Map::single(k, v).insert(k, w).lookup(k) == v)] //~ ERROR postcondition might not hold
+fn overwritten(k: u32, v: i32, w: i32) {}
+
+fn main() {}
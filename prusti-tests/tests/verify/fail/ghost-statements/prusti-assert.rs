@@ -0,0 +1,18 @@
+use prusti_contracts::*;
+
+fn test_args(x: i32) {
+    prusti_assert!(x > 0); //~ ERROR the asserted expression might not hold
+}
+
+fn test_locals() {
+    let mut x = 3;
+    x += 1;
+    prusti_assert!(x == 3); //~ ERROR the asserted expression might not hold
+}
+
+fn test_assume(x: u32) {
+    prusti_assume!(x > 10);
+    prusti_assert!(x > 20); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
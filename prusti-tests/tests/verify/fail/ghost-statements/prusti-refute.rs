@@ -0,0 +1,18 @@
+use prusti_contracts::*;
+
+fn test_holds(x: u32) {
+    prusti_refute!(x == x); //~ ERROR the refuted expression holds in all cases or could not be reached
+}
+
+fn test_unreachable(x: u32) {
+    if x > 5 && x < 3 {
+        prusti_refute!(x == 4); //~ ERROR the refuted expression holds in all cases or could not be reached
+    }
+}
+
+#[requires(x > 0)]
+fn test_precondition(x: i32) {
+    prusti_refute!(x > 0); //~ ERROR the refuted expression holds in all cases or could not be reached
+}
+
+fn main() {}
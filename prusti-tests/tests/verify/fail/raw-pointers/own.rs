@@ -0,0 +1,28 @@
+use prusti_contracts::*;
+
+fn main() {}
+
+#[requires(own(p))]
+#[ensures(own(p))]
+#[ensures(unsafe { *p } == 5)]
+fn set_five(p: *mut i32) {
+    unsafe { std::ptr::write(p, 5); }
+}
+
+fn wrong_value() {
+    let mut x = 0;
+    let p = &mut x as *mut i32;
+    set_five(p);
+    assert!(x == 6); //~ ERROR the asserted expression might not hold
+}
+
+#[requires(own(p))]
+#[ensures(own(p))]
+#[ensures(unsafe { *p } == 5)] //~ ERROR postcondition might not hold
+fn set_six(p: *mut i32) {
+    unsafe { std::ptr::write(p, 6); }
+}
+
+fn not_owned(p: *mut i32) {
+    unsafe { *p = 1; } //~ ERROR the memory pointed to by a raw pointer might not be owned
+}
@@ -0,0 +1,18 @@
+// compile-flags: -Pverify_only=crate::checked::*
+
+use prusti_contracts::*;
+
+mod checked {
+    use prusti_contracts::*;
+
+    #[ensures(result == x + 1)] //~ ERROR postcondition might not hold
+    pub fn identity(x: u32) -> u32 {
+        x
+    }
+}
+
+fn not_checked() {
+    assert!(false);
+}
+
+fn main() {}
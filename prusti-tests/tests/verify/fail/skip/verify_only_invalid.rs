@@ -0,0 +1,4 @@
+// compile-flags: -Pverify_only=crate::checked::[
+// error-pattern: invalid VERIFY_ONLY pattern
+
+fn main() {}
@@ -0,0 +1,22 @@
+use prusti_contracts::*;
+
+fn main() {}
+
+fn wrong_len() {
+    let s = "abc";
+    assert!(s.len() == 2); //~ ERROR the asserted expression might not hold
+}
+
+#[ensures(result == s.len())] //~ ERROR postcondition might not hold
+fn len_plus_one(s: &str) -> usize {
+    s.len() + 1
+}
+
+#[pure]
+fn first_byte(s: &str) -> u8 {
+    s.as_bytes()[0] //~ ERROR the array or slice index may be out of bounds
+}
+
+fn different(a: &str, b: &str) {
+    assert!(a == b); //~ ERROR the asserted expression might not hold
+}
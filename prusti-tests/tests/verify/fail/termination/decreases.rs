@@ -0,0 +1,33 @@
+use prusti_contracts::*;
+
+#[pure]
+#[decreases(n)]
+fn no_lower_bound(n: i32) -> i32 {
+    if n == 0 { 0 } else { no_lower_bound(n - 1) } //~ ERROR termination measure might not decrease
+}
+
+#[pure]
+#[requires(n >= 0)]
+#[decreases(n)]
+fn no_progress(n: i32) -> i32 {
+    if n == 0 { 0 } else { no_progress(n) } //~ ERROR termination measure might not decrease
+}
+
+#[requires(n >= 0)]
+#[decreases(n)]
+fn count_up(n: i32) {
+    if n < 100 {
+        count_up(n + 1); //~ ERROR termination measure might not decrease
+    }
+}
+
+fn loop_variant() {
+    let mut i = 0;
+    while i < 10 {
+        body_invariant!(i < 10);
+        body_variant!(i); //~ ERROR loop variant might not decrease
+        i += 1;
+    }
+}
+
+fn main() {}
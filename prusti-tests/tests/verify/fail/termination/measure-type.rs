@@ -0,0 +1,24 @@
+use prusti_contracts::*;
+
+#[pure]
+#[decreases(x)] //~ ERROR the trait bound `f64: IntegerMeasure` is not satisfied
+fn float_measure(x: f64) -> f64 {
+    if x <= 0.0 { 0.0 } else { float_measure(x - 1.0) }
+}
+
+#[decreases(b)] //~ ERROR the trait bound `bool: IntegerMeasure` is not satisfied
+fn bool_measure(b: bool) {
+    if b {
+        bool_measure(false);
+    }
+}
+
+fn loop_variant(v: f64) {
+    let mut x = v;
+    while x > 0.0 {
+        body_variant!(x); //~ ERROR the trait bound `f64: IntegerMeasure` is not satisfied
+        x -= 1.0;
+    }
+}
+
+fn main() {}
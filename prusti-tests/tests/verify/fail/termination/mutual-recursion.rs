@@ -0,0 +1,48 @@
+use prusti_contracts::*;
+
+#[pure]
+#[requires(n >= 0 && n <= 100)]
+#[decreases(n)]
+fn is_even_up(n: i32) -> bool {
+    if n == 100 { true } else { is_odd_up(n + 1) } //~ ERROR termination measure might not decrease
+}
+
+#[pure]
+#[requires(n >= 0 && n <= 100)]
+#[decreases(100 - n)]
+fn is_odd_up(n: i32) -> bool {
+    if n == 100 { false } else { is_even_up(n + 1) } //~ ERROR termination measure might not decrease
+}
+
+#[requires(n >= 0)]
+#[decreases(n)]
+fn ping_up(n: i32) {
+    if n < 100 {
+        pong_up(n + 1); //~ ERROR termination measure might not decrease
+    }
+}
+
+#[requires(n >= 0)]
+#[decreases(n)]
+fn pong_up(n: i32) {
+    if n < 100 {
+        ping_up(n + 1); //~ ERROR termination measure might not decrease
+    }
+}
+
+#[requires(n >= 0)]
+#[decreases(n)]
+fn with_measure(n: i32) {
+    if n > 0 {
+        without_measure(n - 1); //~ ERROR the termination of this mutually recursive call cannot be checked
+    }
+}
+
+#[requires(n >= 0)]
+fn without_measure(n: i32) {
+    if n > 0 {
+        with_measure(n - 1);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,35 @@
+use prusti_contracts::*;
+
+trait Counter {
+    #[pure]
+    fn count(&self) -> u32;
+
+    #[requires(by > 0)]
+    #[ensures(result > 0)] //~ ERROR postcondition
+    fn step(&self, by: u32) -> u32;
+}
+
+struct Zero;
+
+impl Counter for Zero {
+    #[pure]
+    fn count(&self) -> u32 {
+        0
+    }
+
+    fn step(&self, by: u32) -> u32 {
+        0
+    }
+}
+
+fn call_pre(c: &dyn Counter) {
+    c.step(0); //~ ERROR precondition
+}
+
+// The dynamic type is abstracted, so nothing is known beyond the trait contract.
+fn call_post(c: &dyn Counter) {
+    let r = c.step(1);
+    assert!(r == 1); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
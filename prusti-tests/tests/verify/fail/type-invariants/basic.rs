@@ -0,0 +1,28 @@
+use prusti_contracts::*;
+
+#[invariant(self.value <= 100)]
+struct Percentage {
+    value: u8,
+}
+
+impl Percentage {
+    pub fn new(value: u8) -> Self { //~ ERROR type invariants might not hold
+        Percentage { value }
+    }
+
+    pub fn incr(&mut self) { //~ ERROR type invariants might not hold
+        self.value += 1;
+    }
+
+    pub fn check(&self) {
+        assert!(self.value < 100); //~ ERROR the asserted expression might not hold
+    }
+}
+
+// Private functions can build values that break the invariant, but not pass them to public ones
+fn test(x: u8) {
+    let mut perc = Percentage { value: x };
+    perc.incr(); //~ ERROR precondition might not hold.
+}
+
+fn main() {}
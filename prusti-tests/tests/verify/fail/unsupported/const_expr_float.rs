@@ -1,3 +0,0 @@
-fn main() {
-    let _ = [1.0]; //~ ERROR unsupported constant type Float(F64)
-}
\ No newline at end of file
@@ -0,0 +1,18 @@
+use prusti_contracts::*;
+
+#[requires(f |= |x: i32| [ensures(result == x)])]
+#[ensures(result == a)]
+fn apply(f: fn(i32) -> i32, a: i32) -> i32 {
+    f(a)
+}
+
+fn forward(f: fn(i32) -> i32) -> i32 {
+    apply(f, 1) //~ ERROR spec entailments on function pointers can only be checked when the argument is a function item
+}
+
+#[requires(f |= |x: bool| [ensures(result == x)])] //~ ERROR spec entailments are only supported on closures and function pointers with integer arguments and results
+fn apply_bool(f: fn(bool) -> bool) -> bool {
+    f(true)
+}
+
+fn main() {}
@@ -0,0 +1,38 @@
+use prusti_contracts::*;
+
+struct List {
+    value: u32,
+    next: Option<Box<List>>,
+}
+
+/// The reference to the current node is reborrowed in every iteration of the loop.
+fn clear_last(list: &mut List) {
+    let mut current = list;
+    loop { //~ ERROR references that are reborrowed across the iterations of a loop and are still alive after the loop are not supported
+        match current.next {
+            Some(ref mut next) => current = next,
+            None => break,
+        }
+    }
+    current.value = 0;
+}
+
+/// Two references are reborrowed in every iteration of the same loop.
+fn clear_both_last(first: &mut List, second: &mut List) {
+    let mut x = first;
+    let mut y = second;
+    loop { //~ ERROR references that are reborrowed across the iterations of a loop and are still alive after the loop are not supported
+        match x.next {
+            Some(ref mut next) => x = next,
+            None => break,
+        }
+        match y.next {
+            Some(ref mut next) => y = next,
+            None => break,
+        }
+    }
+    x.value = 0;
+    y.value = 0;
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use prusti_contracts::*;
+
+trait Counter {
+    fn incr(&mut self);
+}
+
+fn incr_twice(c: &mut dyn Counter) { //~ ERROR mutable references to trait objects are not supported
+    c.incr();
+    c.incr();
+}
+
+fn main() {}
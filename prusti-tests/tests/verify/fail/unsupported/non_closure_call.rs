@@ -1,5 +1,5 @@
 pub fn max_by_key<A, B: Ord>(a: A, b: A, key: impl Fn(&A) -> B) -> A {
-    if key(&a) > key(&b) { //~ Error: only calls to closures are supported
+    if key(&a) > key(&b) { //~ Error: calls of a closure argument are only supported if the precondition specifies it with a spec entailment
         a
     } else {
         b
@@ -0,0 +1,22 @@
+use prusti_contracts::*;
+
+fn main() {}
+
+#[ensures(result == a[0])]
+fn first(a: [u32; 4]) -> u32 {
+    match a {
+        [x, ..] => x,
+    }
+}
+
+#[ensures(result == a[1])]
+fn rest(a: [u32; 4]) -> u32 {
+    let [_, tail @ ..] = a;
+    tail[0]
+}
+
+#[ensures(result == a[1] + a[2])]
+fn middle(a: [u32; 4]) -> u32 {
+    let [_, middle @ .., _] = a;
+    middle[0] + middle[1]
+}
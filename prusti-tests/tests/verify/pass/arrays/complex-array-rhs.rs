@@ -0,0 +1,6 @@
+fn main() {
+    let mut a = [0; 3];
+    a[0] += 1;
+    assert!(a[0] == 1);
+    assert!(a[1] == 0);
+}
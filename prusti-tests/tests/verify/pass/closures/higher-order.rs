@@ -0,0 +1,83 @@
+use prusti_contracts::*;
+
+#[requires(f |= |x: i32| [requires(x >= 0), ensures(result >= x)])]
+#[requires(a >= 0)]
+#[ensures(result >= a)]
+fn apply<F: Fn(i32) -> i32>(f: F, a: i32) -> i32 {
+    f(a)
+}
+
+#[requires(f |= |x: i32| [requires(x >= 0), ensures(result == x + 1)])]
+#[requires(a >= 0)]
+#[ensures(result == a + 2)]
+fn apply_twice<F: FnMut(i32) -> i32>(mut f: F, a: i32) -> i32 {
+    let b = f(a);
+    f(b)
+}
+
+#[requires(f |= |x: i32| [requires(x > 0), ensures(result == 2 * x)])]
+#[ensures(result == 10)]
+fn apply_once<F: FnOnce(i32) -> i32>(f: F) -> i32 {
+    f(5)
+}
+
+#[requires(f |= |x: i32| [ensures(result == x)])]
+#[ensures(result == a)]
+fn apply_fn_ptr(f: fn(i32) -> i32, a: i32) -> i32 {
+    f(a)
+}
+
+#[requires(f |= |x: i32| [ensures(result == (x > 0))])]
+#[ensures(result == (a > 0))]
+fn test_fn_ptr(f: fn(i32) -> bool, a: i32) -> bool {
+    f(a)
+}
+
+#[requires(f |= |x: i32| [ensures(result ==> x > 0)])]
+fn check_positive<F: Fn(i32) -> bool>(f: F, a: i32) {
+    if f(a) {
+        assert!(a > 0);
+    }
+}
+
+// The function pointer is reified in another block than the one of the call
+fn apply_later(a: i32) {
+    let f: fn(i32) -> i32 = identity;
+    if a > 0 {
+        let r = apply_fn_ptr(f, a);
+        assert!(r == a);
+    }
+}
+
+#[requires(x >= 0)]
+#[ensures(result == x + 1)]
+fn inc(x: i32) -> i32 {
+    x + 1
+}
+
+#[requires(x > 0)]
+#[ensures(result == 2 * x)]
+fn double(x: i32) -> i32 {
+    2 * x
+}
+
+#[ensures(result == x)]
+fn identity(x: i32) -> i32 {
+    x
+}
+
+#[ensures(result == (x > 0))]
+fn is_positive(x: i32) -> bool {
+    x > 0
+}
+
+fn main() {
+    apply(inc, 3);
+    apply_twice(inc, 3);
+    apply_once(double);
+    let r = apply_fn_ptr(identity, 3);
+    assert!(r == 3);
+    assert!(test_fn_ptr(is_positive, 3));
+    check_positive(is_positive, 3);
+    apply_later(3);
+}
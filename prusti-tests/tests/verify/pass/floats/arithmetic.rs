@@ -0,0 +1,35 @@
+use prusti_contracts::*;
+
+fn constants() {
+    let x = 1.0f64;
+    let y = 2.0f64;
+    assert!(x < y);
+    assert!(x + y == 3.0);
+    assert!(y * y - x == 3.0);
+    assert!(-y < x);
+}
+
+#[requires(a < b)]
+#[ensures(!(b <= a))]
+fn ordering(a: f32, b: f32) {}
+
+#[requires(a < b && b < c)]
+#[ensures(a < c)]
+fn transitive(a: f64, b: f64, c: f64) {}
+
+#[requires(a < b)]
+#[ensures(result)]
+fn not_nan(a: f64, b: f64) -> bool {
+    a == a && b == b
+}
+
+fn casts() {
+    let x = 7u8 as f32;
+    assert!(x == 7.0);
+    let y = x as f64;
+    assert!(y == 7.0);
+    let z = 100.0f64 as u8;
+    assert!(z == 100);
+}
+
+fn main() {}
@@ -0,0 +1,4 @@
+fn main() {
+    let a = [1.0];
+    assert!(a[0] == 1.0);
+}
@@ -0,0 +1,58 @@
+use prusti_contracts::*;
+
+trait Container {
+    type Item: Copy;
+
+    #[pure]
+    fn get(&self) -> Self::Item;
+}
+
+struct Counter {
+    count: u32,
+}
+
+impl Container for Counter {
+    type Item = u32;
+
+    #[pure]
+    fn get(&self) -> u32 {
+        self.count
+    }
+}
+
+struct Holder<C: Container> {
+    item: C::Item,
+}
+
+#[ensures(result == c.get())]
+fn get_item<C: Container>(c: &C) -> C::Item {
+    c.get()
+}
+
+#[ensures(result == c.get())]
+fn get_twice<C: Container>(c: &C) -> <C as Container>::Item {
+    let first = get_item(c);
+    let _second = get_item(c);
+    first
+}
+
+#[ensures(result.item == item)]
+fn hold<C: Container>(item: C::Item) -> Holder<C> {
+    Holder { item }
+}
+
+#[requires(c.count < 100)]
+#[ensures(result == c.count + 1)]
+fn next(c: &Counter) -> <Counter as Container>::Item {
+    c.count + 1
+}
+
+fn client() {
+    let c = Counter { count: 3 };
+    let x = next(&c);
+    assert!(x == 4);
+    let h: Holder<Counter> = hold(x);
+    assert!(h.item == 4);
+}
+
+fn main() {}
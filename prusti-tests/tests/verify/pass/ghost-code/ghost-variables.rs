@@ -0,0 +1,66 @@
+use prusti_contracts::*;
+
+#[pure]
+fn double(x: usize) -> usize {
+    x + x
+}
+
+fn history(n: usize) -> usize {
+    let mut i = 0;
+    ghost! {
+        let mut visited = Seq::empty();
+    }
+    while i < n {
+        body_invariant!(i < n);
+        body_invariant!(visited.len() == i);
+        body_invariant!(i == 0 || visited.lookup(i - 1) == i - 1);
+        ghost! {
+            visited = visited.push(i);
+        }
+        i += 1;
+    }
+    prusti_assert!(visited.len() == n);
+    prusti_assert!(n == 0 || visited.lookup(n - 1) == n - 1);
+    i
+}
+
+fn counter(n: usize) -> usize {
+    let mut i = 0;
+    ghost! {
+        let mut steps = 0;
+    }
+    while i < n {
+        body_invariant!(i < n);
+        body_invariant!(steps == i);
+        i += 1;
+        ghost! {
+            steps += 1;
+        }
+    }
+    prusti_assert!(steps == i);
+    i
+}
+
+fn seen(n: usize) {
+    let mut i = 0;
+    ghost! {
+        let mut seen = Set::empty();
+    }
+    while i < n {
+        body_invariant!(i < n);
+        body_invariant!(i == 0 || seen.contains(i - 1));
+        ghost! {
+            seen = seen.insert(i);
+        }
+        i += 1;
+    }
+}
+
+fn pure_calls(x: usize) {
+    ghost! {
+        let doubled = double(x);
+    }
+    prusti_assert!(doubled == x + x);
+}
+
+fn main() {}
@@ -0,0 +1,76 @@
+use prusti_contracts::*;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[ensures(Seq::<i32>::empty().len() == 0)]
+#[ensures(Seq::<i32>::empty().is_empty())]
+#[ensures(Seq::single(x).lookup(0) == x)]
+#[ensures(Seq::single(x).push(y).len() == 2)]
+#[ensures(Seq::single(x).push(y).lookup(1) == y)]
+#[ensures(Seq::single(x).concat(Seq::single(y)) == Seq::single(x).push(y))]
+#[ensures(Seq::single(x).push(y).contains(y))]
+fn literals(x: i32, y: i32) {}
+
+#[ensures(Seq::single(p).lookup(0) == p)]
+fn structs(p: Point) {}
+
+#[pure]
+#[requires(s.len() > 0)]
+#[ensures(result.len() == s.len() + 1)]
+#[ensures(result.lookup(0) == s.lookup(0))]
+#[ensures(result.lookup(s.len()) == x)]
+fn append(s: Seq<i32>, x: i32) -> Seq<i32> {
+    s.push(x)
+}
+
+#[pure]
+#[ensures(result == s.concat(t))]
+fn concat(s: Seq<u32>, t: Seq<u32>) -> Seq<u32> {
+    s.concat(t)
+}
+
+struct VecWrapper {
+    v: Vec<i32>,
+}
+
+impl VecWrapper {
+    #[trusted]
+    #[ensures(result.model() == Seq::empty())]
+    fn new() -> Self {
+        VecWrapper { v: Vec::new() }
+    }
+
+    #[trusted]
+    #[pure]
+    fn model(&self) -> Seq<i32> {
+        unimplemented!()
+    }
+
+    #[trusted]
+    #[ensures(self.model() == old(self.model()).push(value))]
+    fn push(&mut self, value: i32) {
+        self.v.push(value);
+    }
+
+    #[trusted]
+    #[pure]
+    #[requires(index < self.model().len())]
+    #[ensures(result == self.model().lookup(index))]
+    fn lookup(&self, index: usize) -> i32 {
+        self.v[index]
+    }
+}
+
+fn client() {
+    let mut w = VecWrapper::new();
+    w.push(1);
+    w.push(2);
+    assert!(w.lookup(0) == 1);
+    assert!(w.lookup(1) == 2);
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+use prusti_contracts::*;
+
+#[ensures(Set::<u32>::empty().is_empty())]
+#[ensures(Set::single(x).contains(x))]
+#[ensures(Set::single(x).len() == 1)]
+#[ensures(Set::single(x).insert(y).contains(y))]
+#[ensures(Set::single(x).insert(x) == Set::single(x))]
+#[ensures(Set::single(x).is_subset(Set::single(x).insert(y)))]
+#[ensures(Set::single(x).union(Set::single(y)) == Set::single(y).insert(x))]
+#[ensures(Set::single(x).intersection(Set::empty()).is_empty())]
+#[ensures(Set::single(x).difference(Set::single(x)) == Set::empty())]
+fn sets(x: u32, y: u32) {}
+
+#[ensures(Map::<u32, i32>::empty().is_empty())]
+#[ensures(!Map::<u32, i32>::empty().contains_key(k))]
+#[ensures(Map::single(k, v).contains_key(k))]
+#[ensures(Map::single(k, v).lookup(k) == v)]
+#[ensures(Map::single(k, v).insert(k, w).lookup(k) == w)]
+#[ensures(Map::single(k, v).insert(k, w) == Map::single(k, w))]
+fn maps(k: u32, v: i32, w: i32) {}
+
+#[pure]
+#[requires(m.contains_key(k))]
+#[ensures(result.lookup(k) == m.lookup(k) + 1)]
+fn increment(m: Map<u32, i64>, k: u32) -> Map<u32, i64> {
+    m.insert(k, m.lookup(k) + 1)
+}
+
+fn main() {}
@@ -0,0 +1,28 @@
+use prusti_contracts::*;
+
+#[requires(x > 0)]
+fn test_args(x: i32) {
+    prusti_assert!(x > 0);
+    prusti_assert!(x >= 1);
+}
+
+fn test_locals() {
+    let mut x = 3;
+    prusti_assert!(x == 3);
+    x += 2;
+    prusti_assert!(x == 5);
+    let y = x * 2;
+    prusti_assert!(y == 10 && x == 5);
+}
+
+fn test_loop() {
+    let mut i = 0;
+    while i < 10 {
+        body_invariant!(i < 10);
+        prusti_assert!(i >= 0);
+        i += 1;
+    }
+    prusti_assert!(i == 10);
+}
+
+fn main() {}
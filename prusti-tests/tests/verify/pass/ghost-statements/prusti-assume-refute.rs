@@ -0,0 +1,24 @@
+use prusti_contracts::*;
+
+fn test_assume(x: u32) {
+    prusti_assume!(x > 10);
+    assert!(x > 5);
+}
+
+fn test_refute(x: u32) {
+    prusti_refute!(x == 0);
+    prusti_refute!(x > 5);
+    if x > 5 {
+        prusti_refute!(false);
+    }
+    // A failing refutation does not affect the rest of the method
+    prusti_assert!(x <= 5 || x > 5);
+}
+
+fn test_refute_in_branch(b: bool) {
+    let x = if b { 1 } else { 2 };
+    prusti_refute!(x == 1);
+    prusti_refute!(x == 2);
+}
+
+fn main() {}
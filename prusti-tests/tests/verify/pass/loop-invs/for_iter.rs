@@ -1,8 +1,5 @@
 use prusti_contracts::*;
 
-// ignore-test Unsupported loop. We don't yet generate magic wands in loop invariants, which are
-// required when a loan is created before, and expires after, the loop invariant.
-
 fn test1() {
     let mut sum = 0;
     for i in 0..128 {
@@ -0,0 +1,67 @@
+use prusti_contracts::*;
+
+struct Grid {
+    a: i32,
+    b: i32,
+}
+
+/// The reference is created in the last iteration of the loop and used after it.
+#[ensures(grid.a == 5)]
+fn set_selected(grid: &mut Grid, n: u32) {
+    let mut i = 0;
+    let selected: &mut i32;
+    loop {
+        if i >= n {
+            selected = &mut grid.a;
+            break;
+        }
+        i += 1;
+    }
+    *selected = 5;
+}
+
+/// References created in two different loops are alive after both loops.
+#[ensures(grid.a == 5 && grid.b == 6)]
+fn set_both(grid: &mut Grid, n: u32) {
+    let mut i = 0;
+    let first: &mut i32;
+    loop {
+        if i >= n {
+            first = &mut grid.a;
+            break;
+        }
+        i += 1;
+    }
+    let mut j = 0;
+    let second: &mut i32;
+    loop {
+        if j >= n {
+            second = &mut grid.b;
+            break;
+        }
+        j += 1;
+    }
+    *first = 5;
+    *second = 6;
+}
+
+/// The reference is created in the inner one of two nested loops.
+#[ensures(grid.b == 7)]
+fn set_nested(grid: &mut Grid, n: u32) {
+    let mut i = 0;
+    let selected: &mut i32;
+    'outer: loop {
+        let mut j = 0;
+        while j < n {
+            if i + j >= n {
+                selected = &mut grid.b;
+                break 'outer;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    *selected = 7;
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+use prusti_contracts::*;
+use std::ptr;
+
+fn main() {}
+
+fn null_pointer() {
+    let p: *const i32 = ptr::null();
+    assert!(p.is_null());
+    let q: *mut i32 = ptr::null_mut();
+    assert!(q.is_null());
+}
+
+#[requires(!p.is_null())]
+fn offset_is_not_null(p: *const i32) {
+    let q = unsafe { p.add(1) };
+    assert!(!q.is_null());
+}
+
+#[ensures(result == p)]
+fn zero_offset(p: *const u8) -> *const u8 {
+    unsafe { p.offset(0) }
+}
+
+fn coercion() {
+    let _: *const i32 = ptr::null_mut();
+}
@@ -0,0 +1,40 @@
+use prusti_contracts::*;
+
+fn main() {}
+
+#[requires(own(p))]
+#[ensures(own(p))]
+#[ensures(unsafe { *p } == 5)]
+fn set_five(p: *mut i32) {
+    unsafe { std::ptr::write(p, 5); }
+}
+
+#[requires(own(p))]
+#[ensures(own(p))]
+#[ensures(result == unsafe { *p })]
+fn get(p: *const i32) -> i32 {
+    unsafe { std::ptr::read(p) }
+}
+
+#[requires(own(p))]
+#[requires(unsafe { *p } < 100)]
+#[ensures(own(p))]
+#[ensures(unsafe { *p } == old(unsafe { *p }) + 1)]
+fn increment(p: *mut i32) {
+    unsafe { *p += 1; }
+}
+
+fn set_through_reference(x: &mut i32) {
+    let p = x as *mut i32;
+    set_five(p);
+    assert!(*x == 5);
+}
+
+#[ensures(result == 6)]
+fn call_sequence() -> i32 {
+    let mut x = 0;
+    let p = &mut x as *mut i32;
+    set_five(p);
+    increment(p);
+    get(p)
+}
@@ -0,0 +1,30 @@
+use prusti_contracts::*;
+
+#[prusti::skip]
+fn skipped() {
+    let closure = || assert!(false);
+    closure();
+}
+
+#[prusti::skip]
+mod skipped_module {
+    pub fn also_skipped(x: u32) -> u32 {
+        x + 1
+    }
+}
+
+struct T;
+
+#[prusti::skip]
+impl T {
+    fn skipped_method(&self) {
+        unreachable!();
+    }
+}
+
+#[ensures(result == 5)]
+fn verified() -> u32 {
+    5
+}
+
+fn main() {}
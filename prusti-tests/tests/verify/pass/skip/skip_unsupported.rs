@@ -0,0 +1,24 @@
+// compile-flags: -Pskip_unsupported_features=true -Preport_support_status=true
+
+use prusti_contracts::*;
+
+union Foo {
+    a: u32,
+    b: i32,
+}
+
+fn uses_union() -> Foo {
+    Foo { a: 0 }
+}
+
+#[pure]
+fn uses_function_pointer(f: fn() -> u32) -> u32 {
+    f()
+}
+
+#[ensures(result == 3)]
+fn supported() -> u32 {
+    3
+}
+
+fn main() {}
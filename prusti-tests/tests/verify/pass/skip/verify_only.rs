@@ -0,0 +1,26 @@
+// compile-flags: -Pverify_only=crate::checked::*,crate::main
+
+use prusti_contracts::*;
+
+mod checked {
+    use prusti_contracts::*;
+
+    #[ensures(result == x)]
+    pub fn identity(x: u32) -> u32 {
+        x
+    }
+}
+
+mod unchecked {
+    pub fn fails() {
+        assert!(false);
+    }
+}
+
+fn also_fails() {
+    unreachable!();
+}
+
+fn main() {
+    assert!(checked::identity(3) == 3);
+}
@@ -0,0 +1,26 @@
+use prusti_contracts::*;
+
+fn main() {}
+
+#[ensures(forall(|j: usize| (0 <= j && j < s.len()) ==> s[j] == 0))]
+fn zero_all(s: &mut [i32]) {
+    let mut i = 0;
+    while i < s.len() {
+        body_invariant!(i < s.len());
+        body_invariant!(forall(|j: usize| (0 <= j && j < i) ==> s[j] == 0));
+        s[i] = 0;
+        i += 1;
+    }
+}
+
+#[requires(s.len() > 0)]
+fn read_in_loop(s: &[i32]) -> i32 {
+    let mut i = 0;
+    let mut last = 0;
+    while i < s.len() {
+        body_invariant!(i < s.len());
+        last = s[i];
+        i += 1;
+    }
+    last
+}
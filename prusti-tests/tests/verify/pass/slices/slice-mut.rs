@@ -0,0 +1,35 @@
+use prusti_contracts::*;
+
+fn main() {}
+
+#[requires(s.len() > 1)]
+#[ensures(s.len() == old(s.len()))]
+#[ensures(s[0] == 1)]
+#[ensures(s[1] == old(s[1]))]
+fn set_first(s: &mut [i32]) {
+    s[0] = 1;
+}
+
+#[requires(s.len() > 1)]
+#[requires(s[1] < 100)]
+#[ensures(s[1] == old(s[1]) + 1)]
+fn increment_second(s: &mut [i32]) {
+    s[1] += 1;
+}
+
+#[requires(s.len() > 2)]
+#[ensures(s[2] == 5)]
+#[ensures(s[0] == old(s[0]))]
+fn set_through_borrow(s: &mut [i32]) {
+    let x = &mut s[2];
+    *x = 5;
+}
+
+#[requires(s.len() > 1)]
+fn swap_first_two(s: &mut [i32]) {
+    let a = s[0];
+    let b = s[1];
+    s[0] = b;
+    s[1] = a;
+    assert!(s[0] == b && s[1] == a);
+}
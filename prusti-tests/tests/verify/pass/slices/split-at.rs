@@ -0,0 +1,25 @@
+use prusti_contracts::*;
+
+fn main() {}
+
+#[ensures(result == s.len())]
+fn halves(s: &[u32]) -> usize {
+    let (left, right) = s.split_at(s.len() / 2);
+    left.len() + right.len()
+}
+
+#[requires(s.len() > 3)]
+#[ensures(result == s[0] + s[3])]
+fn first_of_halves(s: &[i32]) -> i32 {
+    let (left, right) = s.split_at(3);
+    assert!(left.len() == 3);
+    assert!(right.len() == s.len() - 3);
+    left[0] + right[0]
+}
+
+#[requires(mid <= s.len())]
+fn generic_split<T>(s: &[T], mid: usize) -> usize {
+    let (left, right) = s.split_at(mid);
+    assert!(left.len() == mid);
+    right.len()
+}
@@ -0,0 +1,34 @@
+use prusti_contracts::*;
+
+fn main() {}
+
+#[requires(s.len() > 1)]
+#[ensures(result == s[0] + s[s.len() - 1])]
+fn first_plus_last(s: &[i32]) -> i32 {
+    match s {
+        [first, .., last] => *first + *last,
+        _ => unreachable!(),
+    }
+}
+
+#[requires(s.len() > 2)]
+#[ensures(result == s[2])]
+fn second_of_rest(s: &[i32]) -> i32 {
+    match s {
+        [_, rest @ ..] => {
+            assert!(rest.len() == s.len() - 1);
+            rest[1]
+        }
+        [] => unreachable!(),
+    }
+}
+
+#[requires(s.len() > 2)]
+#[ensures(s[0] == old(s[0]))]
+#[ensures(s[1] == 7)]
+#[ensures(s.len() == old(s.len()))]
+fn write_to_rest(s: &mut [i32]) {
+    if let [_, rest @ ..] = s {
+        rest[0] = 7;
+    }
+}
@@ -0,0 +1,34 @@
+use prusti_contracts::*;
+
+fn main() {}
+
+// The contract does not mention the string at all.
+#[ensures(result == 42)]
+fn ignore(_s: &str) -> i32 {
+    42
+}
+
+#[ensures(result == s.len())]
+fn length(s: &str) -> usize {
+    s.len()
+}
+
+#[ensures(result == (s.len() == 0))]
+fn empty(s: &str) -> bool {
+    s.is_empty()
+}
+
+#[pure]
+#[requires(!s.is_empty())]
+fn first_byte(s: &str) -> u8 {
+    s.as_bytes()[0]
+}
+
+fn literals() {
+    let s = "hello";
+    assert!(s.len() == 5);
+    assert!(!s.is_empty());
+    assert!(s == "hello");
+    assert!(s != "world");
+    assert!(first_byte(s) == 104);
+}
@@ -0,0 +1,27 @@
+use prusti_contracts::*;
+
+fn main() {}
+
+#[ensures(result == s.len())]
+fn string_len(s: &String) -> usize {
+    s.len()
+}
+
+#[ensures(result == s.is_empty())]
+fn string_is_empty(s: &String) -> bool {
+    s.len() == 0
+}
+
+#[pure]
+fn same_contents(a: &String, b: &str) -> bool {
+    a.as_str() == b
+}
+
+#[requires(same_contents(a, b))]
+#[ensures(a.len() == b.len())]
+fn same_len(a: &String, b: &str) {}
+
+fn moves(s: String) -> String {
+    let t = s;
+    t
+}
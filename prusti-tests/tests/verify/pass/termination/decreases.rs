@@ -0,0 +1,40 @@
+use prusti_contracts::*;
+
+#[pure]
+#[requires(n >= 0)]
+#[decreases(n)]
+fn sum(n: i32) -> i32 {
+    if n == 0 { 0 } else { sum(n - 1) + n }
+}
+
+#[pure]
+#[requires(a >= 0 && b >= 0)]
+#[decreases(a + b)]
+fn ackermann_like(a: i32, b: i32) -> i32 {
+    if a == 0 {
+        b
+    } else if b == 0 {
+        ackermann_like(a - 1, 1)
+    } else {
+        ackermann_like(a, b - 1)
+    }
+}
+
+#[requires(n >= 0)]
+#[decreases(n)]
+fn count_down(n: i32) {
+    if n > 0 {
+        count_down(n - 1);
+    }
+}
+
+fn loop_variant() {
+    let mut i = 10;
+    while i > 0 {
+        body_invariant!(i > 0);
+        body_variant!(i);
+        i -= 1;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+use prusti_contracts::*;
+
+#[pure]
+#[requires(n >= 0)]
+#[decreases(n)]
+fn is_even(n: i32) -> bool {
+    if n == 0 { true } else { is_odd(n - 1) }
+}
+
+#[pure]
+#[requires(n >= 0)]
+#[decreases(n)]
+fn is_odd(n: i32) -> bool {
+    if n == 0 { false } else { is_even(n - 1) }
+}
+
+#[requires(n >= 0)]
+#[decreases(n)]
+fn ping(n: i32) {
+    if n > 0 {
+        pong(n - 1);
+    }
+}
+
+#[requires(n >= 0)]
+#[decreases(n)]
+fn pong(n: i32) {
+    if n > 0 {
+        ping(n - 1);
+    }
+}
+
+fn main() {}
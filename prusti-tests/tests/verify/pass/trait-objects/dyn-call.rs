@@ -0,0 +1,52 @@
+use prusti_contracts::*;
+
+trait Shape {
+    #[pure]
+    fn sides(&self) -> u32;
+
+    #[requires(scale > 0)]
+    #[ensures(result >= self.sides())]
+    fn perimeter(&self, scale: u32) -> u32;
+}
+
+struct Square {
+    side: u32,
+}
+
+impl Shape for Square {
+    #[pure]
+    fn sides(&self) -> u32 {
+        4
+    }
+
+    #[requires(scale > 0)]
+    #[ensures(result >= self.sides())]
+    fn perimeter(&self, scale: u32) -> u32 {
+        4 * scale
+    }
+}
+
+#[requires(s.sides() > 2)]
+#[ensures(result > 2)]
+fn through_ref(s: &dyn Shape) -> u32 {
+    s.perimeter(1)
+}
+
+fn through_box(s: Box<dyn Shape>) -> u32 {
+    let p = s.perimeter(2);
+    assert!(p >= s.sides());
+    p
+}
+
+fn make() -> Box<dyn Shape> {
+    Box::new(Square { side: 3 })
+}
+
+fn unsize_ref() {
+    let sq = Square { side: 1 };
+    let s: &dyn Shape = &sq;
+    let p = s.perimeter(3);
+    assert!(p >= s.sides());
+}
+
+fn main() {}
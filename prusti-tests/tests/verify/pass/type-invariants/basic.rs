@@ -0,0 +1,54 @@
+use prusti_contracts::*;
+
+#[invariant(self.value <= 100)]
+struct Percentage {
+    value: u8,
+}
+
+impl Percentage {
+    #[requires(value <= 100)]
+    pub fn new(value: u8) -> Self {
+        Percentage { value }
+    }
+
+    pub fn incr(&mut self) {
+        assert!(self.value <= 100);
+        if self.value < 100 {
+            self.value += 1;
+        }
+    }
+
+    pub fn get(&self) -> u8 {
+        assert!(self.value <= 100);
+        self.value
+    }
+
+    // The invariant is assumed whenever the fields of the value are accessed
+    fn get_private(&self) -> u8 {
+        assert!(self.value <= 100);
+        self.value
+    }
+
+    // Private functions are allowed to temporarily break the invariant
+    fn overflow(&mut self) {
+        self.value = 200;
+    }
+
+    pub fn reset(&mut self) {
+        self.overflow();
+        self.value = 0;
+    }
+}
+
+#[requires(x <= 100)]
+fn test(x: u8) {
+    let mut perc = Percentage::new(x);
+    perc.incr();
+    assert!(perc.value <= 100);
+    let value = perc.get();
+    assert!(value <= 100);
+    let value = perc.get_private();
+    assert!(value <= 100);
+}
+
+fn main() {}
@@ -0,0 +1,62 @@
+use prusti_contracts::*;
+
+#[invariant(self.len <= self.cap)]
+struct Buffer<T> {
+    data: T,
+    len: usize,
+    cap: usize,
+}
+
+impl<T> Buffer<T> {
+    #[requires(cap > 0)]
+    pub fn new(data: T, cap: usize) -> Self {
+        Buffer { data, len: 0, cap }
+    }
+
+    pub fn push(&mut self) {
+        if self.len < self.cap {
+            self.len += 1;
+        }
+    }
+
+    pub fn free_space(&self) -> usize {
+        self.cap - self.len
+    }
+}
+
+#[invariant(match self { Shape::Square(side) => side > 0, Shape::Empty => true })]
+enum Shape {
+    Square(i32),
+    Empty,
+}
+
+impl Shape {
+    #[requires(side > 0)]
+    pub fn square(side: i32) -> Self {
+        Shape::Square(side)
+    }
+}
+
+#[pure]
+fn is_positive_square(shape: &Shape) -> bool {
+    match shape {
+        Shape::Square(side) => *side > 0,
+        Shape::Empty => true,
+    }
+}
+
+pub fn check_shape(shape: &Shape) {
+    assert!(is_positive_square(shape));
+}
+
+fn test() {
+    let mut buffer = Buffer::new(true, 2);
+    buffer.push();
+    buffer.push();
+    buffer.push();
+    let _ = buffer.free_space();
+    let shape = Shape::square(3);
+    check_shape(&shape);
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+use prusti_contracts::*;
+
+#[ensures(result == x % 8)] //~ ERROR postcondition might not hold
+fn wrong_mask(x: u32) -> u32 {
+    x & 0xF
+}
+
+#[ensures(result <= x)] //~ ERROR postcondition might not hold
+fn or_is_not_smaller(x: u32, y: u32) -> u32 {
+    x | y
+}
+
+#[ensures(result == x * 8)] //~ ERROR postcondition might not hold
+fn shl_wraps(x: u32) -> u32 {
+    x << 3
+}
+
+fn shr_rounds_down() {
+    let x = -7i32;
+    assert!(x >> 1 == -3); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
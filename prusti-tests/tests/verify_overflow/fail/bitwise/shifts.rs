@@ -0,0 +1,17 @@
+use prusti_contracts::*;
+
+fn shl(x: u32, s: u32) -> u32 {
+    x << s //~ ERROR assertion might fail with "attempt to shift left with overflow"
+}
+
+#[requires(s < 64)]
+fn shr(x: i64, s: i32) -> i64 {
+    x >> s //~ ERROR assertion might fail with "attempt to shift right with overflow"
+}
+
+#[requires(s <= 8)]
+fn shl_by_bits(x: u8, s: u8) -> u8 {
+    x << s //~ ERROR assertion might fail with "attempt to shift left with overflow"
+}
+
+fn main() {}
@@ -0,0 +1,47 @@
+use prusti_contracts::*;
+
+#[ensures(result == 5)]
+fn constants() -> u32 {
+    0b0111 & 0b1101
+}
+
+#[ensures(result == x % 16)]
+fn low_bits(x: u32) -> u32 {
+    x & 0xF
+}
+
+#[pure]
+fn is_even(x: u64) -> bool {
+    x & 1 == 0
+}
+
+#[requires(x % 2 == 0)]
+#[ensures(is_even(x))]
+fn even(x: u64) {}
+
+#[ensures(result == y & x)]
+#[ensures(result <= x && result <= y)]
+fn and_bounds(x: u32, y: u32) -> u32 {
+    x & y
+}
+
+#[ensures(result >= x && result >= y)]
+#[ensures(result <= x + y)]
+fn or_bounds(x: u16, y: u16) -> u16 {
+    x | y
+}
+
+#[ensures(result == 0)]
+fn xor_self(x: i64) -> i64 {
+    x ^ x
+}
+
+#[ensures(result == x)]
+fn identities(x: i8) -> i8 {
+    let a = x & x;
+    let b = a | 0;
+    let c = b & -1;
+    c ^ 0
+}
+
+fn main() {}
@@ -0,0 +1,53 @@
+use prusti_contracts::*;
+
+#[requires(x < 1000)]
+#[ensures(result == x * 8)]
+fn shl_const(x: u32) -> u32 {
+    x << 3
+}
+
+#[ensures(result == 0)]
+fn shl_out(x: u8) -> u8 {
+    (x & 1) << 7 << 1
+}
+
+#[ensures(result == -128)]
+fn shl_into_sign_bit() -> i8 {
+    1 << 7
+}
+
+#[ensures(result == x / 4)]
+fn shr_const(x: u32) -> u32 {
+    x >> 2
+}
+
+#[ensures(result == -1)]
+fn shr_negative() -> i32 {
+    let x = -7;
+    x >> 3
+}
+
+#[requires(s == 2)]
+#[ensures(result == x / 4)]
+fn shr_symbolic(x: u64, s: u32) -> u64 {
+    x >> s
+}
+
+#[requires(s < 32)]
+fn shl_checked(x: u32, s: u32) -> u32 {
+    x << s
+}
+
+#[requires(s >= 0 && s < 64)]
+fn shr_checked(x: i64, s: i32) -> i64 {
+    x >> s
+}
+
+#[pure]
+#[ensures(result == x * 2)]
+#[requires(x < 128)]
+fn double(x: u8) -> u8 {
+    x << 1
+}
+
+fn main() {}
@@ -0,0 +1,37 @@
+use prusti_contracts::*;
+
+use std::ops::Range;
+
+fn wrong_iterator_state(n: usize) {
+    for i in 0..n {
+        body_invariant!(|it: Range<usize>| it.end == n + 1); //~ ERROR loop invariant might not hold in the first loop iteration
+    }
+}
+
+fn wrong_bound(n: usize) {
+    for i in 0..n {
+        body_invariant!(|it: Range<usize>| it.start == i + 1 && it.end == n);
+        assert!(i + 1 < n); //~ ERROR the asserted expression might not hold
+    }
+}
+
+fn wrong_iterator_type(v: &[u32]) {
+    for i in 0..v.len() {
+        body_invariant!(|it: Range<u32>| it.end > 0); //~ ERROR the iterator argument of the loop invariant has type
+    }
+}
+
+fn wrong_visited(n: usize) {
+    ghost! {
+        let mut visited = Seq::empty();
+    }
+    for i in 0..n {
+        body_invariant!(|it: Range<usize>| it.start == i + 1 && it.end == n);
+        body_invariant!(visited.len() == i + 1); //~ ERROR loop invariant might not hold in the first loop iteration
+        ghost! {
+            visited = visited.push(i);
+        }
+    }
+}
+
+fn main() {}
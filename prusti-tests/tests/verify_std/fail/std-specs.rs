@@ -0,0 +1,24 @@
+use prusti_contracts::*;
+
+fn unwrap_none() {
+    let x: Option<i32> = None;
+    x.unwrap(); //~ ERROR precondition might not hold
+}
+
+fn unwrap_err(r: Result<i32, i32>) -> i32 {
+    r.unwrap() //~ ERROR precondition might not hold
+}
+
+fn remove_out_of_bounds(v: &mut Vec<i32>) {
+    v.push(1);
+    v.remove(1); //~ ERROR precondition might not hold
+}
+
+fn wrong_length() {
+    let mut v: Vec<i32> = Vec::new();
+    v.push(1);
+    v.pop();
+    assert!(v.len() == 1); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
@@ -0,0 +1,58 @@
+use prusti_contracts::*;
+
+use std::ops::Range;
+
+/// The iterator of the loop has visited the numbers `0..it.start` and will visit the numbers
+/// `it.start..it.end`.
+#[ensures(2 * result + n == n * n)]
+fn sum_below(n: usize) -> usize {
+    let mut sum = 0;
+    for i in 0..n {
+        body_invariant!(|it: Range<usize>| it.start == i + 1 && it.end == n);
+        body_invariant!(2 * sum + i == i * i);
+        sum += i;
+    }
+    sum
+}
+
+#[requires(a <= b)]
+#[ensures(result == b - a)]
+fn count_between(a: usize, b: usize) -> usize {
+    let mut count = 0;
+    let range = a..b;
+    for i in range {
+        body_invariant!(|it: Range<usize>| it.start == i + 1 && it.end == b);
+        body_invariant!(a <= i && count == i - a);
+        count += 1;
+    }
+    count
+}
+
+fn nested(n: usize) {
+    for i in 0..n {
+        body_invariant!(|it: Range<usize>| it.start == i + 1 && it.end == n);
+        for j in 0..i {
+            body_invariant!(|it: Range<usize>| it.start == j + 1 && it.end == i);
+            assert!(j < i && i < n);
+        }
+    }
+}
+
+/// The visited numbers are collected in a ghost sequence.
+fn visited(n: usize) {
+    ghost! {
+        let mut visited = Seq::empty();
+    }
+    for i in 0..n {
+        body_invariant!(|it: Range<usize>| it.start == i + 1 && it.end == n);
+        body_invariant!(visited.len() == i);
+        body_invariant!(i == 0 || visited.lookup(i - 1) == i - 1);
+        ghost! {
+            visited = visited.push(i);
+        }
+    }
+    prusti_assert!(visited.len() == n);
+    prusti_assert!(n == 0 || visited.lookup(n - 1) == n - 1);
+}
+
+fn main() {}
@@ -0,0 +1,31 @@
+use prusti_contracts::*;
+
+use std::slice::Iter;
+
+/// The iterator has visited the first `v.len() - it.len()` elements of the slice and will visit
+/// the remaining `it.len()` ones.
+#[ensures(result == v.len())]
+fn count(v: &[i32]) -> usize {
+    let mut count = 0;
+    for _ in v.iter() {
+        body_invariant!(|it: Iter<i32>| count + it.len() + 1 == v.len());
+        count += 1;
+    }
+    count
+}
+
+/// The visited elements are collected in a ghost sequence.
+fn visited(v: &[i32]) {
+    ghost! {
+        let mut visited = Seq::empty();
+    }
+    for x in v.iter() {
+        body_invariant!(|it: Iter<i32>| visited.len() + it.len() + 1 == v.len());
+        ghost! {
+            visited = visited.push(*x);
+        }
+    }
+    prusti_assert!(visited.len() == v.len());
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+use prusti_contracts::*;
+
+use std::vec::IntoIter;
+
+/// The iterator owns the `it.len()` elements that it has not produced yet.
+fn count(v: Vec<i32>) -> usize {
+    let n = v.len();
+    let mut count = 0;
+    for _ in v {
+        body_invariant!(|it: IntoIter<i32>| count + it.len() + 1 == n);
+        count += 1;
+    }
+    assert!(count == n);
+    count
+}
+
+/// The visited elements are collected in a ghost sequence.
+fn visited(v: Vec<i32>) {
+    let n = v.len();
+    ghost! {
+        let mut visited = Seq::empty();
+    }
+    for x in v {
+        body_invariant!(|it: IntoIter<i32>| visited.len() + it.len() + 1 == n);
+        ghost! {
+            visited = visited.push(x);
+        }
+    }
+    prusti_assert!(visited.len() == n);
+}
+
+fn main() {}
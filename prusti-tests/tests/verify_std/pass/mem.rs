@@ -0,0 +1,10 @@
+use prusti_contracts::*;
+
+fn main() {
+    let mut x = 5;
+    let mut y = 42;
+    std::mem::swap(&mut x, &mut y);
+    assert!(x == 42 && y == 5);
+    let previous = std::mem::replace(&mut x, 7);
+    assert!(previous == 42 && x == 7);
+}
@@ -0,0 +1,33 @@
+use prusti_contracts::*;
+
+fn option(x: Option<i32>) -> i32 {
+    let mut y = Some(3);
+    assert!(y.is_some());
+    let z = y.take();
+    assert!(y.is_none() && z.is_some());
+    assert!(y.replace(4).is_none());
+    assert!(y.as_ref().is_some());
+    assert!(y.or(None).is_some());
+    assert!(y.and(x).is_some() == x.is_some());
+    if x.is_some() {
+        x.unwrap() + y.unwrap()
+    } else {
+        z.expect("z was taken from a `Some`")
+    }
+}
+
+fn result(r: Result<u32, u32>) -> u32 {
+    let ok: Result<u32, u32> = Ok(1);
+    assert!(ok.is_ok() && !ok.is_err());
+    assert!(ok.ok().is_some());
+    assert!(r.and(ok).is_ok() == r.is_ok());
+    if r.is_err() {
+        assert!(r.err().is_some());
+        r.unwrap_err()
+    } else {
+        assert!(None::<u32>.ok_or(0u32).is_err());
+        r.unwrap()
+    }
+}
+
+fn main() {}
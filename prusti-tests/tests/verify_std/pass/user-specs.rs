@@ -0,0 +1,18 @@
+use prusti_contracts::*;
+
+// A specification written by the user replaces the bundled one instead of
+// being reported as a duplicate.
+#[extern_spec]
+impl<T> std::vec::Vec<T> {
+    #[ensures(result.len() == 0)]
+    #[ensures(result.capacity() == 0)]
+    pub fn new() -> std::vec::Vec::<T>;
+
+    #[pure]
+    pub fn capacity(&self) -> usize;
+}
+
+fn main() {
+    let v: Vec<i32> = Vec::new();
+    assert!(v.len() == 0 && v.capacity() == 0);
+}
@@ -0,0 +1,29 @@
+use prusti_contracts::*;
+
+#[requires(v.len() > 0)]
+#[ensures(v.len() == old(v.len()))]
+fn replace_first(v: &mut Vec<i32>, x: i32) {
+    v.remove(0);
+    v.insert(0, x);
+}
+
+fn main() {
+    let mut v = Vec::new();
+    assert!(v.is_empty());
+    v.push(1);
+    v.push(2);
+    v.push(3);
+    assert!(v.len() == 3);
+    replace_first(&mut v, 4);
+    v.pop();
+    assert!(v.len() == 2);
+    v.swap_remove(1);
+    v.truncate(5);
+    assert!(v.len() == 1);
+    let mut w = Vec::with_capacity(2);
+    w.push(5);
+    v.append(&mut w);
+    assert!(v.len() == 2 && w.is_empty());
+    v.clear();
+    assert!(v.len() == 0);
+}
@@ -148,6 +148,8 @@ impl<'p, 'v: 'p, 'tcx: 'v> ArrayTypesEncoder<'tcx> {
         encoder: &'p Encoder<'v, 'tcx>,
         slice_ty_rs: ty::Ty<'tcx>,
     ) -> EncodingResult<EncodedSliceTypes<'tcx>> {
+        // strings are encoded as slices of bytes
+        let slice_ty_rs = encoder.string_slice_ty(slice_ty_rs).unwrap_or(slice_ty_rs);
         if let Some(cached) = self.slice_types_cache.get(&slice_ty_rs) {
             return Ok(cached.clone());
         }
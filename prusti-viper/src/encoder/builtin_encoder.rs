@@ -39,12 +39,58 @@ pub enum BuiltinFunctionKind {
         slice_ty_pred: String,
         elem_ty_pred: String,
     },
+    /// address of a raw pointer offset by a number of elements
+    PtrOffset {
+        elem_ty_pred: String,
+    },
 }
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub enum BuiltinDomainKind {
     Nat,
     Primitive,
+    /// IEEE 754 single precision floats (`f32`)
+    Float32,
+    /// IEEE 754 double precision floats (`f64`)
+    Float64,
+    /// Bitwise operations and shifts on integers with the given number of bits
+    Bitwise { bits: u32, signed: bool },
+}
+
+/// Uninterpreted functions of the float domains.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub enum FloatFunctionKind {
+    /// Rounds a mathematical integer to the nearest float.
+    FromInt,
+    /// Truncates a float towards zero. Unspecified for NaN and infinities.
+    ToInt,
+    /// Reinterprets a bit pattern as a float.
+    FromBits,
+    /// Converts a float of the other precision to the nearest float.
+    FromOther,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Neg,
+    Eq,
+    Lt,
+    Le,
+    IsNan,
+}
+
+/// Uninterpreted functions of the bitwise domains. All of them operate on mathematical
+/// integers that are in the range of the integer type of the domain.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub enum BitwiseFunctionKind {
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+    /// `2` to the power of a shift amount that is smaller than the number of bits.
+    Pow2,
 }
 
 pub struct BuiltinEncoder {}
@@ -91,6 +137,12 @@ impl BuiltinEncoder {
             BuiltinFunctionKind::Unreachable(vir::Type::Seq(_)) => {
                 "builtin$unreach_seq".to_string()
             }
+            BuiltinFunctionKind::Unreachable(vir::Type::Set(_)) => {
+                "builtin$unreach_set".to_string()
+            }
+            BuiltinFunctionKind::Unreachable(vir::Type::Map(..)) => {
+                "builtin$unreach_map".to_string()
+            }
             BuiltinFunctionKind::Undefined(vir::Type::Int) => "builtin$undef_int".to_string(),
             BuiltinFunctionKind::Undefined(vir::Type::Bool) => "builtin$undef_bool".to_string(),
             BuiltinFunctionKind::Undefined(vir::Type::TypedRef(_)) => "builtin$undef_ref".to_string(),
@@ -98,9 +150,12 @@ impl BuiltinEncoder {
             BuiltinFunctionKind::Undefined(vir::Type::Domain(_)) => "builtin$undef_doman".to_string(),
             BuiltinFunctionKind::Undefined(vir::Type::Snapshot(_)) => "builtin$undef_snap".to_string(),
             BuiltinFunctionKind::Undefined(vir::Type::Seq(_)) => "builtin$undef_seq".to_string(),
+            BuiltinFunctionKind::Undefined(vir::Type::Set(_)) => "builtin$undef_set".to_string(),
+            BuiltinFunctionKind::Undefined(vir::Type::Map(..)) => "builtin$undef_map".to_string(),
             BuiltinFunctionKind::ArrayLookupPure { .. }
             | BuiltinFunctionKind::SliceLookupPure { .. } => "lookup_pure".to_string(),
             BuiltinFunctionKind::SliceLen { .. } => "Slice$len".to_string(),
+            BuiltinFunctionKind::PtrOffset { .. } => "builtin$ptr_offset".to_string(),
         }
     }
 
@@ -215,6 +270,30 @@ impl BuiltinEncoder {
                     body: None,
                 }
             },
+            BuiltinFunctionKind::PtrOffset { elem_ty_pred } => {
+                let addr_var = vir::LocalVar::new_typed_ref("addr", elem_ty_pred.clone());
+                let count_var = vir_local!{ count: Int };
+                let result: vir::Expr = vir::LocalVar::new_typed_ref("__result", elem_ty_pred).into();
+                let addr: vir::Expr = addr_var.clone().into();
+                let null = vir::Expr::Const(vir::Const::Null, vir::Position::default());
+
+                // Offsets are only allowed within the same allocation, so apart from a zero
+                // offset being the identity we only know that they never produce a null pointer.
+                vir::Function {
+                    name: fn_name,
+                    formal_args: vec![
+                        addr_var,
+                        count_var.clone(),
+                    ],
+                    return_type: result.get_type().clone(),
+                    pres: vec![],
+                    posts: vec![
+                        vir!{ ([vir::Expr::local(count_var)] == [vir::Expr::from(0)]) ==> ([result] == [addr]) },
+                        vir!{ ([addr] != [null]) ==> ([result] != [null]) },
+                    ],
+                    body: None,
+                }
+            },
         }
     }
 
@@ -222,6 +301,726 @@ impl BuiltinEncoder {
         match kind {
             BuiltinDomainKind::Nat => self.encode_nat_builtin_domain(),
             BuiltinDomainKind::Primitive => self.encode_primitive_builtin_domain(),
+            BuiltinDomainKind::Float32 | BuiltinDomainKind::Float64 => {
+                self.encode_float_builtin_domain(kind)
+            }
+            BuiltinDomainKind::Bitwise { .. } => self.encode_bitwise_builtin_domain(kind),
+        }
+    }
+
+    pub fn encode_float_domain_name(&self, kind: &BuiltinDomainKind) -> &'static str {
+        match kind {
+            BuiltinDomainKind::Float32 => "Float32Domain",
+            BuiltinDomainKind::Float64 => "Float64Domain",
+            _ => unreachable!("{:?} is not a float domain", kind),
+        }
+    }
+
+    pub fn encode_float_domain_type(&self, kind: &BuiltinDomainKind) -> vir::Type {
+        vir::Type::Domain(self.encode_float_domain_name(kind).to_string())
+    }
+
+    /// Number of bits of the significand, including the implicit bit. Every integer whose
+    /// absolute value is at most `2^precision` is exactly representable.
+    fn float_precision(&self, kind: &BuiltinDomainKind) -> u32 {
+        match kind {
+            BuiltinDomainKind::Float32 => 24,
+            BuiltinDomainKind::Float64 => 53,
+            _ => unreachable!("{:?} is not a float domain", kind),
+        }
+    }
+
+    pub fn encode_float_function(
+        &self,
+        kind: &BuiltinDomainKind,
+        function: FloatFunctionKind,
+    ) -> vir::DomainFunc {
+        let domain_name = self.encode_float_domain_name(kind);
+        let prefix = match kind {
+            BuiltinDomainKind::Float32 => "f32",
+            _ => "f64",
+        };
+        let float_type = self.encode_float_domain_type(kind);
+        let other_type = self.encode_float_domain_type(match kind {
+            BuiltinDomainKind::Float32 => &BuiltinDomainKind::Float64,
+            _ => &BuiltinDomainKind::Float32,
+        });
+        let (name, arg_types, return_type) = match function {
+            FloatFunctionKind::FromInt => ("from_int", vec![vir::Type::Int], float_type),
+            FloatFunctionKind::ToInt => ("to_int", vec![float_type], vir::Type::Int),
+            FloatFunctionKind::FromBits => ("from_bits", vec![vir::Type::Int], float_type),
+            FloatFunctionKind::FromOther => ("from_other", vec![other_type], float_type),
+            FloatFunctionKind::Add => ("add", vec![float_type.clone(), float_type.clone()], float_type),
+            FloatFunctionKind::Sub => ("sub", vec![float_type.clone(), float_type.clone()], float_type),
+            FloatFunctionKind::Mul => ("mul", vec![float_type.clone(), float_type.clone()], float_type),
+            FloatFunctionKind::Div => ("div", vec![float_type.clone(), float_type.clone()], float_type),
+            FloatFunctionKind::Rem => ("rem", vec![float_type.clone(), float_type.clone()], float_type),
+            FloatFunctionKind::Neg => ("neg", vec![float_type.clone()], float_type),
+            FloatFunctionKind::Eq => ("eq", vec![float_type.clone(), float_type], vir::Type::Bool),
+            FloatFunctionKind::Lt => ("lt", vec![float_type.clone(), float_type], vir::Type::Bool),
+            FloatFunctionKind::Le => ("le", vec![float_type.clone(), float_type], vir::Type::Bool),
+            FloatFunctionKind::IsNan => ("is_nan", vec![float_type], vir::Type::Bool),
+        };
+        let arg_names = ["a", "b"];
+        vir::DomainFunc {
+            name: format!("{}${}", prefix, name),
+            formal_args: arg_types
+                .into_iter()
+                .zip(arg_names.iter())
+                .map(|(typ, name)| vir::LocalVar::new(*name, typ))
+                .collect(),
+            return_type,
+            unique: false,
+            domain_name: domain_name.to_string(),
+        }
+    }
+
+    /// Encodes a domain with an uninterpreted float sort. The axioms only state properties that
+    /// hold for IEEE 754 arithmetic with the default rounding mode, so the encoding is sound but
+    /// incomplete: beyond NaN handling and ordering, only arithmetic on integers that are
+    /// exactly representable is interpreted.
+    fn encode_float_builtin_domain(&self, kind: BuiltinDomainKind) -> vir::Domain {
+        let domain_name = self.encode_float_domain_name(&kind);
+        let float_type = self.encode_float_domain_type(&kind);
+        let func = |function| self.encode_float_function(&kind, function);
+        let app1 = |function, arg: &vir::Expr| func(function).apply(vec![arg.clone()]);
+        let app2 = |function, left: &vir::Expr, right: &vir::Expr| {
+            func(function).apply(vec![left.clone(), right.clone()])
+        };
+
+        let a_var = vir::LocalVar::new("a", float_type.clone());
+        let b_var = vir::LocalVar::new("b", float_type.clone());
+        let c_var = vir::LocalVar::new("c", float_type);
+        let i_var = vir_local!{ i: Int };
+        let j_var = vir_local!{ j: Int };
+        let a: vir::Expr = a_var.clone().into();
+        let b: vir::Expr = b_var.clone().into();
+        let c: vir::Expr = c_var.clone().into();
+        let i: vir::Expr = i_var.clone().into();
+        let j: vir::Expr = j_var.clone().into();
+        let not_nan = |arg: &vir::Expr| vir::Expr::not(app1(FloatFunctionKind::IsNan, arg));
+
+        let limit: vir::Expr = (1i128 << self.float_precision(&kind)).into();
+        let exact = |arg: vir::Expr| vir::Expr::and(
+            vir::Expr::le_cmp(vir::Expr::minus(limit.clone()), arg.clone()),
+            vir::Expr::le_cmp(arg, limit.clone()),
+        );
+        let from_i = app1(FloatFunctionKind::FromInt, &i);
+        let from_j = app1(FloatFunctionKind::FromInt, &j);
+
+        let mut axioms = vec![];
+        let mut add_axiom = |name: &str, vars: Vec<vir::LocalVar>, triggers: Vec<Vec<vir::Expr>>, body| {
+            axioms.push(vir::DomainAxiom {
+                name: format!("{}${}", domain_name, name),
+                expr: vir::Expr::forall(
+                    vars,
+                    triggers.into_iter().map(vir::Trigger::new).collect(),
+                    body,
+                ),
+                domain_name: domain_name.to_string(),
+            });
+        };
+
+        // A value is NaN iff it is not equal to itself.
+        add_axiom(
+            "nan_irreflexive",
+            vec![a_var.clone()],
+            vec![
+                vec![app1(FloatFunctionKind::IsNan, &a)],
+                vec![app2(FloatFunctionKind::Eq, &a, &a)],
+            ],
+            vir::Expr::eq_cmp(
+                app1(FloatFunctionKind::IsNan, &a),
+                vir::Expr::not(app2(FloatFunctionKind::Eq, &a, &a)),
+            ),
+        );
+        add_axiom(
+            "eq_symmetric",
+            vec![a_var.clone(), b_var.clone()],
+            vec![vec![app2(FloatFunctionKind::Eq, &a, &b)]],
+            vir::Expr::eq_cmp(
+                app2(FloatFunctionKind::Eq, &a, &b),
+                app2(FloatFunctionKind::Eq, &b, &a),
+            ),
+        );
+        add_axiom(
+            "eq_transitive",
+            vec![a_var.clone(), b_var.clone(), c_var.clone()],
+            vec![vec![app2(FloatFunctionKind::Eq, &a, &b), app2(FloatFunctionKind::Eq, &b, &c)]],
+            vir::Expr::implies(
+                vir::Expr::and(
+                    app2(FloatFunctionKind::Eq, &a, &b),
+                    app2(FloatFunctionKind::Eq, &b, &c),
+                ),
+                app2(FloatFunctionKind::Eq, &a, &c),
+            ),
+        );
+        // NaN is unordered: it compares false with everything.
+        add_axiom(
+            "lt_not_nan",
+            vec![a_var.clone(), b_var.clone()],
+            vec![vec![app2(FloatFunctionKind::Lt, &a, &b)]],
+            vir::Expr::implies(
+                app2(FloatFunctionKind::Lt, &a, &b),
+                vir::Expr::and(not_nan(&a), not_nan(&b)),
+            ),
+        );
+        add_axiom(
+            "le_def",
+            vec![a_var.clone(), b_var.clone()],
+            vec![vec![app2(FloatFunctionKind::Le, &a, &b)]],
+            vir::Expr::eq_cmp(
+                app2(FloatFunctionKind::Le, &a, &b),
+                vir::Expr::or(
+                    app2(FloatFunctionKind::Lt, &a, &b),
+                    app2(FloatFunctionKind::Eq, &a, &b),
+                ),
+            ),
+        );
+        add_axiom(
+            "lt_irreflexive",
+            vec![a_var.clone(), b_var.clone()],
+            vec![vec![app2(FloatFunctionKind::Lt, &a, &b)]],
+            vir::Expr::implies(
+                app2(FloatFunctionKind::Lt, &a, &b),
+                vir::Expr::and(
+                    vir::Expr::not(app2(FloatFunctionKind::Lt, &b, &a)),
+                    vir::Expr::not(app2(FloatFunctionKind::Eq, &a, &b)),
+                ),
+            ),
+        );
+        add_axiom(
+            "lt_transitive",
+            vec![a_var.clone(), b_var.clone(), c_var],
+            vec![vec![app2(FloatFunctionKind::Lt, &a, &b), app2(FloatFunctionKind::Lt, &b, &c)]],
+            vir::Expr::implies(
+                vir::Expr::and(
+                    app2(FloatFunctionKind::Lt, &a, &b),
+                    app2(FloatFunctionKind::Lt, &b, &c),
+                ),
+                app2(FloatFunctionKind::Lt, &a, &c),
+            ),
+        );
+        // Values that are not NaN are totally ordered.
+        add_axiom(
+            "lt_total",
+            vec![a_var.clone(), b_var.clone()],
+            vec![
+                vec![app2(FloatFunctionKind::Lt, &a, &b)],
+                vec![app2(FloatFunctionKind::Le, &a, &b)],
+            ],
+            vir::Expr::implies(
+                vir::Expr::and(not_nan(&a), not_nan(&b)),
+                vir::Expr::or(
+                    app2(FloatFunctionKind::Le, &a, &b),
+                    app2(FloatFunctionKind::Lt, &b, &a),
+                ),
+            ),
+        );
+        // NaN propagates through arithmetic.
+        for function in &[
+            FloatFunctionKind::Add,
+            FloatFunctionKind::Sub,
+            FloatFunctionKind::Mul,
+            FloatFunctionKind::Div,
+            FloatFunctionKind::Rem,
+        ] {
+            let result = app2(*function, &a, &b);
+            add_axiom(
+                &format!("{}_nan", func(*function).name),
+                vec![a_var.clone(), b_var.clone()],
+                vec![vec![result.clone()]],
+                vir::Expr::implies(
+                    vir::Expr::or(
+                        app1(FloatFunctionKind::IsNan, &a),
+                        app1(FloatFunctionKind::IsNan, &b),
+                    ),
+                    app1(FloatFunctionKind::IsNan, &result),
+                ),
+            );
+        }
+        add_axiom(
+            "neg_nan",
+            vec![a_var.clone()],
+            vec![vec![app1(FloatFunctionKind::Neg, &a)]],
+            vir::Expr::eq_cmp(
+                app1(FloatFunctionKind::IsNan, &app1(FloatFunctionKind::Neg, &a)),
+                app1(FloatFunctionKind::IsNan, &a),
+            ),
+        );
+        add_axiom(
+            "neg_lt",
+            vec![a_var.clone(), b_var.clone()],
+            vec![vec![app2(FloatFunctionKind::Lt, &a, &b), app1(FloatFunctionKind::Neg, &a)]],
+            vir::Expr::eq_cmp(
+                app2(FloatFunctionKind::Lt, &a, &b),
+                app2(
+                    FloatFunctionKind::Lt,
+                    &app1(FloatFunctionKind::Neg, &b),
+                    &app1(FloatFunctionKind::Neg, &a),
+                ),
+            ),
+        );
+        // Rounding an integer never yields NaN and is monotone.
+        add_axiom(
+            "from_int_not_nan",
+            vec![i_var.clone()],
+            vec![vec![from_i.clone()]],
+            not_nan(&from_i),
+        );
+        add_axiom(
+            "from_int_monotone",
+            vec![i_var.clone(), j_var.clone()],
+            vec![vec![app2(FloatFunctionKind::Le, &from_i, &from_j)]],
+            vir::Expr::implies(
+                vir::Expr::le_cmp(i.clone(), j.clone()),
+                app2(FloatFunctionKind::Le, &from_i, &from_j),
+            ),
+        );
+        // Integers in the exactly representable range are mapped injectively.
+        add_axiom(
+            "from_int_exact",
+            vec![i_var.clone()],
+            vec![vec![from_i.clone()]],
+            vir::Expr::implies(
+                exact(i.clone()),
+                vir::Expr::eq_cmp(app1(FloatFunctionKind::ToInt, &from_i), i.clone()),
+            ),
+        );
+        add_axiom(
+            "from_int_eq",
+            vec![i_var.clone(), j_var.clone()],
+            vec![vec![app2(FloatFunctionKind::Eq, &from_i, &from_j)]],
+            vir::Expr::implies(
+                vir::Expr::and(exact(i.clone()), exact(j.clone())),
+                vir::Expr::eq_cmp(
+                    app2(FloatFunctionKind::Eq, &from_i, &from_j),
+                    vir::Expr::eq_cmp(i.clone(), j.clone()),
+                ),
+            ),
+        );
+        add_axiom(
+            "from_int_lt",
+            vec![i_var.clone(), j_var.clone()],
+            vec![vec![app2(FloatFunctionKind::Lt, &from_i, &from_j)]],
+            vir::Expr::implies(
+                vir::Expr::and(exact(i.clone()), exact(j.clone())),
+                vir::Expr::eq_cmp(
+                    app2(FloatFunctionKind::Lt, &from_i, &from_j),
+                    vir::Expr::lt_cmp(i.clone(), j.clone()),
+                ),
+            ),
+        );
+        // Arithmetic on exactly representable integers is exact. The sign of a zero result
+        // matters for `==` on the domain, so the cases producing `-0.0` are excluded.
+        let arithmetic = vec![
+            ("add_exact", FloatFunctionKind::Add, vir::Expr::add(i.clone(), j.clone()), true.into()),
+            ("sub_exact", FloatFunctionKind::Sub, vir::Expr::sub(i.clone(), j.clone()), true.into()),
+            (
+                "mul_exact",
+                FloatFunctionKind::Mul,
+                vir::Expr::mul(i.clone(), j.clone()),
+                vir::Expr::or(
+                    vir::Expr::ne_cmp(vir::Expr::mul(i.clone(), j.clone()), 0.into()),
+                    vir::Expr::and(
+                        vir::Expr::ge_cmp(i.clone(), 0.into()),
+                        vir::Expr::ge_cmp(j.clone(), 0.into()),
+                    ),
+                ),
+            ),
+        ];
+        for (name, function, result, guard) in arithmetic {
+            let float_result = app2(function, &from_i, &from_j);
+            add_axiom(
+                name,
+                vec![i_var.clone(), j_var.clone()],
+                vec![vec![float_result.clone()]],
+                vir::Expr::implies(
+                    vir::Expr::and(
+                        vir::Expr::and(exact(i.clone()), exact(j.clone())),
+                        vir::Expr::and(exact(result.clone()), guard),
+                    ),
+                    vir::Expr::eq_cmp(float_result, app1(FloatFunctionKind::FromInt, &result)),
+                ),
+            );
+        }
+        add_axiom(
+            "neg_exact",
+            vec![i_var.clone()],
+            vec![vec![app1(FloatFunctionKind::Neg, &from_i)]],
+            vir::Expr::implies(
+                vir::Expr::and(exact(i.clone()), vir::Expr::ne_cmp(i.clone(), 0.into())),
+                vir::Expr::eq_cmp(
+                    app1(FloatFunctionKind::Neg, &from_i),
+                    app1(FloatFunctionKind::FromInt, &vir::Expr::minus(i.clone())),
+                ),
+            ),
+        );
+        // Converting between the precisions preserves NaN and exactly representable integers.
+        let other_kind = match kind {
+            BuiltinDomainKind::Float32 => BuiltinDomainKind::Float64,
+            _ => BuiltinDomainKind::Float32,
+        };
+        let other_from_i = self
+            .encode_float_function(&other_kind, FloatFunctionKind::FromInt)
+            .apply(vec![i.clone()]);
+        let other_a_var = vir::LocalVar::new("a", self.encode_float_domain_type(&other_kind));
+        let other_a: vir::Expr = other_a_var.clone().into();
+        let converted = app1(FloatFunctionKind::FromOther, &other_a);
+        add_axiom(
+            "from_other_nan",
+            vec![other_a_var],
+            vec![vec![converted.clone()]],
+            vir::Expr::eq_cmp(
+                app1(FloatFunctionKind::IsNan, &converted),
+                self.encode_float_function(&other_kind, FloatFunctionKind::IsNan)
+                    .apply(vec![other_a]),
+            ),
+        );
+        let common_limit: vir::Expr = (1i128 << self.float_precision(&BuiltinDomainKind::Float32)).into();
+        add_axiom(
+            "from_other_exact",
+            vec![i_var],
+            vec![vec![app1(FloatFunctionKind::FromOther, &other_from_i)]],
+            vir::Expr::implies(
+                vir::Expr::and(
+                    vir::Expr::le_cmp(vir::Expr::minus(common_limit.clone()), i.clone()),
+                    vir::Expr::le_cmp(i, common_limit),
+                ),
+                vir::Expr::eq_cmp(app1(FloatFunctionKind::FromOther, &other_from_i), from_i),
+            ),
+        );
+
+        let functions = vec![
+            FloatFunctionKind::FromInt,
+            FloatFunctionKind::ToInt,
+            FloatFunctionKind::FromBits,
+            FloatFunctionKind::FromOther,
+            FloatFunctionKind::Add,
+            FloatFunctionKind::Sub,
+            FloatFunctionKind::Mul,
+            FloatFunctionKind::Div,
+            FloatFunctionKind::Rem,
+            FloatFunctionKind::Neg,
+            FloatFunctionKind::Eq,
+            FloatFunctionKind::Lt,
+            FloatFunctionKind::Le,
+            FloatFunctionKind::IsNan,
+        ].into_iter().map(&func).collect();
+
+        vir::Domain {
+            name: domain_name.to_owned(),
+            functions,
+            axioms,
+            type_vars: vec![],
+        }
+    }
+
+    pub fn encode_bitwise_domain_name(&self, kind: &BuiltinDomainKind) -> String {
+        format!("Bitwise{}Domain", self.bitwise_prefix(kind).to_uppercase())
+    }
+
+    fn bitwise_prefix(&self, kind: &BuiltinDomainKind) -> String {
+        match kind {
+            BuiltinDomainKind::Bitwise { bits, signed } => {
+                format!("{}{}", if *signed { "i" } else { "u" }, bits)
+            }
+            _ => unreachable!("{:?} is not a bitwise domain", kind),
+        }
+    }
+
+    /// The smallest and the largest value of the integer type of a bitwise domain.
+    fn bitwise_bounds(&self, kind: &BuiltinDomainKind) -> (vir::Expr, vir::Expr) {
+        match kind {
+            BuiltinDomainKind::Bitwise { bits, signed: true } => (
+                (std::i128::MIN >> (128 - bits)).into(),
+                (std::i128::MAX >> (128 - bits)).into(),
+            ),
+            BuiltinDomainKind::Bitwise { bits, signed: false } => (
+                0.into(),
+                (std::u128::MAX >> (128 - bits)).into(),
+            ),
+            _ => unreachable!("{:?} is not a bitwise domain", kind),
+        }
+    }
+
+    /// Encodes the constant `2^exponent`, for exponents of at most 128.
+    pub fn encode_pow2_const(&self, exponent: u32) -> vir::Expr {
+        if exponent < 128 {
+            (1u128 << exponent).into()
+        } else {
+            debug_assert_eq!(exponent, 128);
+            vir::Expr::mul(self.encode_pow2_const(64), self.encode_pow2_const(64))
+        }
+    }
+
+    /// Wraps a mathematical integer around into the range of the integer type of a bitwise
+    /// domain, like two's complement arithmetic does.
+    pub fn encode_bitwise_wrap(&self, kind: &BuiltinDomainKind, expr: vir::Expr) -> vir::Expr {
+        let (bits, signed) = match kind {
+            BuiltinDomainKind::Bitwise { bits, signed } => (*bits, *signed),
+            _ => unreachable!("{:?} is not a bitwise domain", kind),
+        };
+        let modulus = self.encode_pow2_const(bits);
+        let unsigned = vir::Expr::modulo(expr, modulus.clone());
+        if signed {
+            let (_, max) = self.bitwise_bounds(kind);
+            vir::Expr::ite(
+                vir::Expr::gt_cmp(unsigned.clone(), max),
+                vir::Expr::sub(unsigned.clone(), modulus),
+                unsigned,
+            )
+        } else {
+            unsigned
+        }
+    }
+
+    pub fn encode_bitwise_function(
+        &self,
+        kind: &BuiltinDomainKind,
+        function: BitwiseFunctionKind,
+    ) -> vir::DomainFunc {
+        let name = match function {
+            BitwiseFunctionKind::BitAnd => "bitand",
+            BitwiseFunctionKind::BitOr => "bitor",
+            BitwiseFunctionKind::BitXor => "bitxor",
+            BitwiseFunctionKind::Shl => "shl",
+            BitwiseFunctionKind::Shr => "shr",
+            BitwiseFunctionKind::Pow2 => "pow2",
+        };
+        let formal_args = if function == BitwiseFunctionKind::Pow2 {
+            vec![vir_local!{ a: Int }]
+        } else {
+            vec![vir_local!{ a: Int }, vir_local!{ b: Int }]
+        };
+        vir::DomainFunc {
+            name: format!("{}${}", self.bitwise_prefix(kind), name),
+            formal_args,
+            return_type: vir::Type::Int,
+            unique: false,
+            domain_name: self.encode_bitwise_domain_name(kind),
+        }
+    }
+
+    /// Encodes a domain with uninterpreted bitwise operations and shifts on the integers of a
+    /// type. Like for floats, the axioms are sound but incomplete: shifts by an amount smaller
+    /// than the number of bits are fully interpreted, while of the bitwise operations only
+    /// their algebraic identities and bounds are known.
+    fn encode_bitwise_builtin_domain(&self, kind: BuiltinDomainKind) -> vir::Domain {
+        let domain_name = self.encode_bitwise_domain_name(&kind);
+        let bits = match kind {
+            BuiltinDomainKind::Bitwise { bits, .. } => bits,
+            _ => unreachable!("{:?} is not a bitwise domain", kind),
+        };
+        let func = |function| self.encode_bitwise_function(&kind, function);
+        let app1 = |function, arg: &vir::Expr| func(function).apply(vec![arg.clone()]);
+        let app2 = |function, left: &vir::Expr, right: &vir::Expr| {
+            func(function).apply(vec![left.clone(), right.clone()])
+        };
+
+        let a_var = vir_local!{ a: Int };
+        let b_var = vir_local!{ b: Int };
+        let a: vir::Expr = a_var.clone().into();
+        let b: vir::Expr = b_var.clone().into();
+        let (min, max) = self.bitwise_bounds(&kind);
+        let in_range = |arg: &vir::Expr| vir::Expr::and(
+            vir::Expr::le_cmp(min.clone(), arg.clone()),
+            vir::Expr::le_cmp(arg.clone(), max.clone()),
+        );
+        let both_in_range = vir::Expr::and(in_range(&a), in_range(&b));
+        let is_non_negative = |arg: &vir::Expr| vir::Expr::ge_cmp(arg.clone(), 0.into());
+        let all_ones = match kind {
+            BuiltinDomainKind::Bitwise { signed: true, .. } => (-1).into(),
+            _ => max.clone(),
+        };
+        let zero: vir::Expr = 0.into();
+
+        let mut axioms = vec![];
+        let mut add_axiom = |name: &str, vars: Vec<vir::LocalVar>, triggers: Vec<Vec<vir::Expr>>, body| {
+            let expr = if vars.is_empty() {
+                body
+            } else {
+                vir::Expr::forall(
+                    vars,
+                    triggers.into_iter().map(vir::Trigger::new).collect(),
+                    body,
+                )
+            };
+            axioms.push(vir::DomainAxiom {
+                name: format!("{}${}", domain_name, name),
+                expr,
+                domain_name: domain_name.clone(),
+            });
+        };
+
+        for (function, name) in &[
+            (BitwiseFunctionKind::BitAnd, "bitand"),
+            (BitwiseFunctionKind::BitOr, "bitor"),
+            (BitwiseFunctionKind::BitXor, "bitxor"),
+        ] {
+            add_axiom(
+                &format!("{}_commutative", name),
+                vec![a_var.clone(), b_var.clone()],
+                vec![vec![app2(*function, &a, &b)]],
+                vir::Expr::eq_cmp(app2(*function, &a, &b), app2(*function, &b, &a)),
+            );
+            add_axiom(
+                &format!("{}_range", name),
+                vec![a_var.clone(), b_var.clone()],
+                vec![vec![app2(*function, &a, &b)]],
+                vir::Expr::implies(both_in_range.clone(), in_range(&app2(*function, &a, &b))),
+            );
+        }
+
+        // Identities with the neutral and the absorbing elements, and idempotence.
+        let identities = vec![
+            ("bitand_zero", BitwiseFunctionKind::BitAnd, zero.clone(), zero.clone()),
+            ("bitand_all_ones", BitwiseFunctionKind::BitAnd, all_ones.clone(), a.clone()),
+            ("bitor_zero", BitwiseFunctionKind::BitOr, zero.clone(), a.clone()),
+            ("bitor_all_ones", BitwiseFunctionKind::BitOr, all_ones.clone(), all_ones),
+            ("bitxor_zero", BitwiseFunctionKind::BitXor, zero.clone(), a.clone()),
+        ];
+        for (name, function, constant, result) in identities {
+            add_axiom(
+                name,
+                vec![a_var.clone(), b_var.clone()],
+                vec![vec![app2(function, &a, &b)]],
+                vir::Expr::implies(
+                    vir::Expr::and(in_range(&a), vir::Expr::eq_cmp(b.clone(), constant)),
+                    vir::Expr::eq_cmp(app2(function, &a, &b), result),
+                ),
+            );
+        }
+        let self_identities = vec![
+            ("bitand_self", BitwiseFunctionKind::BitAnd, a.clone()),
+            ("bitor_self", BitwiseFunctionKind::BitOr, a.clone()),
+            ("bitxor_self", BitwiseFunctionKind::BitXor, zero),
+        ];
+        for (name, function, result) in self_identities {
+            add_axiom(
+                name,
+                vec![a_var.clone()],
+                vec![vec![app2(function, &a, &a)]],
+                vir::Expr::implies(in_range(&a), vir::Expr::eq_cmp(app2(function, &a, &a), result)),
+            );
+        }
+
+        // Bounds of the bitwise operations on non-negative values.
+        add_axiom(
+            "bitand_bounds",
+            vec![a_var.clone(), b_var.clone()],
+            vec![vec![app2(BitwiseFunctionKind::BitAnd, &a, &b)]],
+            vir::Expr::implies(
+                vir::Expr::and(both_in_range.clone(), is_non_negative(&a)),
+                vir::Expr::and(
+                    is_non_negative(&app2(BitwiseFunctionKind::BitAnd, &a, &b)),
+                    vir::Expr::le_cmp(app2(BitwiseFunctionKind::BitAnd, &a, &b), a.clone()),
+                ),
+            ),
+        );
+        let both_non_negative = vir::Expr::and(
+            both_in_range,
+            vir::Expr::and(is_non_negative(&a), is_non_negative(&b)),
+        );
+        add_axiom(
+            "bitor_bounds",
+            vec![a_var.clone(), b_var.clone()],
+            vec![vec![app2(BitwiseFunctionKind::BitOr, &a, &b)]],
+            vir::Expr::implies(
+                both_non_negative.clone(),
+                vir::Expr::and(
+                    vir::Expr::ge_cmp(app2(BitwiseFunctionKind::BitOr, &a, &b), a.clone()),
+                    vir::Expr::le_cmp(
+                        app2(BitwiseFunctionKind::BitOr, &a, &b),
+                        vir::Expr::add(a.clone(), b.clone()),
+                    ),
+                ),
+            ),
+        );
+        add_axiom(
+            "bitxor_bounds",
+            vec![a_var.clone(), b_var.clone()],
+            vec![vec![app2(BitwiseFunctionKind::BitXor, &a, &b)]],
+            vir::Expr::implies(
+                both_non_negative,
+                vir::Expr::and(
+                    is_non_negative(&app2(BitwiseFunctionKind::BitXor, &a, &b)),
+                    vir::Expr::le_cmp(
+                        app2(BitwiseFunctionKind::BitXor, &a, &b),
+                        vir::Expr::add(a.clone(), b.clone()),
+                    ),
+                ),
+            ),
+        );
+
+        // The powers of two that shift amounts can denote.
+        let pow2_values = (0..bits)
+            .map(|exponent| vir::Expr::eq_cmp(
+                app1(BitwiseFunctionKind::Pow2, &exponent.into()),
+                self.encode_pow2_const(exponent),
+            ))
+            .fold(true.into(), vir::Expr::and);
+        add_axiom("pow2_values", vec![], vec![], pow2_values);
+        let valid_amount = |arg: &vir::Expr| vir::Expr::and(
+            is_non_negative(arg),
+            vir::Expr::lt_cmp(arg.clone(), bits.into()),
+        );
+        add_axiom(
+            "pow2_positive",
+            vec![b_var.clone()],
+            vec![vec![app1(BitwiseFunctionKind::Pow2, &b)]],
+            vir::Expr::implies(
+                valid_amount(&b),
+                vir::Expr::gt_cmp(app1(BitwiseFunctionKind::Pow2, &b), 0.into()),
+            ),
+        );
+
+        // Shifts by less than the number of bits multiply or divide by a power of two.
+        let valid_shift = vir::Expr::and(in_range(&a), valid_amount(&b));
+        add_axiom(
+            "shl_def",
+            vec![a_var.clone(), b_var.clone()],
+            vec![vec![app2(BitwiseFunctionKind::Shl, &a, &b)]],
+            vir::Expr::implies(
+                valid_shift.clone(),
+                vir::Expr::eq_cmp(
+                    app2(BitwiseFunctionKind::Shl, &a, &b),
+                    self.encode_bitwise_wrap(
+                        &kind,
+                        vir::Expr::mul(a.clone(), app1(BitwiseFunctionKind::Pow2, &b)),
+                    ),
+                ),
+            ),
+        );
+        // Viper's integer division rounds towards negative infinity for positive divisors,
+        // like an arithmetic right shift.
+        add_axiom(
+            "shr_def",
+            vec![a_var, b_var],
+            vec![vec![app2(BitwiseFunctionKind::Shr, &a, &b)]],
+            vir::Expr::implies(
+                valid_shift,
+                vir::Expr::eq_cmp(
+                    app2(BitwiseFunctionKind::Shr, &a, &b),
+                    vir::Expr::div(a.clone(), app1(BitwiseFunctionKind::Pow2, &b)),
+                ),
+            ),
+        );
+
+        let functions = vec![
+            BitwiseFunctionKind::BitAnd,
+            BitwiseFunctionKind::BitOr,
+            BitwiseFunctionKind::BitXor,
+            BitwiseFunctionKind::Shl,
+            BitwiseFunctionKind::Shr,
+            BitwiseFunctionKind::Pow2,
+        ].into_iter().map(&func).collect();
+
+        vir::Domain {
+            name: domain_name,
+            functions,
+            axioms,
+            type_vars: vec![],
         }
     }
 
@@ -9,9 +9,12 @@ use crate::encoder::borrows::{compute_procedure_contract, ProcedureContract, Pro
 use crate::encoder::builtin_encoder::BuiltinEncoder;
 use crate::encoder::builtin_encoder::BuiltinFunctionKind;
 use crate::encoder::builtin_encoder::BuiltinMethodKind;
+use crate::encoder::builtin_encoder::BitwiseFunctionKind;
 use crate::encoder::builtin_encoder::BuiltinDomainKind;
+use crate::encoder::builtin_encoder::FloatFunctionKind;
 use crate::encoder::errors::{ErrorCtxt, ErrorManager, SpannedEncodingError, EncodingError, WithSpan};
 use crate::encoder::foldunfold;
+use crate::encoder::ghost_encoder::{self, GhostCollectionKind};
 use crate::encoder::places;
 use crate::encoder::procedure_encoder::ProcedureEncoder;
 use crate::encoder::pure_function_encoder::PureFunctionEncoder;
@@ -39,6 +42,8 @@ use rustc_hir::def_id::DefId;
 use rustc_middle::mir;
 // use rustc::mir::interpret::GlobalId;
 use rustc_middle::ty;
+use rustc_middle::ty::DefIdTree;
+use rustc_middle::ty::subst::Subst;
 use std::cell::{RefCell, RefMut};
 use std::collections::{HashMap, HashSet};
 use std::io::Write;
@@ -81,6 +86,8 @@ pub struct Encoder<'v, 'tcx: 'v> {
     >>,
     builtin_methods: RefCell<HashMap<BuiltinMethodKind, vir::BodylessMethod>>,
     builtin_functions: RefCell<HashMap<BuiltinFunctionKind, vir::Function>>,
+    /// Builtin domains that are used by the encoding regardless of the configuration.
+    builtin_domains: RefCell<HashSet<BuiltinDomainKind>>,
     procedures: RefCell<HashMap<ProcedureDefId, vir::CfgMethod>>,
     pure_function_bodies: RefCell<HashMap<(ProcedureDefId, String), vir::Expr>>,
     pure_functions: RefCell<HashMap<(ProcedureDefId, String), vir::Function>>,
@@ -104,6 +111,8 @@ pub struct Encoder<'v, 'tcx: 'v> {
     array_types_encoder: RefCell<ArrayTypesEncoder<'tcx>>,
     closures_collector: RefCell<SpecsClosuresCollector<'tcx>>,
     encoding_queue: RefCell<Vec<(ProcedureDefId, Vec<(ty::Ty<'tcx>, ty::Ty<'tcx>)>)>>,
+    /// The local functions that are called in the body of a local function.
+    local_callees: RefCell<HashMap<ProcedureDefId, Vec<ProcedureDefId>>>,
     vir_program_before_foldunfold_writer: RefCell<Box<dyn Write>>,
     vir_program_before_viper_writer: RefCell<Box<dyn Write>>,
     pub typaram_repl: RefCell<Vec<HashMap<ty::Ty<'tcx>, ty::Ty<'tcx>>>>,
@@ -144,6 +153,7 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             procedure_contracts: RefCell::new(HashMap::new()),
             builtin_methods: RefCell::new(HashMap::new()),
             builtin_functions: RefCell::new(HashMap::new()),
+            builtin_domains: RefCell::new(HashSet::new()),
             procedures: RefCell::new(HashMap::new()),
             pure_function_bodies: RefCell::new(HashMap::new()),
             pure_functions: RefCell::new(HashMap::new()),
@@ -162,6 +172,7 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             fields: RefCell::new(HashMap::new()),
             closures_collector: RefCell::new(SpecsClosuresCollector::new()),
             encoding_queue: RefCell::new(vec![]),
+            local_callees: RefCell::new(HashMap::new()),
             vir_program_before_foldunfold_writer,
             vir_program_before_viper_writer,
             typaram_repl: RefCell::new(Vec::new()),
@@ -264,6 +275,21 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             domains.push(builtin_encoder.encode_builtin_domain(BuiltinDomainKind::Primitive));
         }
 
+        // The two float domains refer to each other through their conversion functions.
+        let builtin_domains = self.builtin_domains.borrow();
+        if builtin_domains.contains(&BuiltinDomainKind::Float32)
+            || builtin_domains.contains(&BuiltinDomainKind::Float64)
+        {
+            let builtin_encoder = BuiltinEncoder::new();
+            domains.push(builtin_encoder.encode_builtin_domain(BuiltinDomainKind::Float32));
+            domains.push(builtin_encoder.encode_builtin_domain(BuiltinDomainKind::Float64));
+        }
+        for kind in builtin_domains.iter() {
+            if let BuiltinDomainKind::Bitwise { .. } = kind {
+                domains.push(BuiltinEncoder::new().encode_builtin_domain(kind.clone()));
+            }
+        }
+
         domains.sort_by_key(|d| d.get_identifier());
         domains
     }
@@ -342,6 +368,15 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         Vec<mir::Operand<'tcx>>,
         Vec<ty::Ty<'tcx>>,
     )> {
+        // The closures of specifications imported from other crates are not visited by
+        // `collect_from_all_spec_items`, so their instantiations are collected on demand from
+        // the body in which they are defined.
+        let tcx = self.env().tcx();
+        if !closure_def_id.is_local() && tcx.is_closure(closure_def_id) {
+            if let Some(parent_def_id) = tcx.parent(closure_def_id) {
+                self.closures_collector.borrow_mut().collect(self.env, parent_def_id);
+            }
+        }
         self.closures_collector.borrow().get_single_instantiation(closure_def_id)
     }
 
@@ -357,6 +392,19 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         Some(spec.expect_loop().clone())
     }
 
+    /// Get the specification of the ghost statement (e.g. `prusti_assert!`) type-checked
+    /// by the `def_id` closure.
+    pub fn get_ghost_statement(&self, def_id: DefId) -> Option<typed::GhostStatement<'tcx>> {
+        let spec = self.def_spec.get(&def_id)?;
+        Some(spec.expect_ghost_statement().clone())
+    }
+
+    /// Get the type invariants attached to the `def_id` struct or enum.
+    pub fn get_struct_specs(&self, def_id: DefId) -> Option<Vec<typed::Specification<'tcx>>> {
+        let spec = self.def_spec.get(&def_id)?;
+        Some(spec.expect_struct().clone())
+    }
+
     /// Get the specifications attached to the `def_id` function.
     pub fn get_procedure_specs(&self, def_id: DefId) -> Option<typed::ProcedureSpecification<'tcx>> {
         let spec = self.def_spec.get(&def_id)?;
@@ -454,7 +502,9 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         //     }
         // }
 
-        if let Some(ty) = self_ty {
+        // calls through a trait object only get the trait-level specification, because the
+        // dynamic type of the receiver is unknown
+        if let Some(ty) = self_ty.filter(|ty| !ty.is_trait()) {
             if let Some(id) = self.env().tcx().trait_of_item(proc_def_id) {
                 let proc_name = self.env().tcx().item_name(proc_def_id);
                 let procs = self.env().get_trait_method_decl_for_type(ty, id, proc_name);
@@ -488,6 +538,10 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
     /// For composed data structures, the base expression is returned.
     pub fn encode_value_expr(&self, base: vir::Expr, ty: ty::Ty<'tcx>) -> EncodingResult<vir::Expr> {
         match ty.kind() {
+            _ if self.ghost_collection_kind(ty).is_some() => {
+                let value_field = self.encode_value_field(ty)?;
+                Ok(base.field(value_field))
+            }
             ty::TyKind::Adt(_, _)
             | ty::TyKind::Array(..)
             | ty::TyKind::Tuple(_) => {
@@ -664,6 +718,78 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         builtin_encoder.encode_builtin_function_name(&function_kind)
     }
 
+    fn encode_float_domain_kind(&self, ty: &ty::TyS<'tcx>) -> BuiltinDomainKind {
+        let kind = match ty.kind() {
+            ty::TyKind::Float(ty::FloatTy::F32) => BuiltinDomainKind::Float32,
+            ty::TyKind::Float(ty::FloatTy::F64) => BuiltinDomainKind::Float64,
+            ref x => unreachable!("{:?} is not a float type", x),
+        };
+        self.builtin_domains.borrow_mut().insert(kind.clone());
+        kind
+    }
+
+    /// The Viper type of the values of the float type `ty`.
+    pub fn encode_float_domain_type(&self, ty: ty::Ty<'tcx>) -> vir::Type {
+        let kind = self.encode_float_domain_kind(ty);
+        BuiltinEncoder::new().encode_float_domain_type(&kind)
+    }
+
+    /// Applies a function of the domain that encodes the float type `ty`.
+    pub fn encode_float_function_app(
+        &self,
+        ty: &ty::TyS<'tcx>,
+        function: FloatFunctionKind,
+        args: Vec<vir::Expr>,
+    ) -> vir::Expr {
+        trace!("encode_float_function_app(ty={:?}, function={:?})", ty, function);
+        let kind = self.encode_float_domain_kind(ty);
+        let domain_func = BuiltinEncoder::new().encode_float_function(&kind, function);
+        vir::Expr::domain_func_app(domain_func, args)
+    }
+
+    /// Number of bits of the integer type `ty`. The width of `isize` and `usize` is the one
+    /// of the host, like their bounds in the rest of the encoding.
+    pub fn encode_int_bit_width(&self, ty: ty::Ty<'tcx>) -> u32 {
+        let bits = match ty.kind() {
+            ty::TyKind::Int(int_ty) => int_ty.bit_width(),
+            ty::TyKind::Uint(uint_ty) => uint_ty.bit_width(),
+            ref x => unreachable!("{:?} is not an integer type", x),
+        };
+        bits.unwrap_or(std::mem::size_of::<usize>() as u64 * 8) as u32
+    }
+
+    fn encode_bitwise_domain_kind(&self, ty: ty::Ty<'tcx>) -> BuiltinDomainKind {
+        BuiltinDomainKind::Bitwise {
+            bits: self.encode_int_bit_width(ty),
+            signed: ty.is_signed(),
+        }
+    }
+
+    /// Applies a function of the domain that encodes bitwise operations on the integer type `ty`.
+    pub fn encode_bitwise_function_app(
+        &self,
+        ty: ty::Ty<'tcx>,
+        function: BitwiseFunctionKind,
+        args: Vec<vir::Expr>,
+    ) -> vir::Expr {
+        trace!("encode_bitwise_function_app(ty={:?}, function={:?})", ty, function);
+        let kind = self.encode_bitwise_domain_kind(ty);
+        self.builtin_domains.borrow_mut().insert(kind.clone());
+        let domain_func = BuiltinEncoder::new().encode_bitwise_function(&kind, function);
+        vir::Expr::domain_func_app(domain_func, args)
+    }
+
+    /// Wraps the mathematical integer `expr` around into the range of the integer type `ty`.
+    pub fn encode_int_wrap(&self, ty: ty::Ty<'tcx>, expr: vir::Expr) -> vir::Expr {
+        let kind = self.encode_bitwise_domain_kind(ty);
+        BuiltinEncoder::new().encode_bitwise_wrap(&kind, expr)
+    }
+
+    /// Encodes the constant `2^exponent`, for exponents of at most 128.
+    pub fn encode_pow2_const(&self, exponent: u32) -> vir::Expr {
+        BuiltinEncoder::new().encode_pow2_const(exponent)
+    }
+
     pub fn encode_cast_function_use(&self, src_ty: ty::Ty<'tcx>, dst_ty: ty::Ty<'tcx>)
         -> EncodingResult<String>
     {
@@ -723,7 +849,7 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             def_id
         );
         if !self.procedures.borrow().contains_key(&def_id) {
-            self.closures_collector.borrow_mut().collect(self.env, def_id.expect_local());
+            self.closures_collector.borrow_mut().collect(self.env, def_id);
             let procedure = self.env.get_procedure(def_id);
             let proc_encoder = ProcedureEncoder::new(self, &procedure)?;
             let mut method = match proc_encoder.encode() {
@@ -782,6 +908,12 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         type_encoder.encode_bounds(var)
     }
 
+    /// The minimum and maximum value of an integer type.
+    pub fn encode_integer_bounds(&self, ty: ty::Ty<'tcx>) -> Option<(vir::Expr, vir::Expr)> {
+        let type_encoder = TypeEncoder::new(self, ty);
+        type_encoder.get_integer_bounds()
+    }
+
     /// See `spec_encoder::encode_spec_assertion` for a description of the arguments.
     pub fn encode_assertion(
         &self,
@@ -825,6 +957,10 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
     pub fn encode_type_predicate_use(&self, ty: ty::Ty<'tcx>)
         -> EncodingResult<String>
     {
+        let ty = self.normalize_projections(ty);
+        if let Some(slice_ty) = self.string_slice_ty(ty) {
+            return self.encode_type_predicate_use(slice_ty);
+        }
         if !self.type_predicate_names.borrow().contains_key(ty.kind()) {
             let type_encoder = TypeEncoder::new(self, ty);
             let name = type_encoder.encode_predicate_use()?;
@@ -871,7 +1007,10 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             | ty::TyKind::Tuple(_)
             | ty::TyKind::Never
             | ty::TyKind::Array(..)
-            | ty::TyKind::Param(_) => true,
+            | ty::TyKind::Param(_)
+            | ty::TyKind::Projection(_) => true,
+            _ if self.string_slice_ty(ty).is_some() => true,
+            _ if self.ghost_collection_kind(ty).is_some() => true,
             ty::TyKind::Adt(_, _) => {
                 self.env().tcx().has_structural_eq_impls(ty)
             }
@@ -879,6 +1018,109 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         }
     }
 
+    /// Returns the kind of `ty` if it is one of the ghost collection types of
+    /// `prusti_contracts`.
+    pub fn ghost_collection_kind(&self, ty: ty::Ty<'tcx>) -> Option<GhostCollectionKind> {
+        match ty.kind() {
+            ty::TyKind::Adt(adt_def, _) => GhostCollectionKind::from_def_path(
+                &self.env().tcx().def_path_str(adt_def.did)
+            ),
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of the ghost collection if `def_id` is a method of one.
+    pub fn ghost_collection_method_kind(&self, def_id: DefId) -> Option<GhostCollectionKind> {
+        let tcx = self.env().tcx();
+        let impl_def_id = tcx.impl_of_method(def_id)?;
+        if tcx.trait_id_of_impl(impl_def_id).is_some() {
+            return None;
+        }
+        self.ghost_collection_kind(tcx.type_of(impl_def_id))
+    }
+
+    /// Encodes a call of a method of a ghost collection, whose arguments are already
+    /// encoded as snapshots.
+    pub fn encode_ghost_method_call(
+        &self,
+        def_id: DefId,
+        substs: ty::subst::SubstsRef<'tcx>,
+        args: Vec<vir::Expr>,
+    ) -> EncodingResult<vir::Expr> {
+        let tcx = self.env().tcx();
+        let impl_def_id = tcx.impl_of_method(def_id).unwrap();
+        let collection_ty = tcx.type_of(impl_def_id).subst(tcx, substs);
+        let kind = self.ghost_collection_kind(collection_ty).unwrap();
+        ghost_encoder::encode_ghost_method_call(
+            kind,
+            self.encode_snapshot_type(collection_ty)?,
+            &tcx.item_name(def_id).as_str(),
+            args,
+        )
+    }
+
+    /// The field of a ghost collection that holds its Viper value of type `collection_ty`.
+    /// Unlike `encode_value_field`, this does not encode the snapshot of `ty`, so that it
+    /// can be used while snapshots are being encoded.
+    pub fn encode_ghost_value_field(
+        &self,
+        ty: ty::Ty<'tcx>,
+        collection_ty: vir::Type,
+    ) -> EncodingResult<vir::Field> {
+        let name = format!("val_{}", self.encode_type_predicate_use(ty)?);
+        let field = vir::Field::new(name, collection_ty);
+        self.fields
+            .borrow_mut()
+            .entry(field.name.clone())
+            .or_insert_with(|| field.clone());
+        Ok(field)
+    }
+
+    /// `str` and `String` are both encoded like the byte slice `[u8]`, i.e. as an abstract
+    /// predicate whose snapshot is the sequence of UTF-8 bytes. Returns that slice type if `ty`
+    /// is a string type.
+    pub fn string_slice_ty(&self, ty: ty::Ty<'tcx>) -> Option<ty::Ty<'tcx>> {
+        let tcx = self.env().tcx();
+        let is_string = match ty.kind() {
+            ty::TyKind::Str => true,
+            ty::TyKind::Adt(adt_def, _) => matches!(
+                &*tcx.def_path_str(adt_def.did),
+                "std::string::String" | "alloc::string::String"
+            ),
+            _ => false,
+        };
+        if is_string {
+            Some(tcx.mk_slice(tcx.types.u8))
+        } else {
+            None
+        }
+    }
+
+    /// Encodes the snapshot of a string literal, i.e. the sequence of its bytes.
+    pub fn encode_str_literal_snapshot(
+        &self,
+        value: &ty::ConstKind<'tcx>,
+    ) -> EncodingResult<vir::Expr> {
+        let bytes = match value {
+            ty::ConstKind::Value(mir::interpret::ConstValue::Slice { data, start, end }) => {
+                data.inspect_with_uninit_and_ptr_outside_interpreter(*start..*end).to_vec()
+            }
+            _ => return Err(EncodingError::unsupported(
+                format!("unsupported string constant: {:?}", value)
+            )),
+        };
+        let tcx = self.env().tcx();
+        let elems = bytes.into_iter().map(vir::Expr::from).collect();
+        self.encode_snapshot_constructor(
+            tcx.mk_slice(tcx.types.u8),
+            vec![vir::Expr::Seq(
+                vir::Type::Seq(box vir::Type::Int),
+                elems,
+                vir::Position::default(),
+            )],
+        )
+    }
+
     pub fn encode_snapshot_type(&self, ty: ty::Ty<'tcx>)
         -> EncodingResult<vir::Type>
     {
@@ -1003,6 +1245,11 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         value: &ty::ConstKind<'tcx>
     ) -> EncodingResult<vir::Expr> {
         trace!("encode_const_expr {:?}", value);
+        if let ty::TyKind::Ref(_, inner_ty, _) = ty.kind() {
+            if inner_ty.is_str() {
+                return self.encode_str_literal_snapshot(value);
+            }
+        }
         let scalar_value = self.const_eval_intlike(value)?;
 
         let expr = match ty.kind() {
@@ -1020,6 +1267,14 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             ty::TyKind::Uint(ty::UintTy::U64) => scalar_value.to_u64().unwrap().into(),
             ty::TyKind::Uint(ty::UintTy::U128) => scalar_value.to_u128().unwrap().into(),
             ty::TyKind::Uint(ty::UintTy::Usize) => scalar_value.to_machine_usize(&self.env().tcx()).unwrap().into(),
+            ty::TyKind::Float(ty::FloatTy::F32) => {
+                let bits = scalar_value.to_f32().unwrap().to_bits() as u32;
+                self.encode_float_const(ty, f64::from(f32::from_bits(bits)), bits.into(), 1 << 24)
+            }
+            ty::TyKind::Float(ty::FloatTy::F64) => {
+                let bits = scalar_value.to_f64().unwrap().to_bits() as u64;
+                self.encode_float_const(ty, f64::from_bits(bits), bits.into(), 1 << 53)
+            }
             ty::TyKind::FnDef(def_id, _) => {
                 self.encode_spec_funcs(*def_id)?;
                 vir::Expr::Const(vir::Const::FnPtr, vir::Position::default())
@@ -1034,6 +1289,25 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         Ok(expr)
     }
 
+    /// Encodes a float constant. Integral values that are exactly representable are encoded
+    /// by rounding the integer, so that the arithmetic axioms of the float domain apply to
+    /// them; all other values (including `-0.0`, infinities and NaN) by their bit pattern.
+    fn encode_float_const(&self, ty: &ty::TyS<'tcx>, value: f64, bits: u128, limit: i64) -> vir::Expr {
+        let is_exact_int = value.is_finite()
+            && value.trunc() == value
+            && value.abs() <= limit as f64
+            && !(value == 0.0 && value.is_sign_negative());
+        if is_exact_int {
+            self.encode_float_function_app(
+                ty,
+                FloatFunctionKind::FromInt,
+                vec![(value as i64).into()],
+            )
+        } else {
+            self.encode_float_function_app(ty, FloatFunctionKind::FromBits, vec![bits.into()])
+        }
+    }
+
     pub fn encode_int_cast(&self, value: u128, ty: ty::Ty<'tcx>) -> vir::Expr {
         trace!("encode_int_cast {:?} as {:?}", value, ty);
 
@@ -1160,7 +1434,9 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
                 PureFunctionEncoder::new(self, proc_def_id, procedure.get_mir(), false);
             let (mut function, needs_patching) = if let Some(predicate_body) = self.get_predicate_body(proc_def_id) {
                 (pure_function_encoder.encode_predicate_function(predicate_body)?, false)
-            } else if self.is_trusted(proc_def_id) {
+            } else if self.is_trusted(proc_def_id) || !proc_def_id.is_local() {
+                // Only the contract of the pure functions of other crates is used, because
+                // their body is not available in a form that can be encoded.
                 (pure_function_encoder.encode_bodyless_function()?, false)
             } else {
                 (pure_function_encoder.encode_function()?, true)
@@ -1311,12 +1587,77 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         result
     }
 
+    /// Resolve a call of the trait method `def_id` with the substitutions `substs` to the method
+    /// of the implementation, if only the latter is pure. This is the case, for example, when an
+    /// external specification marks the method of a standard library type as pure.
+    pub fn resolve_pure_trait_method(
+        &self,
+        def_id: ProcedureDefId,
+        substs: ty::subst::SubstsRef<'tcx>,
+    ) -> ProcedureDefId {
+        let tcx = self.env().tcx();
+        if self.is_pure(def_id) || tcx.trait_of_item(def_id).is_none() {
+            return def_id;
+        }
+        match ty::Instance::resolve(tcx, ty::ParamEnv::reveal_all(), def_id, substs) {
+            Ok(Some(instance)) if self.is_pure(instance.def_id()) => instance.def_id(),
+            _ => def_id,
+        }
+    }
+
     pub fn get_predicate_body(&self, def_id: ProcedureDefId) -> Option<&typed::Assertion<'tcx>> {
         let result = self.def_spec.get(&def_id).map_or(None, |spec| spec.expect_procedure().predicate_body.as_ref());
         trace!("get_predicate_body {:?} = {:?}", def_id, result);
         result
     }
 
+    pub fn get_termination_measure(&self, def_id: ProcedureDefId) -> Option<&typed::Assertion<'tcx>> {
+        let result = self.def_spec.get(&def_id).map_or(None, |spec| spec.expect_procedure().decreases.as_ref());
+        trace!("get_termination_measure {:?} = {:?}", def_id, result);
+        result
+    }
+
+    /// Returns true if a call from `caller` to `callee` might be recursive, i.e. if `callee`
+    /// is `caller` or if it might call `caller` through calls of local functions.
+    pub fn is_recursive_call(&self, caller: ProcedureDefId, callee: ProcedureDefId) -> bool {
+        let mut visited = HashSet::new();
+        let mut to_visit = vec![callee];
+        while let Some(def_id) = to_visit.pop() {
+            if def_id == caller {
+                return true;
+            }
+            if visited.insert(def_id) {
+                to_visit.extend(self.get_local_callees(def_id));
+            }
+        }
+        false
+    }
+
+    fn get_local_callees(&self, def_id: ProcedureDefId) -> Vec<ProcedureDefId> {
+        if !def_id.is_local() || !self.env.tcx().is_mir_available(def_id) {
+            return vec![];
+        }
+        self.local_callees.borrow_mut().entry(def_id).or_insert_with(|| {
+            let mir = self.env.mir(def_id);
+            mir.basic_blocks()
+                .iter()
+                .filter_map(|bb_data| match bb_data.terminator().kind {
+                    mir::TerminatorKind::Call {
+                        func: mir::Operand::Constant(box mir::Constant {
+                            literal: mir::ConstantKind::Ty(ty::Const { ty, .. }),
+                            ..
+                        }),
+                        ..
+                    } => match ty.kind() {
+                        ty::TyKind::FnDef(callee, _) if callee.is_local() => Some(*callee),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect()
+        }).clone()
+    }
+
     pub fn has_extern_spec(&self, def_id: ProcedureDefId) -> bool {
         // FIXME: eventually, procedure specs (the entries in def_spec) should
         // have an `is_extern_spec` field. For now, due to the way we handle
@@ -1345,10 +1686,44 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
                 rep.super_fold_with(self)
             }
         }
-        ty.fold_with(&mut Resolver {
+        let ty = ty.fold_with(&mut Resolver {
             tcx: self.env().tcx(),
             // TODO: creating each time a current_tymap might be slow. This can be optimized.
             tymap: self.current_tymap(),
+        });
+        // substituting type parameters can make projections normalizable
+        self.normalize_projections(ty)
+    }
+
+    /// Normalizes the associated type projections in `ty` whose trait reference is fully
+    /// known, e.g. `<std::ops::Range<usize> as Iterator>::Item` becomes `usize`. Projections
+    /// on type parameters, such as `T::Item`, are kept and encoded like type parameters.
+    pub fn normalize_projections(&self, ty: ty::Ty<'tcx>) -> ty::Ty<'tcx> {
+        use rustc_middle::ty::fold::{TypeFolder, TypeFoldable};
+        struct Normalizer<'tcx> {
+            tcx: ty::TyCtxt<'tcx>,
+        }
+        impl<'tcx> TypeFolder<'tcx> for Normalizer<'tcx> {
+            fn tcx(&self) -> ty::TyCtxt<'tcx> {
+                self.tcx
+            }
+            fn fold_ty(&mut self, ty: ty::Ty<'tcx>) -> ty::Ty<'tcx> {
+                match ty.kind() {
+                    _ if !ty.has_projections() => ty,
+                    ty::TyKind::Projection(_)
+                        if !ty.has_param_types_or_consts() && !ty.has_escaping_bound_vars() =>
+                    {
+                        self.tcx.normalize_erasing_regions(ty::ParamEnv::reveal_all(), ty)
+                    }
+                    _ => ty.super_fold_with(self),
+                }
+            }
+        }
+        if !ty.has_projections() {
+            return ty;
+        }
+        ty.fold_with(&mut Normalizer {
+            tcx: self.env().tcx(),
         })
     }
 
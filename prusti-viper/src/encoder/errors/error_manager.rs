@@ -4,10 +4,10 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use prusti_common::vir::Position;
-use std::collections::HashMap;
+use prusti_common::vir::{self, Position, StmtWalker};
+use std::collections::{HashMap, HashSet};
 use rustc_span::source_map::SourceMap;
-use rustc_span::MultiSpan;
+use rustc_span::{MultiSpan, Span};
 use viper::VerificationError;
 use prusti_interface::PrustiError;
 use log::debug;
@@ -82,6 +82,18 @@ pub enum ErrorCtxt {
     ApplyMagicWandOnExpiry,
     /// A diverging function call performed in a pure function
     DivergingCallInPureFunction,
+    /// A check that the termination measure of a function decreases at a recursive call
+    /// and stays non-negative
+    TerminationMeasureDecrease,
+    /// A Viper `assert expr` that checks that the variant of a loop decreases in every
+    /// iteration and stays non-negative
+    AssertLoopVariantDecrease,
+    /// A Viper `assert expr` that encodes a `prusti_assert!(expr)`
+    PrustiAssert,
+    /// A Viper `inhale expr` that encodes a `prusti_assume!(expr)`
+    PrustiAssume,
+    /// A Viper `assert expr` that encodes a `prusti_refute!(expr)`, which is expected to fail
+    PrustiRefute,
     /// A Viper pure function call with `false` precondition that encodes a Rust panic in a pure function
     PanicInPureFunction(PanicCause),
     /// A Viper `assert e1 ==> e2` that encodes a weakening of the precondition
@@ -96,12 +108,57 @@ pub enum ErrorCtxt {
     Unsupported(String),
 }
 
+impl ErrorCtxt {
+    /// The name of the variant, without its arguments.
+    pub fn category(&self) -> &'static str {
+        match self {
+            ErrorCtxt::Panic(_) => "Panic",
+            ErrorCtxt::ExhaleMethodPrecondition => "ExhaleMethodPrecondition",
+            ErrorCtxt::AssertMethodPostcondition => "AssertMethodPostcondition",
+            ErrorCtxt::AssertMethodPostconditionTypeInvariants => "AssertMethodPostconditionTypeInvariants",
+            ErrorCtxt::ExhaleMethodPostcondition => "ExhaleMethodPostcondition",
+            ErrorCtxt::ExhaleLoopInvariantOnEntry => "ExhaleLoopInvariantOnEntry",
+            ErrorCtxt::ExhaleLoopInvariantAfterIteration => "ExhaleLoopInvariantAfterIteration",
+            ErrorCtxt::AssertLoopInvariantOnEntry => "AssertLoopInvariantOnEntry",
+            ErrorCtxt::AssertLoopInvariantAfterIteration => "AssertLoopInvariantAfterIteration",
+            ErrorCtxt::AssertTerminator(_) => "AssertTerminator",
+            ErrorCtxt::BoundsCheckAssert => "BoundsCheckAssert",
+            ErrorCtxt::AbortTerminator => "AbortTerminator",
+            ErrorCtxt::UnreachableTerminator => "UnreachableTerminator",
+            ErrorCtxt::Unexpected => "Unexpected",
+            ErrorCtxt::PureFunctionDefinition => "PureFunctionDefinition",
+            ErrorCtxt::PureFunctionCall => "PureFunctionCall",
+            ErrorCtxt::PureFunctionPostconditionValueRangeOfResult => "PureFunctionPostconditionValueRangeOfResult",
+            ErrorCtxt::PureFunctionAssertTerminator(_) => "PureFunctionAssertTerminator",
+            ErrorCtxt::GenericExpression => "GenericExpression",
+            ErrorCtxt::GenericStatement => "GenericStatement",
+            ErrorCtxt::PackageMagicWandForPostcondition => "PackageMagicWandForPostcondition",
+            ErrorCtxt::ApplyMagicWandOnExpiry => "ApplyMagicWandOnExpiry",
+            ErrorCtxt::DivergingCallInPureFunction => "DivergingCallInPureFunction",
+            ErrorCtxt::TerminationMeasureDecrease => "TerminationMeasureDecrease",
+            ErrorCtxt::AssertLoopVariantDecrease => "AssertLoopVariantDecrease",
+            ErrorCtxt::PrustiAssert => "PrustiAssert",
+            ErrorCtxt::PrustiAssume => "PrustiAssume",
+            ErrorCtxt::PrustiRefute => "PrustiRefute",
+            ErrorCtxt::PanicInPureFunction(_) => "PanicInPureFunction",
+            ErrorCtxt::AssertMethodPreconditionWeakening(_) => "AssertMethodPreconditionWeakening",
+            ErrorCtxt::AssertMethodPostconditionStrengthening(_) => "AssertMethodPostconditionStrengthening",
+            ErrorCtxt::TypeCast => "TypeCast",
+            ErrorCtxt::Unsupported(_) => "Unsupported",
+        }
+    }
+}
+
 /// The error manager
 #[derive(Clone)]
 pub struct ErrorManager<'tcx> {
     codemap: &'tcx SourceMap,
     source_span: HashMap<u64, MultiSpan>,
     error_contexts: HashMap<u64, ErrorCtxt>,
+    /// For each encoded procedure, its span and the Rust names of its Viper local variables.
+    procedure_variables: Vec<(Span, HashMap<String, String>)>,
+    /// The positions of the encoded `prusti_refute!` checks.
+    refutations: Vec<u64>,
     next_pos_id: u64,
 }
 
@@ -112,6 +169,8 @@ impl<'tcx> ErrorManager<'tcx>
             codemap,
             source_span: HashMap::new(),
             error_contexts: HashMap::new(),
+            procedure_variables: Vec::new(),
+            refutations: Vec::new(),
             next_pos_id: 1,
         }
     }
@@ -155,6 +214,110 @@ impl<'tcx> ErrorManager<'tcx>
         self.error_contexts.insert(pos.id(), error_ctxt);
     }
 
+    /// Register the position of a `prusti_refute!` check, whose failure is the expected outcome.
+    pub fn register_refutation<T: Into<MultiSpan>>(&mut self, span: T) -> Position {
+        let pos = self.register(span, ErrorCtxt::PrustiRefute);
+        self.refutations.push(pos.id());
+        pos
+    }
+
+    /// The positions of the refutations that are checked in `method`.
+    pub fn method_refutations(&self, method: &vir::CfgMethod) -> HashSet<u64> {
+        struct RefutationCollector<'a> {
+            refutations: &'a [u64],
+            found: HashSet<u64>,
+        }
+        impl<'a> StmtWalker for RefutationCollector<'a> {
+            fn walk_assert(&mut self, _expr: &vir::Expr, pos: &Position) {
+                if self.refutations.contains(&pos.id()) {
+                    self.found.insert(pos.id());
+                }
+            }
+        }
+        let mut collector = RefutationCollector {
+            refutations: &self.refutations,
+            found: HashSet::new(),
+        };
+        method.walk_statements(|stmt| collector.walk(stmt));
+        collector.found
+    }
+
+    /// Remove from `ver_errors` the failures of refutations, which are expected, and report
+    /// an error for each refutation that never failed. Only the refutations in
+    /// `checked_refutations`, which belong to methods whose verification completed, are
+    /// considered; the verifier has to be configured to report all the errors of these methods.
+    ///
+    /// A refutation can be encoded more than once (e.g. inside a loop), so it succeeds if any
+    /// of its encodings fails.
+    pub fn check_refutations(
+        &self,
+        ver_errors: Vec<VerificationError>,
+        checked_refutations: &HashSet<u64>,
+    ) -> (Vec<VerificationError>, Vec<PrustiError>) {
+        let mut failed_refutations = HashSet::new();
+        let mut other_errors = vec![];
+        for ver_error in ver_errors {
+            let opt_pos_id = ver_error.pos_id.as_ref()
+                .and_then(|pos_id| pos_id.parse::<u64>().ok());
+            match opt_pos_id.map(|pos_id| (pos_id, self.error_contexts.get(&pos_id))) {
+                Some((pos_id, Some(ErrorCtxt::PrustiRefute)))
+                    if ver_error.full_id == "assert.failed:assertion.false" =>
+                {
+                    failed_refutations.insert(self.source_span[&pos_id].primary_span());
+                }
+                _ => other_errors.push(ver_error),
+            }
+        }
+
+        let mut reported_refutations = HashSet::new();
+        let mut refutation_errors = vec![];
+        for pos_id in self.refutations.iter().filter(|pos_id| checked_refutations.contains(pos_id)) {
+            let span = &self.source_span[pos_id];
+            let primary_span = span.primary_span();
+            if !failed_refutations.contains(&primary_span)
+                && reported_refutations.insert(primary_span)
+            {
+                refutation_errors.push(PrustiError::verification(
+                    "the refuted expression holds in all cases or could not be reached",
+                    span.clone(),
+                ));
+            }
+        }
+        (other_errors, refutation_errors)
+    }
+
+    /// Register the Rust names of the Viper variables that track the values of the user
+    /// variables of the procedure at `span`. These are used to translate counterexamples.
+    pub fn register_procedure_variables(&mut self, span: Span, names: HashMap<String, String>) {
+        self.procedure_variables.push((span, names));
+    }
+
+    /// Translate the counterexample attached to a verification error, if any, to a list of
+    /// Rust variables and their values.
+    ///
+    /// Only the variables of the innermost procedure containing the error are reported.
+    pub fn translate_counterexample(&self, ver_error: &VerificationError) -> Option<Vec<(String, String)>> {
+        let counterexample = ver_error.counterexample.as_ref()?;
+        let error_span = ver_error.pos_id.as_ref()
+            .and_then(|pos_id| pos_id.parse::<u64>().ok())
+            .and_then(|pos_id| self.source_span.get(&pos_id))
+            .and_then(|span| span.primary_span())?;
+        let (_, names) = self.procedure_variables.iter()
+            .filter(|(proc_span, _)| proc_span.contains(error_span))
+            .min_by_key(|(proc_span, _)| proc_span.hi() - proc_span.lo())?;
+
+        let mut entries = vec![];
+        for (viper_name, value) in &counterexample.entries {
+            // Silicon might suffix the variable names with a version (e.g. `cex$_1@3`).
+            let viper_name = viper_name.split('@').next().unwrap();
+            if let Some(rust_name) = names.get(viper_name) {
+                entries.push((rust_name.clone(), translate_model_value(value)));
+            }
+        }
+        debug!("Counterexample of {:?}: {:?}", ver_error, entries);
+        Some(entries)
+    }
+
     pub fn translate_verification_error(&self, ver_error: &VerificationError) -> PrustiError {
         debug!("Verification error: {:?}", ver_error);
         let opt_pos_id: Option<u64> = match ver_error.pos_id {
@@ -244,7 +407,7 @@ impl<'tcx> ErrorManager<'tcx>
             }
         };
 
-        match (ver_error.full_id.as_str(), error_ctxt) {
+        let error = match (ver_error.full_id.as_str(), error_ctxt) {
             ("assert.failed:assertion.false", ErrorCtxt::Panic(PanicCause::Generic)) => {
                 PrustiError::verification("statement might panic", error_span)
                     .set_failing_assertion(opt_cause_span)
@@ -361,6 +524,26 @@ impl<'tcx> ErrorManager<'tcx>
                 ).push_primary_span(opt_cause_span)
             }
 
+            ("application.precondition:assertion.false", ErrorCtxt::TerminationMeasureDecrease) |
+            ("assert.failed:assertion.false", ErrorCtxt::TerminationMeasureDecrease) => {
+                PrustiError::verification(
+                    "termination measure might not decrease at this recursive call.",
+                    error_span
+                ).push_primary_span(opt_cause_span)
+            }
+
+            ("assert.failed:assertion.false", ErrorCtxt::PrustiAssert) => {
+                PrustiError::verification("the asserted expression might not hold", error_span)
+                    .set_failing_assertion(opt_cause_span)
+            }
+
+            ("assert.failed:assertion.false", ErrorCtxt::AssertLoopVariantDecrease) => {
+                PrustiError::verification(
+                    "loop variant might not decrease in this loop iteration.",
+                    error_span
+                ).push_primary_span(opt_cause_span)
+            }
+
             (
                 "application.precondition:assertion.false",
                 ErrorCtxt::PanicInPureFunction(PanicCause::Generic),
@@ -525,6 +708,16 @@ impl<'tcx> ErrorManager<'tcx>
                     ASSERT_TIMEOUT to a larger value."
                 )
             }
-        }
+        };
+        error.set_context(error_ctxt.category())
+    }
+}
+
+/// Translate a value of the SMT model of a counterexample to Rust syntax.
+fn translate_model_value(value: &str) -> String {
+    // Negative integers are reported as `(- n)`.
+    match value.strip_prefix("(- ").and_then(|value| value.strip_suffix(')')) {
+        Some(abs_value) => format!("-{}", abs_value),
+        None => value.to_string(),
     }
 }
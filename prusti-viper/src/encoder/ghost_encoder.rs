@@ -0,0 +1,139 @@
+// © 2021, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Encoding of the ghost collection types `Seq`, `Set` and `Map` of
+//! `prusti_contracts`. They can only be used in specifications and are
+//! encoded directly as the corresponding Viper collections.
+
+use prusti_common::vir::{self, ContainerOpKind};
+use crate::encoder::errors::{EncodingError, EncodingResult};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GhostCollectionKind {
+    Seq,
+    Set,
+    Map,
+}
+
+impl GhostCollectionKind {
+    /// Recognizes a ghost collection type by the definition path of its ADT.
+    pub fn from_def_path(def_path: &str) -> Option<Self> {
+        match def_path {
+            "prusti_contracts::Seq" => Some(GhostCollectionKind::Seq),
+            "prusti_contracts::Set" => Some(GhostCollectionKind::Set),
+            "prusti_contracts::Map" => Some(GhostCollectionKind::Map),
+            _ => None,
+        }
+    }
+
+    /// Builds the Viper collection type from the snapshot types of the type
+    /// arguments of the collection.
+    pub fn encode_type(self, mut type_args: Vec<vir::Type>) -> vir::Type {
+        match self {
+            GhostCollectionKind::Seq => vir::Type::Seq(box type_args.remove(0)),
+            GhostCollectionKind::Set => vir::Type::Set(box type_args.remove(0)),
+            GhostCollectionKind::Map => {
+                let val_ty = type_args.remove(1);
+                let key_ty = type_args.remove(0);
+                vir::Type::Map(box key_ty, box val_ty)
+            }
+        }
+    }
+
+    fn len_op(self) -> ContainerOpKind {
+        match self {
+            GhostCollectionKind::Seq => ContainerOpKind::SeqLen,
+            GhostCollectionKind::Set => ContainerOpKind::SetLen,
+            GhostCollectionKind::Map => ContainerOpKind::MapLen,
+        }
+    }
+}
+
+fn container_op(kind: ContainerOpKind, left: vir::Expr, right: vir::Expr) -> vir::Expr {
+    vir::Expr::ContainerOp(kind, box left, box right, vir::Position::default())
+}
+
+/// Encodes a call of the method `method` of a ghost collection of type
+/// `collection_ty`. The arguments (including the receiver) are snapshots.
+pub fn encode_ghost_method_call(
+    kind: GhostCollectionKind,
+    collection_ty: vir::Type,
+    method: &str,
+    args: Vec<vir::Expr>,
+) -> EncodingResult<vir::Expr> {
+    use self::GhostCollectionKind::*;
+    let literal = |elems| vir::Expr::Seq(collection_ty.clone(), elems, vir::Position::default());
+    Ok(match (kind, method, &args[..]) {
+        (_, "empty", []) => literal(vec![]),
+        (Seq, "single", [elem])
+        | (Set, "single", [elem]) => literal(vec![elem.clone()]),
+        (Map, "single", [key, value]) => literal(vec![
+            container_op(ContainerOpKind::Maplet, key.clone(), value.clone()),
+        ]),
+        (_, "len", [collection]) => {
+            container_op(kind.len_op(), collection.clone(), vir::Expr::from(0))
+        }
+        (_, "is_empty", [collection]) => vir::Expr::eq_cmp(
+            container_op(kind.len_op(), collection.clone(), vir::Expr::from(0)),
+            vir::Expr::from(0),
+        ),
+
+        (Seq, "push", [seq, elem]) => container_op(
+            ContainerOpKind::SeqConcat,
+            seq.clone(),
+            literal(vec![elem.clone()]),
+        ),
+        (Seq, "concat", [left, right]) => {
+            container_op(ContainerOpKind::SeqConcat, left.clone(), right.clone())
+        }
+        (Seq, "lookup", [seq, index]) => {
+            container_op(ContainerOpKind::SeqIndex, seq.clone(), index.clone())
+        }
+        (Seq, "contains", [seq, elem]) => {
+            container_op(ContainerOpKind::SeqContains, seq.clone(), elem.clone())
+        }
+
+        (Set, "insert", [set, elem]) => container_op(
+            ContainerOpKind::SetUnion,
+            set.clone(),
+            literal(vec![elem.clone()]),
+        ),
+        (Set, "union", [left, right]) => {
+            container_op(ContainerOpKind::SetUnion, left.clone(), right.clone())
+        }
+        (Set, "intersection", [left, right]) => {
+            container_op(ContainerOpKind::SetIntersection, left.clone(), right.clone())
+        }
+        (Set, "difference", [left, right]) => {
+            container_op(ContainerOpKind::SetMinus, left.clone(), right.clone())
+        }
+        (Set, "is_subset", [left, right]) => {
+            container_op(ContainerOpKind::SetSubset, left.clone(), right.clone())
+        }
+        (Set, "contains", [set, elem]) => {
+            container_op(ContainerOpKind::SetContains, set.clone(), elem.clone())
+        }
+
+        (Map, "insert", [map, key, value]) => container_op(
+            ContainerOpKind::MapUpdate,
+            map.clone(),
+            container_op(ContainerOpKind::Maplet, key.clone(), value.clone()),
+        ),
+        (Map, "lookup", [map, key]) => {
+            container_op(ContainerOpKind::MapLookup, map.clone(), key.clone())
+        }
+        (Map, "contains_key", [map, key]) => {
+            container_op(ContainerOpKind::MapContains, map.clone(), key.clone())
+        }
+
+        _ => {
+            return Err(EncodingError::unsupported(format!(
+                "the method {:?}::{} of ghost collections is not supported",
+                kind, method,
+            )));
+        }
+    })
+}
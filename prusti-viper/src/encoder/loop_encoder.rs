@@ -154,6 +154,7 @@ impl<'p, 'tcx: 'p> LoopEncoder<'p, 'tcx> {
                     && self.mir()[bb].terminator().successors().any(|&succ_bb| {
                         self.procedure.is_reachable_block(succ_bb)
                             && self.procedure.is_spec_block(succ_bb)
+                            && !self.procedure.is_ghost_statement_block(succ_bb)
                     })
             })
             .cloned()
@@ -6,8 +6,12 @@
 
 mod downcast_detector;
 mod place_encoding;
+mod raw_deref_detector;
+mod read_locals_detector;
 
+use crate::encoder::builtin_encoder::BitwiseFunctionKind;
 use crate::encoder::builtin_encoder::BuiltinFunctionKind;
+use crate::encoder::builtin_encoder::FloatFunctionKind;
 use crate::encoder::errors::{
     ErrorCtxt, PanicCause, SpannedEncodingError, EncodingError, WithSpan,
     SpannedEncodingResult, EncodingResult
@@ -30,6 +34,8 @@ use prusti_interface::environment::mir_utils::MirPlace;
 
 use downcast_detector::detect_downcasts;
 pub use place_encoding::{PlaceEncoding, ExprOrArrayBase};
+pub use raw_deref_detector::detect_raw_pointer_deref;
+pub use read_locals_detector::detect_read_locals;
 
 pub static PRECONDITION_LABEL: &'static str = "pre";
 pub static WAND_LHS_LABEL: &'static str = "lhs";
@@ -280,9 +286,50 @@ pub trait PlaceEncoder<'v, 'tcx: 'v> {
                 }
             }
 
-            mir::ProjectionElem::Subslice { .. } => return Err(EncodingError::unsupported(
-                "slice patterns are not supported",
-            )),
+            mir::ProjectionElem::Subslice { from, to, from_end: true } => {
+                debug!("subslice: {:?}[{}..len - {}]", encoded_base, from, to);
+                match base_ty.kind() {
+                    // A subslice of a slice has the same type as the slice itself
+                    ty::TyKind::Slice(_) => (
+                        PlaceEncoding::SubsliceAccess {
+                            base: box encoded_base,
+                            from: (*from).into(),
+                            to: (*to).into(),
+                            encoded_slice_ty: self.encoder().encode_type(base_ty)?,
+                            rust_slice_ty: base_ty,
+                        },
+                        base_ty,
+                        None,
+                    ),
+                    _ => return Err(EncodingError::unsupported(
+                        format!("subslice patterns on '{:?}' are not supported", base_ty),
+                    )),
+                }
+            }
+
+            mir::ProjectionElem::Subslice { from, to, from_end: false } => {
+                debug!("subslice: {:?}[{}..{}]", encoded_base, from, to);
+                match base_ty.kind() {
+                    // A subslice of an array is an array of the length of the subslice
+                    ty::TyKind::Array(elem_ty, _) => {
+                        let subarray_ty = self.encoder().env().tcx().mk_array(elem_ty, to - from);
+                        (
+                            PlaceEncoding::ArraySubsliceAccess {
+                                base: box encoded_base,
+                                from: *from as usize,
+                                encoded_subarray_ty: self.encoder().encode_type(subarray_ty)?,
+                                rust_array_ty: base_ty,
+                                rust_subarray_ty: subarray_ty,
+                            },
+                            subarray_ty,
+                            None,
+                        )
+                    }
+                    _ => return Err(EncodingError::unsupported(
+                        format!("subslice patterns on '{:?}' are not supported", base_ty),
+                    )),
+                }
+            }
         })
     }
 
@@ -518,6 +565,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
         self.encoder.encode_snapshot_type(ty)
     }
 
+    /// Encodes a binary operation whose operands are of type `ty`.
     pub fn encode_bin_op_expr(
         &self,
         op: mir::BinOp,
@@ -525,6 +573,9 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
         right: vir::Expr,
         ty: ty::Ty<'tcx>,
     ) -> EncodingResult<vir::Expr> {
+        if let ty::TyKind::Float(_) = ty.kind() {
+            return self.encode_float_bin_op_expr(op, left, right, ty);
+        }
         let is_bool = ty.kind() == &ty::TyKind::Bool;
         Ok(match op {
             mir::BinOp::Eq => vir::Expr::eq_cmp(left, right),
@@ -543,10 +594,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
             mir::BinOp::BitXor if is_bool => vir::Expr::xor(left, right),
             mir::BinOp::BitAnd |
             mir::BinOp::BitOr |
-            mir::BinOp::BitXor => {
-                return Err(EncodingError::unsupported(
-                    "bitwise operations on non-boolean types are not supported"
-                ))
+            mir::BinOp::BitXor |
+            mir::BinOp::Shl |
+            mir::BinOp::Shr => {
+                return self.encode_bitwise_bin_op_expr(op, left, right, ty);
             }
             unsupported_op => {
                 return Err(EncodingError::unsupported(format!(
@@ -557,9 +608,109 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
         })
     }
 
-    pub fn encode_unary_op_expr(&self, op: mir::UnOp, expr: vir::Expr) -> vir::Expr {
+    /// Encodes a binary operation on floats using the functions of the float domains.
+    fn encode_float_bin_op_expr(
+        &self,
+        op: mir::BinOp,
+        left: vir::Expr,
+        right: vir::Expr,
+        ty: ty::Ty<'tcx>,
+    ) -> EncodingResult<vir::Expr> {
+        let (function, args) = match op {
+            mir::BinOp::Eq | mir::BinOp::Ne => (FloatFunctionKind::Eq, vec![left, right]),
+            mir::BinOp::Lt => (FloatFunctionKind::Lt, vec![left, right]),
+            mir::BinOp::Le => (FloatFunctionKind::Le, vec![left, right]),
+            mir::BinOp::Gt => (FloatFunctionKind::Lt, vec![right, left]),
+            mir::BinOp::Ge => (FloatFunctionKind::Le, vec![right, left]),
+            mir::BinOp::Add => (FloatFunctionKind::Add, vec![left, right]),
+            mir::BinOp::Sub => (FloatFunctionKind::Sub, vec![left, right]),
+            mir::BinOp::Mul => (FloatFunctionKind::Mul, vec![left, right]),
+            mir::BinOp::Div => (FloatFunctionKind::Div, vec![left, right]),
+            mir::BinOp::Rem => (FloatFunctionKind::Rem, vec![left, right]),
+            unsupported_op => {
+                return Err(EncodingError::unsupported(format!(
+                    "operation '{:?}' on floats is not supported",
+                    unsupported_op
+                )))
+            }
+        };
+        let result = self.encoder.encode_float_function_app(ty, function, args);
+        Ok(if op == mir::BinOp::Ne {
+            vir::Expr::not(result)
+        } else {
+            result
+        })
+    }
+
+    /// Encodes a bitwise operation or a shift on integers. Operations on constants are
+    /// evaluated, shifts by a constant amount and masks of the form `2^k - 1` are encoded with
+    /// integer arithmetic, and everything else uses the functions of the bitwise domains.
+    fn encode_bitwise_bin_op_expr(
+        &self,
+        op: mir::BinOp,
+        left: vir::Expr,
+        right: vir::Expr,
+        ty: ty::Ty<'tcx>,
+    ) -> EncodingResult<vir::Expr> {
+        if !ty.is_integral() {
+            return Err(EncodingError::unsupported(format!(
+                "operation '{:?}' on type '{:?}' is not supported",
+                op,
+                ty,
+            )));
+        }
+        let bits = self.encoder.encode_int_bit_width(ty);
+        let left_value = const_int_value(&left);
+        let right_value = const_int_value(&right);
+        let shift_amount = right_value.filter(|amount| 0 <= *amount && *amount < bits as i128);
+        // The number of bits `k` of a mask `2^k - 1`.
+        let mask_bits = |value: Option<i128>| value
+            .filter(|value| *value >= 0 && (*value as u128 + 1).is_power_of_two())
+            .map(|value| (value as u128 + 1).trailing_zeros());
+        let function = match (op, left_value, right_value) {
+            (mir::BinOp::BitAnd, Some(l), Some(r)) => return Ok((l & r).into()),
+            (mir::BinOp::BitOr, Some(l), Some(r)) => return Ok((l | r).into()),
+            (mir::BinOp::BitXor, Some(l), Some(r)) => return Ok((l ^ r).into()),
+            (mir::BinOp::BitAnd, _, _) if mask_bits(right_value).is_some() => {
+                let modulus = self.encoder.encode_pow2_const(mask_bits(right_value).unwrap());
+                return Ok(vir::Expr::modulo(left, modulus));
+            }
+            (mir::BinOp::BitAnd, _, _) if mask_bits(left_value).is_some() => {
+                let modulus = self.encoder.encode_pow2_const(mask_bits(left_value).unwrap());
+                return Ok(vir::Expr::modulo(right, modulus));
+            }
+            (mir::BinOp::Shl, _, _) if shift_amount.is_some() => {
+                let factor = self.encoder.encode_pow2_const(shift_amount.unwrap() as u32);
+                return Ok(self.encoder.encode_int_wrap(ty, vir::Expr::mul(left, factor)));
+            }
+            (mir::BinOp::Shr, _, _) if shift_amount.is_some() => {
+                // Viper's integer division rounds towards negative infinity for positive
+                // divisors, like an arithmetic right shift.
+                let divisor = self.encoder.encode_pow2_const(shift_amount.unwrap() as u32);
+                return Ok(vir::Expr::div(left, divisor));
+            }
+            (mir::BinOp::BitAnd, _, _) => BitwiseFunctionKind::BitAnd,
+            (mir::BinOp::BitOr, _, _) => BitwiseFunctionKind::BitOr,
+            (mir::BinOp::BitXor, _, _) => BitwiseFunctionKind::BitXor,
+            (mir::BinOp::Shl, _, _) => BitwiseFunctionKind::Shl,
+            (mir::BinOp::Shr, _, _) => BitwiseFunctionKind::Shr,
+            _ => unreachable!("{:?} is not a bitwise operation", op),
+        };
+        Ok(self.encoder.encode_bitwise_function_app(ty, function, vec![left, right]))
+    }
+
+    /// Encodes a unary operation whose operand is of type `ty`.
+    pub fn encode_unary_op_expr(
+        &self,
+        op: mir::UnOp,
+        expr: vir::Expr,
+        ty: ty::Ty<'tcx>,
+    ) -> vir::Expr {
         match op {
             mir::UnOp::Not => vir::Expr::not(expr),
+            mir::UnOp::Neg if ty.is_floating_point() => {
+                self.encoder.encode_float_function_app(ty, FloatFunctionKind::Neg, vec![expr])
+            }
             mir::UnOp::Neg => vir::Expr::minus(expr),
         }
     }
@@ -575,6 +726,14 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
         if !op.is_checkable() || !config::check_overflows() {
             Ok(false.into())
         } else {
+            if let mir::BinOp::Shl | mir::BinOp::Shr = op {
+                // The shift amount must be smaller than the number of bits of the shifted value.
+                let bits = self.encoder.encode_int_bit_width(ty);
+                return Ok(vir::Expr::or(
+                    vir::Expr::lt_cmp(right.clone(), 0.into()),
+                    vir::Expr::ge_cmp(right, bits.into()),
+                ));
+            }
             let result = self.encode_bin_op_expr(op, left, right, ty)?;
 
             Ok(match op {
@@ -639,12 +798,6 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
                     }
                 },
 
-                mir::BinOp::Shl | mir::BinOp::Shr => {
-                    return Err(EncodingError::unsupported(
-                        "overflow checks on a shift operation are unsupported",
-                    ));
-                }
-
                 _ => unreachable!("{:?}", op),
             })
         }
@@ -739,6 +892,59 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
                 }
             }
 
+            (ty::TyKind::Float(src_float_ty), ty::TyKind::Float(dst_float_ty)) => {
+                let encoded_operand = self.encode_operand_expr(operand).with_span(span)?;
+                if src_float_ty == dst_float_ty {
+                    encoded_operand
+                } else {
+                    self.encoder.encode_float_function_app(
+                        dst_ty,
+                        FloatFunctionKind::FromOther,
+                        vec![encoded_operand],
+                    )
+                }
+            }
+
+            (ty::TyKind::Int(_), ty::TyKind::Float(_))
+            | (ty::TyKind::Uint(_), ty::TyKind::Float(_)) => {
+                let encoded_operand = self.encode_operand_expr(operand).with_span(span)?;
+                self.encoder.encode_float_function_app(
+                    dst_ty,
+                    FloatFunctionKind::FromInt,
+                    vec![encoded_operand],
+                )
+            }
+
+            // Float to integer casts saturate and map NaN to zero
+            (ty::TyKind::Float(_), ty::TyKind::Int(_))
+            | (ty::TyKind::Float(_), ty::TyKind::Uint(_)) => {
+                let encoded_operand = self.encode_operand_expr(operand).with_span(span)?;
+                let (lower, upper) = self.encoder.encode_integer_bounds(dst_ty).unwrap();
+                let is_nan = self.encoder.encode_float_function_app(
+                    src_ty,
+                    FloatFunctionKind::IsNan,
+                    vec![encoded_operand.clone()],
+                );
+                let truncated = self.encoder.encode_float_function_app(
+                    src_ty,
+                    FloatFunctionKind::ToInt,
+                    vec![encoded_operand],
+                );
+                vir::Expr::ite(
+                    is_nan,
+                    0.into(),
+                    vir::Expr::ite(
+                        vir::Expr::lt_cmp(truncated.clone(), lower.clone()),
+                        lower,
+                        vir::Expr::ite(
+                            vir::Expr::gt_cmp(truncated.clone(), upper.clone()),
+                            upper,
+                            truncated,
+                        ),
+                    ),
+                )
+            }
+
             _ => {
                 return Err(SpannedEncodingError::unsupported(
                     format!(
@@ -833,3 +1039,13 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
         }
     }
 }
+
+/// The value of an encoded integer constant, if it fits into an `i128`.
+fn const_int_value(expr: &vir::Expr) -> Option<i128> {
+    match expr {
+        vir::Expr::Const(vir::Const::Int(value), _) => Some(*value as i128),
+        vir::Expr::Const(vir::Const::BigInt(value), _) => value.parse().ok(),
+        vir::Expr::SnapApp(box expr, _) => const_int_value(expr),
+        _ => None,
+    }
+}
@@ -45,13 +45,29 @@ pub enum PlaceEncoding<'tcx> {
         encoded_elem_ty: vir::Type,
         rust_slice_ty: ty::Ty<'tcx>,
     },
+    /// Subslice projection `base[from..len - to]`, as created by slice patterns
+    SubsliceAccess {
+        base: Box<PlaceEncoding<'tcx>>,
+        from: vir::Expr,
+        to: vir::Expr,
+        encoded_slice_ty: vir::Type,
+        rust_slice_ty: ty::Ty<'tcx>,
+    },
+    /// Subarray projection `base[from..from + len]` of an array, as created by array patterns,
+    /// where `len` is the length of the subarray type
+    ArraySubsliceAccess {
+        base: Box<PlaceEncoding<'tcx>>,
+        from: usize,
+        encoded_subarray_ty: vir::Type,
+        rust_array_ty: ty::Ty<'tcx>,
+        rust_subarray_ty: ty::Ty<'tcx>,
+    },
 }
 
 /// Return type of PlaceEncoding::into_array_base
 pub enum ExprOrArrayBase {
     Expr(vir::Expr),
     ArrayBase(vir::Expr),
-    #[allow(dead_code)]
     SliceBase(vir::Expr),
 }
 
@@ -87,7 +103,8 @@ impl<'tcx> PlaceEncoding<'tcx> {
                     base@ExprOrArrayBase::SliceBase(_) => base,
                 }
             }
-            PlaceEncoding::ArrayAccess { base, .. } => {
+            PlaceEncoding::ArrayAccess { base, .. }
+            | PlaceEncoding::ArraySubsliceAccess { base, .. } => {
                 // need to check base's into_expr_or_array_base, maybe we're not the outermost
                 // array
                 match base.into_array_base() {
@@ -96,7 +113,8 @@ impl<'tcx> PlaceEncoding<'tcx> {
                     base@ExprOrArrayBase::SliceBase(_) => base,
                 }
             }
-            PlaceEncoding::SliceAccess { base, .. } => {
+            PlaceEncoding::SliceAccess { base, .. }
+            | PlaceEncoding::SubsliceAccess { base, .. } => {
                 match base.into_array_base() {
                     ExprOrArrayBase::Expr(e) => ExprOrArrayBase::SliceBase(e),
                     base@ExprOrArrayBase::ArrayBase(_) => base,
@@ -117,6 +135,8 @@ impl<'tcx> PlaceEncoding<'tcx> {
             PlaceEncoding::ArrayAccess { ref encoded_elem_ty, .. } => encoded_elem_ty,
             PlaceEncoding::Variant { ref field, .. } => &field.typ,
             PlaceEncoding::SliceAccess { ref encoded_elem_ty, .. } => encoded_elem_ty,
+            PlaceEncoding::SubsliceAccess { ref encoded_slice_ty, .. } => encoded_slice_ty,
+            PlaceEncoding::ArraySubsliceAccess { ref encoded_subarray_ty, .. } => encoded_subarray_ty,
         }
     }
 
@@ -143,6 +163,12 @@ impl<'tcx> Display for PlaceEncoding<'tcx> {
             PlaceEncoding::ArrayAccess { base, index, .. } => write!(f, "{}[{}]", base, index),
             PlaceEncoding::Variant { base, field } => write!(f, "{}[{}]", base, field),
             PlaceEncoding::SliceAccess { base, index, .. } => write!(f, "{}[{}]", base, index),
+            PlaceEncoding::SubsliceAccess { base, from, to, .. } => {
+                write!(f, "{}[{}..len-{}]", base, from, to)
+            }
+            PlaceEncoding::ArraySubsliceAccess { base, from, rust_subarray_ty, .. } => {
+                write!(f, "{}[{}..{}+len({:?})]", base, from, from, rust_subarray_ty)
+            }
         }
     }
 }
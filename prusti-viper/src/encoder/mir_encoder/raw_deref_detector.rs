@@ -0,0 +1,39 @@
+use rustc_middle::mir;
+use rustc_middle::mir::visit::Visitor;
+
+/// Returns the location of the first statement or terminator that dereferences the raw pointer
+/// stored in `local`, if any.
+pub fn detect_raw_pointer_deref<'tcx>(body: &mir::Body<'tcx>, local: mir::Local)
+    -> Option<mir::Location>
+{
+    if !body.local_decls[local].ty.is_unsafe_ptr() {
+        return None;
+    }
+    let mut collector = RawDerefCollector { local, location: None };
+    collector.visit_body(body);
+    collector.location
+}
+
+struct RawDerefCollector {
+    local: mir::Local,
+    location: Option<mir::Location>,
+}
+
+impl<'tcx> Visitor<'tcx> for RawDerefCollector {
+    fn visit_projection_elem(
+        &mut self,
+        local: mir::Local,
+        proj_base: &[mir::PlaceElem<'tcx>],
+        elem: mir::PlaceElem<'tcx>,
+        context: mir::visit::PlaceContext,
+        location: mir::Location,
+    ) {
+        self.super_projection_elem(local, proj_base, elem, context, location);
+
+        if local == self.local && proj_base.is_empty() && elem == mir::PlaceElem::Deref
+            && self.location.is_none()
+        {
+            self.location = Some(location);
+        }
+    }
+}
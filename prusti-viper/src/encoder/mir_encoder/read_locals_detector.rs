@@ -0,0 +1,38 @@
+use rustc_middle::mir;
+use rustc_middle::mir::visit::{MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor};
+
+/// Returns the local variables that the statement or terminator at `location` reads or borrows
+/// as a whole. The permission to read them is available right before `location`.
+pub fn detect_read_locals<'tcx>(body: &mir::Body<'tcx>, location: mir::Location)
+    -> Vec<mir::Local>
+{
+    let mut collector = ReadLocalsCollector { locals: vec![] };
+    collector.visit_location(body, location);
+    collector.locals
+}
+
+struct ReadLocalsCollector {
+    locals: Vec<mir::Local>,
+}
+
+impl<'tcx> Visitor<'tcx> for ReadLocalsCollector {
+    fn visit_place(
+        &mut self,
+        place: &mir::Place<'tcx>,
+        context: PlaceContext,
+        location: mir::Location,
+    ) {
+        self.super_place(place, context, location);
+
+        let is_read = matches!(
+            context,
+            PlaceContext::NonMutatingUse(NonMutatingUseContext::Copy)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::Inspect)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::SharedBorrow)
+            | PlaceContext::MutatingUse(MutatingUseContext::Borrow)
+        );
+        if is_read && place.projection.is_empty() && !self.locals.contains(&place.local) {
+            self.locals.push(place.local);
+        }
+    }
+}
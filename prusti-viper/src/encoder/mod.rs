@@ -12,6 +12,7 @@ mod specs_closures_collector;
 mod encoder;
 mod errors;
 mod foldunfold;
+mod ghost_encoder;
 mod initialisation;
 mod loop_encoder;
 mod mir_encoder;
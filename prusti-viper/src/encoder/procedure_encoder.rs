@@ -15,6 +15,11 @@ use crate::encoder::initialisation::InitInfo;
 use crate::encoder::loop_encoder::{LoopEncoder, LoopEncoderError};
 use crate::encoder::mir_encoder::{MirEncoder, FakeMirEncoder, PlaceEncoder, PlaceEncoding, ExprOrArrayBase};
 use crate::encoder::mir_encoder::PRECONDITION_LABEL;
+use crate::encoder::mir_encoder::detect_raw_pointer_deref;
+use crate::encoder::mir_encoder::detect_read_locals;
+use crate::encoder::spec_encoder::{
+    collect_spec_entailments, encode_spec_entailment_call, encode_spec_entailment_check,
+};
 use crate::encoder::mir_successor::MirSuccessor;
 use crate::encoder::places::{Local, LocalVariableManager, Place};
 use crate::encoder::Encoder;
@@ -110,6 +115,12 @@ pub struct ProcedureEncoder<'p, 'v: 'p, 'tcx: 'v> {
     old_ghost_vars: HashMap<String, vir::Type>,
     /// For each loop head, the block at whose end the loop invariant holds
     cached_loop_invariant_block: HashMap<BasicBlockIndex, BasicBlockIndex>,
+    /// For the address stored in a raw pointer, the place from which it was copied. The
+    /// permission to the pointee is lent to copies and returned to the first place of the
+    /// chain after a call.
+    raw_ptr_origins: HashMap<vir::Expr, vir::Expr>,
+    /// The Viper variables that track the values of user variables for counterexamples.
+    counterexample_vars: HashMap<mir::Local, vir::LocalVar>,
 }
 
 impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
@@ -165,6 +176,8 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             old_to_ghost_var: HashMap::new(),
             old_ghost_vars: HashMap::new(),
             cached_loop_invariant_block: HashMap::new(),
+            raw_ptr_origins: HashMap::new(),
+            counterexample_vars: HashMap::new(),
         })
     }
 
@@ -182,32 +195,21 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 SpannedEncodingError::unsupported(msg, self.mir_encoder.get_span_of_basic_block(loop_head))
             }
 
-            PoloniusInfoError::LoansInNestedLoops(location1, _loop1, _location2, _loop2) => {
-                SpannedEncodingError::unsupported(
-                    "creation of loans in nested loops is not supported".to_string(),
-                    self.mir.source_info(location1).span,
-                )
-            }
-
-            PoloniusInfoError::ReborrowingDagHasNoMagicWands(location) => {
+            PoloniusInfoError::ReborrowingDagHasNoMagicWands(loop_head) => {
                 SpannedEncodingError::unsupported(
-                    "the creation of loans in this loop is not supported \
+                    "references that are reborrowed across the iterations of a loop and are \
+                    still alive after the loop are not supported \
                     (ReborrowingDagHasNoMagicWands)",
-                    self.mir.source_info(location).span,
+                    self.mir_encoder.get_span_of_basic_block(loop_head),
                 )
             }
 
-            PoloniusInfoError::MultipleMagicWandsPerLoop(location) => SpannedEncodingError::unsupported(
-                "the creation of loans in this loop is not supported \
-                    (MultipleMagicWandsPerLoop)",
-                self.mir.source_info(location).span,
-            ),
-
-            PoloniusInfoError::MagicWandHasNoRepresentativeLoan(location) => {
+            PoloniusInfoError::MultipleMagicWandsPerLoop(loop_head) => {
                 SpannedEncodingError::unsupported(
-                    "the creation of loans in this loop is not supported \
-                    (MagicWandHasNoRepresentativeLoan)",
-                    self.mir.source_info(location).span,
+                    "more than one reference that is reborrowed across the iterations of a loop \
+                    and is still alive after the loop is not supported \
+                    (MultipleMagicWandsPerLoop)",
+                    self.mir_encoder.get_span_of_basic_block(loop_head),
                 )
             }
 
@@ -247,6 +249,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 .with_span(mir_span)?
         );
 
+        if config::counterexample() {
+            self.encode_counterexample_vars();
+        }
+
         // Prepare assertions to check specification refinement
         let mut precondition_weakening: Option<typed::Assertion> = None;
         let mut postcondition_strengthening: Option<typed::Assertion> = None;
@@ -362,6 +368,18 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             }
         }
 
+        // Check that the execution leaves the ghost blocks only through their end, before
+        // the code after an escape is mistaken for ghost code
+        for bbi in self.procedure.get_reachable_nonspec_cfg_blocks() {
+            if self.procedure.is_ghost_escape(bbi) {
+                return Err(SpannedEncodingError::incorrect(
+                    "ghost code must terminate and cannot leave its ghost block, e.g. with \
+                    `return`, `break`, `continue` or `?`",
+                    self.mir[bbi].terminator().source_info.span,
+                ));
+            }
+        }
+
         // Load Polonius info
         self.polonius_info = Some(
             PoloniusInfo::new(&self.procedure, &self.cached_loop_invariant_block)
@@ -480,6 +498,8 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             .encoder
             .error_manager()
             .register(self.mir.span, ErrorCtxt::Unexpected);
+        let mir = self.mir;
+        let locals = &self.locals;
         let method_with_fold_unfold = foldunfold::add_fold_unfold(
             self.encoder,
             self.cfg_method,
@@ -492,7 +512,28 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 foldunfold::FoldUnfoldError::Unsupported(msg) => {
                     SpannedEncodingError::unsupported(msg, mir_span)
                 }
-                
+
+                foldunfold::FoldUnfoldError::FailedToObtain(ref perm) => {
+                    let raw_pointer = perm.get_place().all_proper_prefixes().into_iter().find(
+                        |place| place.get_type().name().starts_with("raw_ref$")
+                    );
+                    if let Some(raw_pointer) = raw_pointer {
+                        SpannedEncodingError::incorrect(
+                            "the memory pointed to by a raw pointer might not be owned; \
+                            its ownership can be stated with `own(..)` in the contract",
+                            raw_pointer_deref_span(mir, locals, &raw_pointer).unwrap_or(mir_span),
+                        )
+                    } else {
+                        SpannedEncodingError::internal(
+                            format!(
+                                "generating fold-unfold Viper statements failed ({:?})",
+                                foldunfold_error,
+                            ),
+                            mir_span,
+                        )
+                    }
+                }
+
                 _ => SpannedEncodingError::internal(
                     format!(
                         "generating fold-unfold Viper statements failed ({:?})",
@@ -503,6 +544,11 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             }
         })?;
 
+        // Assume the type invariants at the unfolds
+        let method_with_fold_unfold = self.encode_unfold_type_invariants(
+            method_with_fold_unfold
+        )?;
+
         // Fix variable declarations.
         let final_method = fix_ghost_vars(method_with_fold_unfold);
 
@@ -518,6 +564,71 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         Ok(final_method)
     }
 
+    /// Assumes the type invariant of a value before each unfold of its predicate, so that the
+    /// body of a method can rely on it when accessing the fields of the value. The invariants
+    /// are checked at the boundaries of the public functions.
+    fn encode_unfold_type_invariants(
+        &self,
+        mut method: vir::CfgMethod,
+    ) -> SpannedEncodingResult<vir::CfgMethod> {
+        for block in method.basic_blocks.iter_mut() {
+            let stmts = std::mem::take(&mut block.stmts);
+            block.stmts = self.patch_unfold_type_invariants(stmts)?;
+        }
+        Ok(method)
+    }
+
+    fn patch_unfold_type_invariants(
+        &self,
+        stmts: Vec<vir::Stmt>,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let mut patched_stmts = Vec::with_capacity(stmts.len());
+        for stmt in stmts {
+            match stmt {
+                vir::Stmt::Unfold(ref pred_name, ref args, _, _) => {
+                    if let Some(invariant) = self.encode_user_type_invariant(pred_name, &args[0])? {
+                        patched_stmts.push(vir::Stmt::Inhale(invariant));
+                    }
+                    patched_stmts.push(stmt);
+                }
+                vir::Stmt::If(guard, then_stmts, else_stmts) => {
+                    patched_stmts.push(vir::Stmt::If(
+                        guard,
+                        self.patch_unfold_type_invariants(then_stmts)?,
+                        self.patch_unfold_type_invariants(else_stmts)?,
+                    ));
+                }
+                _ => patched_stmts.push(stmt),
+            }
+        }
+        Ok(patched_stmts)
+    }
+
+    /// Encodes the type invariant of the value `arg` of the predicate `pred_name`, if the
+    /// type has invariants written by the user.
+    fn encode_user_type_invariant(
+        &self,
+        pred_name: &str,
+        arg: &vir::Expr,
+    ) -> SpannedEncodingResult<Option<vir::Expr>> {
+        let ty = if let Ok(ty) = self.encoder.decode_type_predicate(pred_name) {
+            ty
+        } else {
+            return Ok(None);
+        };
+        match ty.kind() {
+            ty::TyKind::Adt(adt_def, _)
+                if self.encoder.get_struct_specs(adt_def.did).map_or(false, |specs| !specs.is_empty()) =>
+            {
+                Ok(Some(
+                    self.encoder.encode_invariant_func_app(ty, arg.clone())
+                        .with_span(self.mir.span)?
+                ))
+            }
+            _ => Ok(None),
+        }
+    }
+
     /// Encodes a topologically ordered group of blocks.
     ///
     /// Returns:
@@ -574,6 +685,57 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         Ok((group_head, still_unresolved_edges))
     }
 
+    /// Declares a Viper variable for each user variable of a primitive type. Silicon reports
+    /// the values of the Viper variables in a counterexample, but the variables of the encoding
+    /// are references to the heap, whose values mean nothing to the user. Instead, the declared
+    /// variable is updated with the value of the Rust variable after each assignment to it and
+    /// before each read of it.
+    fn encode_counterexample_vars(&mut self) {
+        let mut user_locals: Vec<_> = self.mir.var_debug_info
+            .iter()
+            .filter_map(|info| match info.value {
+                mir::VarDebugInfoContents::Place(place) => place.as_local()
+                    .map(|local| (local, info.name.to_ident_string())),
+                _ => None,
+            })
+            .collect();
+        user_locals.push((mir::RETURN_PLACE, "result".to_string()));
+
+        let mut names = HashMap::new();
+        for (local, rust_name) in user_locals {
+            let var_type = match self.mir_encoder.get_local_ty(local).kind() {
+                ty::TyKind::Bool => vir::Type::Bool,
+                ty::TyKind::Int(_) | ty::TyKind::Uint(_) | ty::TyKind::Char => vir::Type::Int,
+                _ => continue,
+            };
+            let var_name = format!("cex${}", self.mir_encoder.encode_local_var_name(local));
+            self.cfg_method.add_local_var(&var_name, var_type.clone());
+            names.insert(var_name.clone(), rust_name);
+            self.counterexample_vars.insert(local, vir::LocalVar::new(var_name, var_type));
+        }
+        self.encoder.error_manager().register_procedure_variables(self.mir.span, names);
+    }
+
+    /// Updates the counterexample variable of `local`, if there is one, with the current
+    /// value of `local`.
+    fn encode_counterexample_var_update(
+        &self,
+        local: mir::Local,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        if let Some(var) = self.counterexample_vars.get(&local) {
+            let value_field = self.encoder
+                .encode_value_field(self.mir_encoder.get_local_ty(local))
+                .with_span(self.mir.span)?;
+            Ok(vec![vir::Stmt::Assign(
+                vir::Expr::local(var.clone()),
+                vir::Expr::local(self.encode_prusti_local(local.into())).field(value_field),
+                vir::AssignKind::Copy,
+            )])
+        } else {
+            Ok(vec![])
+        }
+    }
+
     fn encode_unresolved_edges<F: Fn(BasicBlockIndex) -> Option<CfgBlockIndex>>(
         &mut self,
         mut unresolved_edges: Vec<(CfgBlockIndex, BasicBlockIndex)>,
@@ -763,6 +925,22 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 self.encode_loop_invariant_inhale_stmts(loop_head, before_invariant_block, false)?;
             self.cfg_method.add_stmts(inv_post_block, stmts);
         }
+        // Record the value of the loop variant at the beginning of an arbitrary iteration
+        let loop_variant = self.encode_loop_variant_spec(loop_head, before_invariant_block)?;
+        let variant_var = if let Some((ref encoded_variant, _)) = loop_variant {
+            let variant_var = self.cfg_method.add_fresh_local_var(vir::Type::Int);
+            self.cfg_method.add_stmt(
+                inv_post_block,
+                vir::Stmt::Assign(
+                    variant_var.clone().into(),
+                    encoded_variant.clone(),
+                    vir::AssignKind::Copy,
+                ),
+            );
+            Some(variant_var)
+        } else {
+            None
+        };
 
         // Encode the last B2 group (start - G - B1 - invariant - *B2* - G - B1 - end)
         let (last_b2_head, last_b2_edges) = self.encode_blocks_group(
@@ -792,8 +970,9 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         heads.push(last_b1_head);
 
         // Build the "end" CFG block (start - G - B1 - invariant - B2 - G - B1 - *end*)
-        // (1) checks the invariant after one loop iteration
-        // (2) kills the program path with an `assume false`
+        // (1) checks that the loop variant decreased, if there is one
+        // (2) checks the invariant after one loop iteration
+        // (3) kills the program path with an `assume false`
         let end_body_block = self.cfg_method.add_block(
             &format!("{}_end_body", loop_label_prefix),
             vec![vir::Stmt::comment(format!(
@@ -801,6 +980,22 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 loop_label_prefix
             ))],
         );
+        if let (Some((encoded_variant, variant_span)), Some(variant_var)) = (loop_variant, variant_var) {
+            let variant_pos = self.encoder.error_manager().register(
+                variant_span,
+                ErrorCtxt::AssertLoopVariantDecrease,
+            );
+            self.cfg_method.add_stmt(
+                end_body_block,
+                vir::Stmt::Assert(
+                    vir::Expr::and(
+                        vir::Expr::le_cmp(0.into(), encoded_variant.clone()),
+                        vir::Expr::lt_cmp(encoded_variant, variant_var.into()),
+                    ),
+                    variant_pos,
+                ),
+            );
+        }
         {
             let stmts = self.encode_loop_invariant_exhale_stmts(
                 loop_head,
@@ -892,6 +1087,8 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 vir::Type::Domain(_) => BuiltinMethodKind::HavocRef,
                 vir::Type::Snapshot(_) => BuiltinMethodKind::HavocRef,
                 vir::Type::Seq(_) => BuiltinMethodKind::HavocRef,
+                vir::Type::Set(_) => BuiltinMethodKind::HavocRef,
+                vir::Type::Map(..) => BuiltinMethodKind::HavocRef,
             };
             let stmt = vir::Stmt::MethodCall(
                 self.encoder.encode_builtin_method_use(builtin_method),
@@ -1073,6 +1270,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             downcast_stmts.push(vir::Stmt::Downcast(encoded_place, variant_field));
         }
 
+        if self.procedure.is_ghost_block(location.block) {
+            self.check_ghost_code(location)?;
+        }
+
         let bb_data = &self.mir[location.block];
         let index = location.statement_index;
         let stmts_succ_res = if index < bb_data.statements.len() {
@@ -1085,6 +1286,40 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 .map(|(stmts, succ)| (stmts, Some(succ)))
         };
 
+        // Track the values of the user variables for counterexamples. A variable can also be
+        // modified through a mutable reference, which is only visible once the reference
+        // expired, so the variables are also updated right before they are read.
+        let mut read_locals_update = vec![];
+        if !self.counterexample_vars.is_empty() {
+            for local in detect_read_locals(self.mir, location) {
+                read_locals_update.extend(self.encode_counterexample_var_update(local)?);
+            }
+        }
+        let assigned_local = if index < bb_data.statements.len() {
+            match bb_data.statements[index].kind {
+                mir::StatementKind::Assign(box (ref place, _)) => place.as_local(),
+                _ => None,
+            }
+        } else {
+            match bb_data.terminator().kind {
+                mir::TerminatorKind::Call { destination: Some((ref place, _)), .. } => {
+                    place.as_local()
+                }
+                _ => None,
+            }
+        };
+        let stmts_succ_res = match stmts_succ_res {
+            Ok((stmts, succ)) => {
+                let mut updated_stmts = read_locals_update;
+                updated_stmts.extend(stmts);
+                if let Some(local) = assigned_local {
+                    updated_stmts.extend(self.encode_counterexample_var_update(local)?);
+                }
+                Ok((updated_stmts, succ))
+            }
+            res => res,
+        };
+
         // Intercept encoding error caused by an unsupported feature
         match stmts_succ_res {
             Ok(stmts_succ) => Ok(stmts_succ),
@@ -1124,6 +1359,62 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         }
     }
 
+    /// Check that the ghost code at `location` does not modify non-ghost state and
+    /// that it only calls functions without side effects.
+    fn check_ghost_code(&self, location: mir::Location) -> SpannedEncodingResult<()> {
+        let span = self.mir_encoder.get_span_of_location(location);
+        let is_nonghost = |place: &mir::Place<'tcx>| self.procedure.is_nonghost_local(place.local);
+        let bb_data = &self.mir[location.block];
+        let modifies_nonghost_state = if location.statement_index < bb_data.statements.len() {
+            match bb_data.statements[location.statement_index].kind {
+                mir::StatementKind::Assign(box (ref lhs, ref rhs)) => {
+                    is_nonghost(lhs) || match rhs {
+                        mir::Rvalue::Ref(_, mir::BorrowKind::Mut { .. }, ref place)
+                        | mir::Rvalue::AddressOf(mir::Mutability::Mut, ref place)
+                        | mir::Rvalue::Use(mir::Operand::Move(ref place)) => is_nonghost(place),
+                        _ => false,
+                    }
+                }
+                _ => false,
+            }
+        } else {
+            match bb_data.terminator().kind {
+                TerminatorKind::Call { ref args, ref destination, ref func, .. } => {
+                    let is_allowed_call = match self.mir_encoder.get_operand_ty(func).kind() {
+                        ty::TyKind::FnDef(def_id, substs) => {
+                            let tcx = self.encoder.env().tcx();
+                            tcx.def_path_str(*def_id) == "prusti_contracts::ghost_end"
+                                || self.encoder.ghost_collection_method_kind(*def_id).is_some()
+                                || self.encoder.is_pure(
+                                    self.encoder.resolve_pure_trait_method(*def_id, substs)
+                                )
+                        }
+                        _ => false,
+                    };
+                    if !is_allowed_call {
+                        return Err(SpannedEncodingError::incorrect(
+                            "ghost code can only call pure functions",
+                            span,
+                        ));
+                    }
+                    destination.map_or(false, |(ref place, _)| is_nonghost(place))
+                        || args.iter().any(|arg| match arg {
+                            mir::Operand::Move(ref place) => is_nonghost(place),
+                            _ => false,
+                        })
+                }
+                _ => false,
+            }
+        };
+        if modifies_nonghost_state {
+            return Err(SpannedEncodingError::incorrect(
+                "ghost code cannot modify non-ghost state",
+                span,
+            ));
+        }
+        Ok(())
+    }
+
     /// Note: it's better to call `encode_statement_at` instead of this method.
     fn encode_statement(
         &mut self,
@@ -1160,6 +1451,15 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                         rhs,
                         location,
                     )?
+                } else if let PlaceEncoding::SliceAccess { box base, index, rust_slice_ty, .. } = lhs_place_encoding {
+                    // Same as above, `slice[idx] = val`
+                    self.encode_slice_direct_assign(
+                        base,
+                        index,
+                        rust_slice_ty,
+                        rhs,
+                        location,
+                    )?
                 } else {
                     let (encoded_lhs, pre_stmts) = self.postprocess_place_encoding(lhs_place_encoding, ArrayAccessKind::Mutable(None, location))
                         .with_span(span)?;
@@ -1316,21 +1616,48 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                         ty,
                         location,
                     )?
+                } else if ty.builtin_deref(true).map_or(false, |tm| tm.ty.is_trait()) {
+                    trace!("trait object: operand={:?}, ty={:?}", operand, ty);
+                    self.encode_assign_trait_object(
+                        encoded_lhs,
+                        operand,
+                        ty,
+                        location,
+                    )?
                 } else {
                     return Err(EncodingError::unsupported(
                         "unsizing a pointer or reference value is not supported"
                     )).with_span(span);
                 }
             }
+            &mir::Rvalue::Cast(
+                mir::CastKind::Pointer(ty::adjustment::PointerCast::MutToConstPointer),
+                ref operand,
+                _,
+            ) => {
+                self.encode_assign_operand(&encoded_lhs, operand, location)?
+            }
+            &mir::Rvalue::Cast(
+                mir::CastKind::Pointer(ty::adjustment::PointerCast::ReifyFnPointer),
+                _,
+                _,
+            ) => {
+                // Function pointers are abstract. The callee that receives one specifies it with
+                // a spec entailment, which the call site checks against the reified function.
+                self.encode_havoc_and_allocation(&encoded_lhs)
+            }
             &mir::Rvalue::Cast(mir::CastKind::Pointer(_), _, _) => {
                 return Err(EncodingError::unsupported(
-                    "raw pointers are not supported"
+                    "this kind of pointer cast is not supported"
                 )).with_span(span);
             }
-            &mir::Rvalue::AddressOf(_, _) => {
-                return Err(EncodingError::unsupported(
-                    "raw addresses of expressions or casting a reference to a raw pointer are not supported"
-                )).with_span(span);
+            &mir::Rvalue::AddressOf(_, ref place) => {
+                self.encode_assign_address_of(
+                    encoded_lhs,
+                    place,
+                    ty,
+                    location,
+                )?
             }
             &mir::Rvalue::ThreadLocalRef(_) => {
                 return Err(EncodingError::unsupported(
@@ -1354,14 +1681,24 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         // postprocessing if necessary ourselves.
         if let mir::Rvalue::Ref(_, mir::BorrowKind::Mut { .. }, ref rhs_place) = loan_places.source {
             let (rhs_place_encoding, ..) = self.mir_encoder.encode_place(rhs_place).unwrap();
-            if let PlaceEncoding::ArrayAccess { .. } = rhs_place_encoding {
+            if matches!(
+                rhs_place_encoding,
+                PlaceEncoding::ArrayAccess { .. }
+                | PlaceEncoding::SliceAccess { .. }
+                | PlaceEncoding::SubsliceAccess { .. }
+            ) {
                 // encode expiry of the array borrow
                 let (expired_expr, regained_array, wand_rhs) = self.array_magic_wand_at[&loan_places.location].clone();
 
                 // expiring base is something like ref$i32, so we need .val_ref.val_int
                 let deref = self.encoder.encode_value_expr(expiring_base.clone(), expiring_ty)?;
                 let target_ty = expiring_ty.peel_refs();
-                let expiring_base_value = self.encoder.encode_value_expr(deref, target_ty)?;
+                let expiring_base_value = if let ty::TyKind::Slice(..) = target_ty.kind() {
+                    // a subslice is itself a reference to the slice predicate
+                    deref
+                } else {
+                    self.encoder.encode_value_expr(deref, target_ty)?
+                };
 
                 // the original magic wand refered to the temporary variable that we created for array
                 // encoding. the expiry here refers to the non-temporary rust variable, so we need
@@ -1946,9 +2283,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
 
                 // Is the target a specification block?
                 if self.procedure.is_spec_block(default_target) {
+                    if self.procedure.is_ghost_statement_block(default_target) {
+                        stmts.extend(self.encode_ghost_statements(default_target)?);
+                    }
                     stmts.push(vir::Stmt::comment(format!(
                         "Ignore default target {:?}, as it is only used by Prusti to type-check \
-                        a loop invariant.",
+                        a specification.",
                         default_target
                     )));
                     kill_default_target = true;
@@ -2080,6 +2420,33 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                     let _cleanup_token = self.encoder.push_temp_tymap(tymap);
 
                     match full_func_proc_name {
+                        "prusti_contracts::ghost_begin"
+                        | "prusti_contracts::ghost_end" => {
+                            // Markers of the boundaries of a `ghost!` block, which have
+                            // already been used to identify the ghost blocks.
+                            stmts.push(vir::Stmt::comment("ghost code marker"));
+                        }
+
+                        _ if self.encoder.ghost_collection_method_kind(def_id).is_some() => {
+                            if !self.procedure.is_ghost_block(location.block) {
+                                return Err(SpannedEncodingError::incorrect(
+                                    "ghost collections can only be used in specifications \
+                                    and ghost code",
+                                    term.source_info.span,
+                                ));
+                            }
+                            stmts.extend(
+                                self.encode_ghost_method_call(
+                                    location,
+                                    term.source_info.span,
+                                    args,
+                                    destination,
+                                    def_id,
+                                    substs,
+                                )?
+                            );
+                        }
+
                         "std::rt::begin_panic"
                         | "core::panicking::panic"
                         | "core::panicking::panic_fmt" => {
@@ -2207,6 +2574,18 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                                     )?);
                                 }
 
+                                ty::TyKind::Param(_) => {
+                                    debug!("Encoding call to closure argument of type {:?}", cl_type);
+                                    stmts.extend(self.encode_spec_entailment_call(
+                                        location,
+                                        term.source_info.span,
+                                        cl_type,
+                                        args,
+                                        true,
+                                        destination,
+                                    )?);
+                                }
+
                                 _ => {
                                     return Err(SpannedEncodingError::unsupported(
                                         format!("only calls to closures are supported. The term is a {:?}, not a closure.", cl_type.kind()),
@@ -2216,6 +2595,43 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                             }
                         }
 
+                        "std::ops::FnMut::call_mut"
+                        | "std::ops::FnOnce::call_once"
+                            if matches!(substs[0].expect_ty().kind(), ty::TyKind::Param(_)) =>
+                        {
+                            let cl_type: ty::Ty = substs[0].expect_ty();
+                            debug!("Encoding call to closure argument of type {:?}", cl_type);
+                            stmts.extend(self.encode_spec_entailment_call(
+                                location,
+                                term.source_info.span,
+                                cl_type,
+                                args,
+                                true,
+                                destination,
+                            )?);
+                        }
+
+                        "core::slice::<impl [T]>::split_at" => {
+                            debug!("Encoding call of slice::split_at");
+                            stmts.extend(
+                                self.encode_slice_split_at_call(
+                                    location,
+                                    term.source_info.span,
+                                    args,
+                                    destination,
+                                    def_id,
+                                    self_ty,
+                                )?
+                            );
+                        }
+
+                        "core::slice::<impl [T]>::split_at_mut" => {
+                            return Err(SpannedEncodingError::unsupported(
+                                "splitting mutable slices is not supported; use a subslice pattern instead",
+                                term.source_info.span,
+                            ));
+                        }
+
                         "core::slice::<impl [T]>::len" => {
                             debug!("Encoding call of slice::len");
                             stmts.extend(
@@ -2224,19 +2640,85 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                                     args,
                                     location,
                                     span,
+                                    false,
+                                )?
+                            );
+                        }
+
+                        "core::slice::<impl [T]>::is_empty" => {
+                            debug!("Encoding call of slice::is_empty");
+                            stmts.extend(
+                                self.encode_slice_len_call(
+                                    destination,
+                                    args,
+                                    location,
+                                    span,
+                                    true,
+                                )?
+                            );
+                        }
+
+                        "std::ptr::read"
+                        | "core::ptr::read"
+                        | "std::ptr::write"
+                        | "core::ptr::write"
+                        | "std::ptr::null"
+                        | "core::ptr::null"
+                        | "std::ptr::null_mut"
+                        | "core::ptr::null_mut"
+                        | "core::ptr::const_ptr::<impl *const T>::is_null"
+                        | "core::ptr::mut_ptr::<impl *mut T>::is_null"
+                        | "core::ptr::const_ptr::<impl *const T>::add"
+                        | "core::ptr::mut_ptr::<impl *mut T>::add"
+                        | "core::ptr::const_ptr::<impl *const T>::offset"
+                        | "core::ptr::mut_ptr::<impl *mut T>::offset"
+                        | "core::ptr::const_ptr::<impl *const T>::sub"
+                        | "core::ptr::mut_ptr::<impl *mut T>::sub" => {
+                            debug!("Encoding call of {}", full_func_proc_name);
+                            stmts.extend(
+                                self.encode_raw_ptr_function_call(
+                                    full_func_proc_name,
+                                    destination,
+                                    args,
+                                    location,
+                                    span,
                                 )?
                             );
                         }
 
                         "std::iter::Iterator::next" |
-                        "core::iter::Iterator::next" => {
+                        "core::iter::Iterator::next"
+                            if self.yields_mutable_references(destination) =>
+                        {
                             return Err(SpannedEncodingError::unsupported(
-                                "iterators are not fully supported yet".to_string(),
+                                "iterators that yield mutable references are not supported yet",
                                 term.source_info.span,
                             ));
                         }
 
+                        "std::iter::IntoIterator::into_iter" |
+                        "core::iter::IntoIterator::into_iter"
+                            if args.len() == 1 && destination.map_or(false, |(place, _)|
+                                place.ty(self.mir, self.encoder.env().tcx()).ty ==
+                                    self.mir_encoder.get_operand_ty(&args[0])
+                            )
+                        => {
+                            // `for` loops call `into_iter` on the iterator they consume. For
+                            // iterators, it returns the iterator itself.
+                            debug!("Encoding call of IntoIterator::into_iter on an iterator");
+                            let (ref target_place, _) = destination.as_ref().unwrap();
+                            let (encoded_lhs, pre_stmts, _, _) = self.encode_place(
+                                target_place,
+                                ArrayAccessKind::Mutable(None, location),
+                            ).with_span(span)?;
+                            stmts.extend(pre_stmts);
+                            stmts.extend(
+                                self.encode_assign_operand(&encoded_lhs, &args[0], location)?
+                            );
+                        }
+
                         _ => {
+                            let def_id = self.encoder.resolve_pure_trait_method(def_id, substs);
                             let is_pure_function = self.encoder.is_pure(def_id);
                             if is_pure_function {
                                 let (function_name, _) = self.encoder
@@ -2290,6 +2772,29 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 }
             }
 
+            TerminatorKind::Call {
+                ref args,
+                ref destination,
+                ref func,
+                ..
+            } if self.mir_encoder.get_operand_ty(func).is_fn_ptr() => {
+                let fn_ptr_ty = self.mir_encoder.get_operand_ty(func);
+                debug!("Encoding call through function pointer of type {:?}", fn_ptr_ty);
+                stmts.extend(self.encode_spec_entailment_call(
+                    location,
+                    term.source_info.span,
+                    fn_ptr_ty,
+                    args,
+                    false,
+                    destination,
+                )?);
+                if let &Some((_, target)) = destination {
+                    (stmts, MirSuccessor::Goto(target))
+                } else {
+                    (stmts, MirSuccessor::Kill)
+                }
+            }
+
             TerminatorKind::Call { .. } => {
                 // Other kind of calls?
                 unimplemented!();
@@ -2356,12 +2861,14 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         Ok(result)
     }
 
+    /// Encode a call of `len` (or `is_empty`, if `is_empty` is set) on a slice.
     fn encode_slice_len_call(
         &mut self,
         destination: &Option<(mir::Place<'tcx>, BasicBlockIndex)>,
         args: &[mir::Operand<'tcx>],
         location: mir::Location,
         span: Span,
+        is_empty: bool,
     ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
         assert!(args.len() == 1, "unexpected args to slice::len(): {:?}", args);
         let slice_operand = self.mir_encoder.encode_operand_expr(&args[0])
@@ -2377,7 +2884,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         let slice_ty = if let ty::TyKind::Ref(_, slice_ty, _) = slice_ty_ref.kind() { slice_ty } else { unreachable!() };
         let slice_types = self.encoder.encode_slice_types(slice_ty).with_span(span)?;
 
-        let rhs = slice_types.encode_slice_len_call(self.encoder, slice_operand);
+        let slice_len = slice_types.encode_slice_len_call(self.encoder, slice_operand);
+        let rhs = if is_empty {
+            vir!{ [slice_len] == [vir::Expr::from(0)] }
+        } else {
+            slice_len
+        };
 
         let (encoded_lhs, encode_stmts, ty, _) = self.encode_place(
             &destination.as_ref().unwrap().0,
@@ -2407,37 +2919,453 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         Ok(stmts)
     }
 
-    fn encode_cmp_function_call(
+    /// Encode a call of `slice::split_at`. The call is encoded like the call of a function
+    /// without specification, and the two halves are then related to the split slice like the
+    /// subslices of `encode_subslice`: their lengths add up to the length of the slice, and
+    /// their elements are the ones of the slice before and from `mid`.
+    fn encode_slice_split_at_call(
         &mut self,
-        called_def_id: ProcedureDefId,
         location: mir::Location,
-        call_site_span: Span,
+        span: Span,
         args: &[mir::Operand<'tcx>],
         destination: &Option<(mir::Place<'tcx>, BasicBlockIndex)>,
-        bin_op: vir::BinOpKind,
+        def_id: ProcedureDefId,
+        self_ty: Option<&'tcx ty::TyS<'tcx>>,
     ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
-        let arg_ty = self.mir_encoder.get_operand_ty(&args[0]);
+        assert!(args.len() == 2, "unexpected args to slice::split_at(): {:?}", args);
+        let slice_ty_ref = self.mir_encoder.get_operand_ty(&args[0]);
+        let slice_ty = if let ty::TyKind::Ref(_, slice_ty, _) = slice_ty_ref.kind() { slice_ty } else { unreachable!() };
+        let slice_types = self.encoder.encode_slice_types(slice_ty).with_span(span)?;
+        let slice = self.mir_encoder.encode_operand_expr(&args[0]).with_span(span)?;
+        let mid = self.mir_encoder.encode_operand_expr(&args[1]).with_span(span)?;
 
-        if self.encoder.supports_snapshot_equality(&arg_ty).with_span(call_site_span)? {
-            let lhs = self.mir_encoder.encode_operand_expr(&args[0])
-                .with_span(call_site_span)?;
-            let rhs = self.mir_encoder.encode_operand_expr(&args[1])
-                .with_span(call_site_span)?;
+        let mut stmts = vec![];
+        let pre_label = self.cfg_method.get_fresh_label_name();
+        stmts.push(vir::Stmt::Label(pre_label.clone()));
+        let old = |e| { vir::Expr::labelled_old(&pre_label, e) };
 
-            let expr = match bin_op {
-                vir::BinOpKind::EqCmp => vir::Expr::eq_cmp(
-                    vir::Expr::snap_app(lhs),
-                    vir::Expr::snap_app(rhs),
-                ),
-                vir::BinOpKind::NeCmp => vir::Expr::ne_cmp(
-                    vir::Expr::snap_app(lhs),
-                    vir::Expr::snap_app(rhs),
-                ),
-                _ => unreachable!()
-            };
+        // `split_at` panics if `mid` is greater than the length of the slice
+        let slice_len = slice_types.encode_slice_len_call(self.encoder, slice.clone());
+        let pos = self
+            .encoder
+            .error_manager()
+            .register(span, ErrorCtxt::Panic(PanicCause::Generic));
+        stmts.push(vir::Stmt::Assert(vir!{ [ mid.clone() ] <= [ slice_len.clone() ] }, pos));
 
-            let (target_value, mut stmts) = self.encode_pure_function_call_lhs_value(destination)
-                .with_span(call_site_span)?;
+        stmts.extend(self.encode_impure_function_call(
+            location,
+            span,
+            args,
+            destination,
+            def_id,
+            self_ty,
+        )?);
+
+        let (ref target_place, _) = destination.as_ref().unwrap();
+        let (encoded_lhs, encode_stmts, _, _) = self.encode_place(
+            target_place,
+            ArrayAccessKind::Shared,
+        ).with_span(span)?;
+        stmts.extend(encode_stmts);
+        let mut halves = vec![];
+        for field_num in 0..2 {
+            let half_field = self.encoder
+                .encode_raw_ref_field(format!("tuple_{}", field_num), slice_ty_ref)
+                .with_span(span)?;
+            halves.push(
+                self.encoder
+                    .encode_value_expr(encoded_lhs.clone().field(half_field), slice_ty_ref)
+                    .with_span(span)?
+            );
+        }
+
+        let lookup_ret_ty = self.encoder.encode_snapshot_type(slice_types.elem_ty_rs).with_span(span)?;
+        let i_var: vir::Expr = vir_local!{ i: Int }.into();
+        let old_mid = old(mid);
+        let old_slice_len = old(slice_len);
+        let halves_bounds = [
+            (vir::Expr::from(0), old_mid.clone()),
+            (old_mid.clone(), vir!{ [ old_slice_len.clone() ] - [ old_mid.clone() ] }),
+        ];
+        for (half, (start, half_len)) in halves.into_iter().zip(halves_bounds.iter().cloned()) {
+            let len = slice_types.encode_slice_len_call(self.encoder, half.clone());
+            stmts.push(vir!{ inhale [ vir!{ [ len ] == [ half_len.clone() ] } ] });
+
+            // forall i: Int :: { lookup_pure(half, i) }
+            //   0 <= i && i < half_len ==> lookup_pure(half, i) == old(lookup_pure(slice, i + start))
+            let lookup_half_i = slice_types.encode_lookup_pure_call(
+                self.encoder,
+                half,
+                i_var.clone(),
+                lookup_ret_ty.clone(),
+            );
+            let lookup_slice_shifted = slice_types.encode_lookup_pure_call(
+                self.encoder,
+                slice.clone(),
+                vir!{ [ i_var ] + [ start ] },
+                lookup_ret_ty.clone(),
+            );
+            let in_half = vir!{
+                [ vir!{ [ vir::Expr::from(0) ] <= [ i_var ] } ]
+                && [ vir!{ [ i_var ] < [ half_len ] } ]
+            };
+            let elems_shifted = vir!{ [ lookup_half_i ] == [ old(lookup_slice_shifted) ] };
+            stmts.push(vir!{ inhale [ vir!{ forall i: Int :: { [lookup_half_i] } ([in_half] ==> [elems_shifted]) } ] });
+        }
+
+        Ok(stmts)
+    }
+
+    /// Encode a call of a function that operates on raw pointers. Reading and writing use the
+    /// permission to the target, which must have been stated with `own(..)`.
+    fn encode_raw_ptr_function_call(
+        &mut self,
+        func_proc_name: &str,
+        destination: &Option<(mir::Place<'tcx>, BasicBlockIndex)>,
+        args: &[mir::Operand<'tcx>],
+        location: mir::Location,
+        span: Span,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let mut stmts = vec![];
+
+        let label = self.cfg_method.get_fresh_label_name();
+        stmts.push(vir::Stmt::Label(label.clone()));
+
+        let null = vir::Expr::Const(vir::Const::Null, vir::Position::default());
+        let func_name = func_proc_name.rsplit("::").next().unwrap();
+        if func_name == "null" || func_name == "null_mut" {
+            assert!(args.is_empty(), "unexpected args to {}: {:?}", func_proc_name, args);
+            let (encoded_lhs, encode_stmts, ty, _) = self.encode_place(
+                &destination.as_ref().unwrap().0,
+                ArrayAccessKind::Mutable(None, location),
+            ).with_span(span)?;
+            stmts.extend(encode_stmts);
+            stmts.extend(self.encode_raw_ptr_value_assign(&encoded_lhs, null, ty, location)?);
+            return Ok(stmts);
+        }
+
+        let addr = self.mir_encoder.encode_operand_expr(&args[0])
+            .with_span(span)?;
+        let target_ty = match self.mir_encoder.get_operand_ty(&args[0]).kind() {
+            ty::TyKind::RawPtr(ty::TypeAndMut { ty, .. }) => ty,
+            _ => unreachable!("unexpected args to {}: {:?}", func_proc_name, args),
+        };
+
+        if func_name == "write" {
+            assert_eq!(args.len(), 2);
+            stmts.extend(self.encode_assign_operand(&addr, &args[1], location)?);
+        } else {
+            let (encoded_lhs, encode_stmts, ty, _) = self.encode_place(
+                &destination.as_ref().unwrap().0,
+                ArrayAccessKind::Mutable(None, location),
+            ).with_span(span)?;
+            stmts.extend(encode_stmts);
+            match func_name {
+                "read" => {
+                    stmts.extend(self.encode_copy2(addr, encoded_lhs, target_ty, location)?);
+                }
+                "is_null" => {
+                    stmts.extend(self.encode_copy_value_assign(
+                        encoded_lhs,
+                        vir!{ [addr] == [null] },
+                        ty,
+                        location,
+                    )?);
+                }
+                "add" | "offset" | "sub" => {
+                    assert_eq!(args.len(), 2);
+                    let mut count = self.mir_encoder.encode_operand_expr(&args[1])
+                        .with_span(span)?;
+                    if func_name == "sub" {
+                        count = vir::Expr::minus(count);
+                    }
+                    let elem_ty_pred = self.encoder.encode_type_predicate_use(target_ty)
+                        .with_span(span)?;
+                    let offset_func = self.encoder.encode_builtin_function_use(
+                        BuiltinFunctionKind::PtrOffset { elem_ty_pred: elem_ty_pred.clone() }
+                    );
+                    let addr_ty = vir::Type::TypedRef(elem_ty_pred);
+                    let offset_addr = vir::Expr::func_app(
+                        offset_func,
+                        vec![addr, count],
+                        vec![
+                            vir::LocalVar::new("addr", addr_ty.clone()),
+                            vir_local!{ count: Int },
+                        ],
+                        addr_ty,
+                        vir::Position::default(),
+                    );
+                    stmts.extend(
+                        self.encode_raw_ptr_value_assign(&encoded_lhs, offset_addr, ty, location)?
+                    );
+                }
+                _ => unreachable!("{}", func_proc_name),
+            }
+        }
+
+        self.encode_transfer_args_permissions(location, args, &mut stmts, label.clone(), false)?;
+
+        // Store a label for permissions got back from the call
+        debug!(
+            "Raw pointer function call location {:?} has label {}",
+            location, label
+        );
+        self.label_after_location.insert(location, label);
+
+        Ok(stmts)
+    }
+
+    /// Encode a call of a closure argument or of a function pointer, whose body is not known.
+    /// The call is specified by the spec entailment `callee |= ..` in the precondition of the
+    /// procedure: the preconditions of the entailment are checked before the call, and its
+    /// postconditions are assumed after the call.
+    ///
+    /// If `tupled_args` is true, the call is a call of `Fn::call` (or of `FnMut::call_mut`,
+    /// `FnOnce::call_once`), whose second argument is the tuple of the actual arguments.
+    fn encode_spec_entailment_call(
+        &mut self,
+        location: mir::Location,
+        span: Span,
+        callee_ty: ty::Ty<'tcx>,
+        args: &[mir::Operand<'tcx>],
+        tupled_args: bool,
+        destination: &Option<(mir::Place<'tcx>, BasicBlockIndex)>,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let contract = self.procedure_contract();
+        let mut entailments = contract
+            .functional_precondition()
+            .iter()
+            .flat_map(|assertion| collect_spec_entailments(self.encoder, assertion))
+            .filter(|(closure_ty, _)| *closure_ty == callee_ty)
+            .map(|(_, entailment)| entailment.clone())
+            .collect::<Vec<_>>();
+        let entailment = match entailments.len() {
+            1 => entailments.pop().unwrap(),
+            0 => {
+                return Err(SpannedEncodingError::unsupported(
+                    format!(
+                        "calls of a {} are only supported if the precondition specifies it \
+                        with a spec entailment `|=`",
+                        if tupled_args { "closure argument" } else { "function pointer" },
+                    ),
+                    span,
+                ));
+            }
+            _ => {
+                return Err(SpannedEncodingError::unsupported(
+                    format!(
+                        "the callee of type {:?} is specified by more than one spec entailment",
+                        callee_ty,
+                    ),
+                    span,
+                ));
+            }
+        };
+        let target_args: Vec<vir::Expr> = contract
+            .args
+            .iter()
+            .map(|local| self.encode_prusti_local(*local).into())
+            .collect();
+
+        // The values of the actual arguments.
+        let mut encoded_args = vec![];
+        if tupled_args {
+            assert_eq!(args.len(), 2);
+            let encoded_tuple = self.mir_encoder.encode_operand_place(&args[1])
+                .with_span(span)?
+                .ok_or_else(|| SpannedEncodingError::unsupported(
+                    "constant arguments of closure calls are not supported",
+                    span,
+                ))?;
+            let arg_tuple_ty = self.mir_encoder.get_operand_ty(&args[1]);
+            if let ty::TyKind::Tuple(substs) = arg_tuple_ty.kind() {
+                for (field_num, ty) in substs.iter().enumerate() {
+                    let arg_ty = ty.expect_ty();
+                    let tuple_field = self.encoder
+                        .encode_raw_ref_field(format!("tuple_{}", field_num), arg_ty)
+                        .with_span(span)?;
+                    encoded_args.push(
+                        self.encoder
+                            .encode_value_expr(encoded_tuple.clone().field(tuple_field), arg_ty)
+                            .with_span(span)?
+                    );
+                }
+            } else {
+                unreachable!("the arguments of a closure call are not a tuple: {:?}", arg_tuple_ty);
+            }
+        } else {
+            for arg in args {
+                encoded_args.push(self.mir_encoder.encode_operand_expr(arg).with_span(span)?);
+            }
+        }
+
+        let mut stmts = vec![];
+        let label = self.cfg_method.get_fresh_label_name();
+        stmts.push(vir::Stmt::Label(label.clone()));
+
+        // Store the arguments in local variables, so that the contract can be evaluated in the
+        // state of the precondition of the procedure, where the entailment holds.
+        let mut call_args = vec![];
+        for encoded_arg in encoded_args {
+            let call_arg = self.cfg_method.add_fresh_local_var(encoded_arg.get_type().clone());
+            stmts.push(vir::Stmt::Assign(
+                call_arg.clone().into(),
+                encoded_arg,
+                vir::AssignKind::Copy,
+            ));
+            call_args.push(call_arg.into());
+        }
+
+        // The result is stored in a local variable too, which needs the type of its value.
+        let result_ty = match destination {
+            Some((ref target_place, _)) => target_place.ty(self.mir, self.encoder.env().tcx()).ty,
+            None => self.encoder.env().tcx().types.never,
+        };
+        let result_var_ty = match result_ty.kind() {
+            ty::TyKind::Never => vir::Type::Int,
+            ty::TyKind::Adt(..) | ty::TyKind::Tuple(..) | ty::TyKind::Array(..)
+                if self.encoder.ghost_collection_kind(result_ty).is_none() =>
+            {
+                return Err(SpannedEncodingError::unsupported(
+                    format!(
+                        "calls of a {} returning a value of type {:?} are not supported",
+                        if tupled_args { "closure argument" } else { "function pointer" },
+                        result_ty,
+                    ),
+                    span,
+                ));
+            }
+            _ => self.encoder.encode_value_field(result_ty).with_span(span)?.typ,
+        };
+        let call_result = self.cfg_method.add_fresh_local_var(result_var_ty);
+        let (pre, post) = encode_spec_entailment_call(
+            self.encoder,
+            &entailment,
+            &target_args,
+            &call_args,
+            &call_result.clone().into(),
+        )?;
+
+        let pos = self
+            .encoder
+            .error_manager()
+            .register(span, ErrorCtxt::ExhaleMethodPrecondition);
+        stmts.push(vir::Stmt::Assert(pre.old(PRECONDITION_LABEL), pos));
+
+        if let Some((ref target_place, _)) = destination {
+            stmts.push(vir::Stmt::Inhale(post.old(PRECONDITION_LABEL)));
+            let (encoded_lhs, encode_stmts, ty, _) = self.encode_place(
+                target_place,
+                ArrayAccessKind::Mutable(None, location),
+            ).with_span(span)?;
+            stmts.extend(encode_stmts);
+            stmts.extend(self.encode_copy_value_assign(
+                encoded_lhs,
+                call_result.into(),
+                ty,
+                location,
+            )?);
+        } else {
+            // The call does not return
+            stmts.push(vir::Stmt::Inhale(false.into()));
+        }
+
+        self.encode_transfer_args_permissions(location, args, &mut stmts, label.clone(), false)?;
+
+        // Store a label for permissions got back from the call
+        debug!(
+            "Closure call location {:?} has label {}",
+            location, label
+        );
+        self.label_after_location.insert(location, label);
+
+        Ok(stmts)
+    }
+
+    /// The function item that the function pointer `arg` is known to point to, i.e. the one
+    /// that is reified into it, possibly through copies between locals. A local is followed
+    /// only if it is assigned exactly once and never borrowed mutably, so that it holds the same
+    /// value in every block where it is used.
+    fn find_reified_fn_item(&self, arg: &mir::Operand<'tcx>) -> Option<ProcedureDefId> {
+        let mut local = arg.place()?.as_local()?;
+        let mut visited = HashSet::new();
+        while visited.insert(local) {
+            let mut assigned_rvalues = vec![];
+            for bb_data in self.mir.basic_blocks() {
+                for stmt in &bb_data.statements {
+                    if let mir::StatementKind::Assign(box (ref place, ref rvalue)) = stmt.kind {
+                        if place.local == local {
+                            assigned_rvalues.push(rvalue);
+                        }
+                        match rvalue {
+                            mir::Rvalue::Ref(_, mir::BorrowKind::Mut { .. }, borrowed)
+                            | mir::Rvalue::AddressOf(mir::Mutability::Mut, borrowed)
+                                if borrowed.local == local => return None,
+                            _ => {}
+                        }
+                    }
+                }
+                if let mir::TerminatorKind::Call {
+                    destination: Some((ref place, _)), ..
+                } = bb_data.terminator().kind {
+                    if place.local == local {
+                        return None;
+                    }
+                }
+            }
+            if assigned_rvalues.len() != 1 {
+                return None;
+            }
+            match assigned_rvalues[0] {
+                mir::Rvalue::Cast(
+                    mir::CastKind::Pointer(ty::adjustment::PointerCast::ReifyFnPointer),
+                    mir::Operand::Constant(constant),
+                    _,
+                ) => return match constant.ty().kind() {
+                    ty::TyKind::FnDef(def_id, _) => Some(*def_id),
+                    _ => None,
+                },
+                mir::Rvalue::Use(mir::Operand::Copy(place))
+                | mir::Rvalue::Use(mir::Operand::Move(place)) => {
+                    local = place.as_local()?;
+                }
+                _ => return None,
+            }
+        }
+        None
+    }
+
+    fn encode_cmp_function_call(
+        &mut self,
+        called_def_id: ProcedureDefId,
+        location: mir::Location,
+        call_site_span: Span,
+        args: &[mir::Operand<'tcx>],
+        destination: &Option<(mir::Place<'tcx>, BasicBlockIndex)>,
+        bin_op: vir::BinOpKind,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let arg_ty = self.mir_encoder.get_operand_ty(&args[0]);
+
+        if self.encoder.supports_snapshot_equality(&arg_ty).with_span(call_site_span)? {
+            let lhs = self.mir_encoder.encode_operand_expr(&args[0])
+                .with_span(call_site_span)?;
+            let rhs = self.mir_encoder.encode_operand_expr(&args[1])
+                .with_span(call_site_span)?;
+
+            let expr = match bin_op {
+                vir::BinOpKind::EqCmp => vir::Expr::eq_cmp(
+                    vir::Expr::snap_app(lhs),
+                    vir::Expr::snap_app(rhs),
+                ),
+                vir::BinOpKind::NeCmp => vir::Expr::ne_cmp(
+                    vir::Expr::snap_app(lhs),
+                    vir::Expr::snap_app(rhs),
+                ),
+                _ => unreachable!()
+            };
+
+            let (target_value, mut stmts) = self.encode_pure_function_call_lhs_value(destination)
+                .with_span(call_site_span)?;
             let inhaled_expr = vir::Expr::eq_cmp(target_value.into(), expr);
 
             let (call_stmts, label) = self.encode_pure_function_call_site(
@@ -2501,6 +3429,20 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         }
     }
 
+    /// Check whether the destination of a call of `Iterator::next` has type `Option<&mut T>`.
+    fn yields_mutable_references(
+        &self,
+        destination: &Option<(mir::Place<'tcx>, BasicBlockIndex)>,
+    ) -> bool {
+        let tcx = self.encoder.env().tcx();
+        destination.map_or(false, |(place, _)| match place.ty(self.mir, tcx).ty.kind() {
+            ty::TyKind::Adt(_, substs) => substs.types().any(|ty| {
+                matches!(ty.kind(), ty::TyKind::Ref(_, _, Mutability::Mut))
+            }),
+            _ => false,
+        })
+    }
+
     fn encode_impure_function_call(
         &mut self,
         location: mir::Location,
@@ -2596,6 +3538,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         let mut const_arg_vars: HashSet<vir::Expr> = HashSet::new();
         let mut type_invs: HashMap<String, vir::Function> = HashMap::new();
         let mut constant_args = vec![];
+        let mut raw_ptr_args = vec![];
 
         for (mir_arg, arg, arg_ty, encoded_operand) in operands {
             arguments.push(arg.clone());
@@ -2603,6 +3546,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             let encoded_local = self.encode_prusti_local(arg);
             let arg_place = vir::Expr::local(encoded_local);
             debug!("arg: {:?} {}", arg, arg_place);
+            if arg_ty.is_unsafe_ptr() {
+                let addr_field = self.encoder.encode_value_field(arg_ty).with_span(call_site_span)?;
+                raw_ptr_args.push(arg_place.clone().field(addr_field));
+            }
             let inv_name = self.encoder.encode_type_invariant_use(arg_ty)
                 .with_span(call_site_span)?;
             let arg_inv = self.encoder.encode_type_invariant_def(arg_ty)
@@ -2715,6 +3662,31 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             ).with_span(call_site_span)?
         };
 
+        // A spec entailment on a function pointer can only be checked if the argument is known to
+        // point to a function item, whose specification the entailment is then checked against.
+        let mut fn_ptr_entailments = vec![];
+        for assertion in procedure_contract.functional_precondition() {
+            for (closure_ty, entailment) in collect_spec_entailments(self.encoder, assertion) {
+                if !closure_ty.is_fn_ptr() {
+                    continue;
+                }
+                let mut fn_ptr_args = mir_args.iter()
+                    .filter(|arg| self.mir_encoder.get_operand_ty(arg) == closure_ty);
+                let fn_def_id = match (fn_ptr_args.next(), fn_ptr_args.next()) {
+                    (Some(arg), None) => self.find_reified_fn_item(arg),
+                    _ => None,
+                };
+                match fn_def_id {
+                    Some(fn_def_id) => fn_ptr_entailments.push((entailment.clone(), fn_def_id)),
+                    None => return Err(SpannedEncodingError::unsupported(
+                        "spec entailments on function pointers can only be checked when the \
+                        argument is a function item",
+                        call_site_span,
+                    )),
+                }
+            }
+        }
+
         // Store a label for the pre state
         let pre_label = self.cfg_method.get_fresh_label_name();
         stmts.push(vir::Stmt::Label(pre_label.clone()));
@@ -2741,10 +3713,44 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             replace_fake_exprs(pre_func_spec),
             pos,
         ));
+        if !fn_ptr_entailments.is_empty() {
+            let target_args: Vec<vir::Expr> = procedure_contract
+                .args
+                .iter()
+                .map(|local| self.encode_prusti_local(*local).into())
+                .collect();
+            for (entailment, fn_def_id) in fn_ptr_entailments {
+                self.encoder.encode_spec_funcs(fn_def_id)?;
+                let entailment_check = encode_spec_entailment_check(
+                    self.encoder,
+                    &entailment,
+                    fn_def_id,
+                    &target_args,
+                )?;
+                stmts.push(vir::Stmt::Assert(
+                    replace_fake_exprs(entailment_check),
+                    pos,
+                ));
+            }
+        }
         stmts.push(vir::Stmt::Assert(
             replace_fake_exprs(pre_invs_spec),
             pos,
         ));
+        if let Some(termination_check) = self.encode_recursive_call_termination(
+            called_def_id,
+            &procedure_contract,
+            call_site_span,
+        )? {
+            let termination_pos = self
+                .encoder
+                .error_manager()
+                .register(call_site_span, ErrorCtxt::TerminationMeasureDecrease);
+            stmts.push(vir::Stmt::Assert(
+                replace_fake_exprs(termination_check),
+                termination_pos,
+            ));
+        }
         let pre_perm_spec = replace_fake_exprs(pre_type_spec.clone());
         assert!(!pos.is_default());
         stmts.push(vir::Stmt::Exhale(
@@ -2816,6 +3822,21 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             })
             .collect();
 
+        // The permissions to the targets of raw pointer arguments are given back to the
+        // places from which the pointers were copied.
+        let raw_ptr_returns: Vec<_> = raw_ptr_args
+            .iter()
+            .filter_map(|addr| {
+                if self.raw_ptr_origins.contains_key(addr) {
+                    Some((addr.clone().old(&pre_label), self.raw_ptr_root(addr)))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let post_type_spec = post_type_spec.replace_multiple_places(&raw_ptr_returns);
+        let post_func_spec = post_func_spec.replace_multiple_places(&raw_ptr_returns);
+
         let post_perm_spec = replace_fake_exprs(post_type_spec);
         stmts.push(vir::Stmt::Inhale(
             post_perm_spec.remove_read_permissions(),
@@ -2885,6 +3906,40 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         )
     }
 
+    /// Encode a call of a method of a ghost collection in ghost code, by inhaling
+    /// that the snapshot of the result is the value computed by the method.
+    fn encode_ghost_method_call(
+        &mut self,
+        location: mir::Location,
+        call_site_span: Span,
+        args: &[mir::Operand<'tcx>],
+        destination: &Option<(mir::Place<'tcx>, BasicBlockIndex)>,
+        called_def_id: ProcedureDefId,
+        substs: ty::subst::SubstsRef<'tcx>,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let mut arg_exprs = vec![];
+        for operand in args.iter() {
+            let arg_expr = self.mir_encoder.encode_operand_expr(operand)
+                .with_span(call_site_span)?;
+            arg_exprs.push(vir::Expr::snap_app(arg_expr));
+        }
+        let result = self.encoder.encode_ghost_method_call(called_def_id, substs, arg_exprs)
+            .with_span(call_site_span)?;
+
+        let (target_place, mut stmts) = self.encode_pure_function_call_lhs_place(destination);
+        let inhaled_expr = vir::Expr::eq_cmp(vir::Expr::snap_app(target_place), result);
+
+        let (call_stmts, label) = self.encode_pure_function_call_site(
+            location,
+            destination,
+            inhaled_expr,
+        );
+        stmts.extend(call_stmts);
+
+        self.encode_transfer_args_permissions(location, args, &mut stmts, label, false)?;
+        Ok(stmts)
+    }
+
     fn encode_specified_pure_function_call(
         &mut self,
         location: mir::Location,
@@ -3032,8 +4087,26 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                     ty::TyKind::RawPtr(ty::TypeAndMut {
                         ty: ref inner_ty, ..
                     }),
-                )
-                | (Some(ref place), ty::TyKind::Ref(_, ref inner_ty, _)) => {
+                ) => {
+                    let addr_field = self.encoder
+                        .encode_dereference_field(inner_ty)
+                        .with_span(span)?;
+                    let addr = place.clone().field(addr_field);
+                    // Give the permission to the target back to the place the address
+                    // was first copied from.
+                    let origin = if self.raw_ptr_origins.contains_key(&addr) {
+                        self.raw_ptr_root(&addr)
+                    } else {
+                        addr.clone().old(&label)
+                    };
+                    stmts.extend(self.encode_transfer_permissions(
+                        addr,
+                        origin,
+                        location,
+                        is_in_package_stmt,
+                    ));
+                }
+                (Some(ref place), ty::TyKind::Ref(_, ref inner_ty, _)) => {
                     let ref_field = self.encoder
                         .encode_dereference_field(inner_ty)
                         .with_span(span)?;
@@ -3083,11 +4156,17 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         -> SpannedEncodingResult<vir::Expr>
     {
         Ok(match self.locals.get_type(local).kind() {
-            ty::TyKind::RawPtr(ty::TypeAndMut {
-                ref ty,
-                mutbl: mutability,
-            })
-            | ty::TyKind::Ref(_, ref ty, mutability) => {
+            ty::TyKind::RawPtr(ty::TypeAndMut { ref ty, .. }) => {
+                // The permission to the pointee is not carried by a raw pointer.
+                let encoded_local = self.encode_prusti_local(local);
+                let field = self.encoder.encode_dereference_field(ty)
+                    .with_span(
+                        self.mir_encoder.get_local_span(local.into())
+                    )?;
+                let place = vir::Expr::from(encoded_local).field(field);
+                vir::Expr::acc_permission(place, vir::PermAmount::Write)
+            }
+            ty::TyKind::Ref(_, ref ty, mutability) => {
                 // Use unfolded references.
                 let encoded_local = self.encode_prusti_local(local);
                 let field = self.encoder.encode_dereference_field(ty)
@@ -3114,6 +4193,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         })
     }
 
+    /// Type invariants are assumed and checked only at the boundaries of public functions, so
+    /// that private helpers are allowed to temporarily break them.
+    fn has_type_invariant_boundary(&self, def_id: ProcedureDefId) -> bool {
+        self.encoder.env().tcx().visibility(def_id).is_public()
+    }
+
     /// Encode the precondition with three expressions:
     /// - one for the type encoding
     /// - one for the type invariants
@@ -3197,7 +4282,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 None,
                 ErrorCtxt::GenericExpression,
             )?;
-            func_spec.push(value);
+            if value.is_only_permissions() {
+                // Ownership of raw pointer targets, stated with `own(..)`.
+                type_spec.push(value);
+            } else {
+                func_spec.push(value);
+            }
         }
         let precondition_spans = MultiSpan::from_spans(
             func_precondition
@@ -3211,11 +4301,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         );
 
         let mut invs_spec: Vec<vir::Expr> = vec![];
+        let check_type_invariants = self.has_type_invariant_boundary(contract.def_id);
         for arg in contract.args.iter() {
             // FIXME: this is somewhat hacky to avoid consistency errors with raw_ref args. this
             // assumes that invariants for raw_ref types are always empty.
             let ty = self.locals.get_type(*arg);
-            if !ty.is_unsafe_ptr() {
+            if check_type_invariants && !ty.is_unsafe_ptr() {
                 invs_spec.push(
                     self.encoder.encode_invariant_func_app(
                         ty,
@@ -3288,6 +4379,11 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             start_cfg_block,
             vir::Stmt::Inhale(func_spec),
         );
+        for arg in self.mir.args_iter() {
+            for stmt in self.encode_counterexample_var_update(arg)? {
+                self.cfg_method.add_stmt(start_cfg_block, stmt);
+            }
+        }
         self.cfg_method.add_stmt(
             start_cfg_block,
             vir::Stmt::Label(PRECONDITION_LABEL.to_string()),
@@ -3431,6 +4527,82 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         }
     }
 
+    /// Encode the check that a recursive call decreases the termination measure: the measure
+    /// of the called procedure, evaluated on the arguments of the call, has to be non-negative
+    /// and smaller than the measure of this procedure evaluated in its pre-state. A call is
+    /// recursive if the called procedure might call this one back, so mutual recursion is
+    /// checked as well, provided that all the involved procedures have a measure.
+    ///
+    /// Returns `None` if the call is not recursive or the procedure has no measure.
+    fn encode_recursive_call_termination(
+        &self,
+        called_def_id: ProcedureDefId,
+        called_contract: &ProcedureContract<'tcx>,
+        call_site_span: Span,
+    ) -> SpannedEncodingResult<Option<vir::Expr>> {
+        let caller_measure = if let Some(measure) = self.encoder.get_termination_measure(self.proc_def_id) {
+            measure
+        } else {
+            return Ok(None);
+        };
+        if !self.encoder.is_recursive_call(self.proc_def_id, called_def_id) {
+            return Ok(None);
+        }
+        let callee_measure = if let Some(measure) = self.encoder.get_termination_measure(called_def_id) {
+            measure
+        } else {
+            return Err(SpannedEncodingError::incorrect(
+                "the termination of this mutually recursive call cannot be checked, because \
+                the called function has no `#[decreases(..)]` clause",
+                call_site_span,
+            ));
+        };
+
+        let contract = self.procedure_contract();
+        let caller_args: Vec<vir::Expr> = contract
+            .args
+            .iter()
+            .map(|local| self.encode_prusti_local(*local).into())
+            .collect();
+        let caller_measure = self.encoder.encode_assertion(
+            caller_measure,
+            &self.mir,
+            Some(PRECONDITION_LABEL),
+            &caller_args,
+            None,
+            false,
+            None,
+            ErrorCtxt::GenericExpression,
+        )?;
+        let caller_measure = self.wrap_arguments_into_old(
+            caller_measure,
+            PRECONDITION_LABEL,
+            contract,
+            &caller_args,
+        )?;
+
+        let callee_args: Vec<vir::Expr> = called_contract
+            .args
+            .iter()
+            .map(|local| self.encode_prusti_local(*local).into())
+            .collect();
+        let callee_measure = self.encoder.encode_assertion(
+            callee_measure,
+            &self.mir,
+            None,
+            &callee_args,
+            None,
+            false,
+            None,
+            ErrorCtxt::GenericExpression,
+        )?;
+
+        Ok(Some(vir::Expr::and(
+            vir::Expr::le_cmp(0.into(), callee_measure.clone()),
+            vir::Expr::lt_cmp(callee_measure, caller_measure),
+        )))
+    }
+
     /// Wrap function arguments used in the postcondition into ``old``:
     ///
     /// +   For references wrap the base ``_1.var_ref``.
@@ -3499,6 +4671,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
     )> {
         let mut type_spec = vec![];
         let mut invs_spec = vec![];
+        let check_type_invariants = self.has_type_invariant_boundary(contract.def_id);
         let mut read_transfer = vec![]; // Permissions taken as read
                                         // references that need to
                                         // be transfered to old.
@@ -3528,12 +4701,14 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 }
                 Mutability::Mut => {
                     add_type_spec(vir::PermAmount::Write);
-                    let inv = self
-                        .encoder
-                        .encode_invariant_func_app(place_ty, old_place_expr)
-                        // TODO: Use a better span
-                        .with_span(self.mir.span)?;
-                    invs_spec.push(inv);
+                    if check_type_invariants {
+                        let inv = self
+                            .encoder
+                            .encode_invariant_func_app(place_ty, old_place_expr)
+                            // TODO: Use a better span
+                            .with_span(self.mir.span)?;
+                        invs_spec.push(inv);
+                    }
                 }
             };
         }
@@ -3606,19 +4781,26 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 contract,
                 &encoded_args
             )?;
-            func_spec.push(assertion);
+            if assertion.is_only_permissions() {
+                // Ownership of raw pointer targets, stated with `own(..)`.
+                type_spec.push(assertion);
+            } else {
+                func_spec.push(assertion);
+            }
         }
         let postcondition_span = MultiSpan::from_spans(func_spec_spans);
         let func_spec_pos = self.encoder.error_manager()
             .register_span(postcondition_span.clone());
 
         // Encode invariant for return value
-        invs_spec.push(
-            self.encoder.encode_invariant_func_app(
-                self.locals.get_type(contract.returned_value),
-                encoded_return.clone(),
-            ).with_span(postcondition_span.clone())?
-        );
+        if check_type_invariants {
+            invs_spec.push(
+                self.encoder.encode_invariant_func_app(
+                    self.locals.get_type(contract.returned_value),
+                    encoded_return.clone(),
+                ).with_span(postcondition_span.clone())?
+            );
+        }
 
         // Encode possible strengthening, in case of trait method implementation
         let strengthening_spec: Option<Expr> = postcondition_strengthening
@@ -3802,7 +4984,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             for arg_index in self.mir.args_iter() {
                 let arg_ty = self.mir.local_decls[arg_index].ty;
                 let arg_span = self.mir_encoder.get_local_span(arg_index);
-                if self.mir_encoder.is_reference(arg_ty) {
+                if arg_ty.is_region_ptr() {
                     let encoded_arg = self.mir_encoder.encode_local(arg_index)?;
                     let (deref_place, ..) =
                         self.mir_encoder
@@ -3941,19 +5123,22 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                     .encode_deref(encoded_arg.clone(), ty)
                     .with_span(arg_span)?;
 
-                // Fold argument.
-                let deref_pred = self
-                    .mir_encoder
-                    .encode_place_predicate_permission(
-                        encoded_deref.clone(),
-                        vir::PermAmount::Write,
-                    )
-                    .unwrap();
-                for stmt in self
-                    .encode_obtain(deref_pred, type_inv_pos)
-                    .drain(..)
-                {
-                    self.cfg_method.add_stmt(return_cfg_block, stmt);
+                // Fold argument. The target of a raw pointer is owned only if the
+                // postcondition says so.
+                if !ty.is_unsafe_ptr() {
+                    let deref_pred = self
+                        .mir_encoder
+                        .encode_place_predicate_permission(
+                            encoded_deref.clone(),
+                            vir::PermAmount::Write,
+                        )
+                        .unwrap();
+                    for stmt in self
+                        .encode_obtain(deref_pred, type_inv_pos)
+                        .drain(..)
+                    {
+                        self.cfg_method.add_stmt(return_cfg_block, stmt);
+                    }
                 }
 
                 // Transfer permissions.
@@ -3993,7 +5178,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         let ty = self.locals.get_type(contract.returned_value);
         let encoded_return: vir::Expr = self.encode_prusti_local(contract.returned_value).into();
         let return_span = self.mir_encoder.get_local_span(contract.returned_value.into());
-        let encoded_return_expr = if self.mir_encoder.is_reference(ty) {
+        let encoded_return_expr = if ty.is_region_ptr() {
             let (encoded_deref, ..) = self.mir_encoder
                 .encode_deref(encoded_return, ty)
                 .with_span(return_span)?;
@@ -4329,7 +5514,8 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                                                     &e,
                                                 ));
                                             }
-                                            ExprOrArrayBase::ArrayBase(b) => {
+                                            ExprOrArrayBase::ArrayBase(b)
+                                            | ExprOrArrayBase::SliceBase(b) => {
                                                 let eq = self.construct_value_preserving_array_equality(loop_head, b);
                                                 // arrays can be mentioned multiple times, so we
                                                 // need to check here
@@ -4337,7 +5523,6 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                                                     equalities.push(eq);
                                                 }
                                             }
-                                            ExprOrArrayBase::SliceBase(_) => unimplemented!("slices in loops not yet implemented"),
                                         }
                                     }
                                 }
@@ -4390,6 +5575,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         for bbi in self.procedure.get_reachable_cfg_blocks() {
             if Some(loop_head) == self.loop_encoder.get_loop_head(bbi)
                 && self.procedure.is_spec_block(bbi)
+                && !self.procedure.is_ghost_statement_block(bbi)
             {
                 res.push(bbi)
             } else {
@@ -4466,6 +5652,129 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         Ok((encoded_specs, MultiSpan::from_spans(encoded_spec_spans)))
     }
 
+    /// Encode the variant of a loop, if it has one.
+    fn encode_loop_variant_spec(
+        &self,
+        loop_head: BasicBlockIndex,
+        loop_inv_block: BasicBlockIndex,
+    ) -> SpannedEncodingResult<Option<(vir::Expr, MultiSpan)>> {
+        // `body_variant!(..)` is desugared to a closure with special attributes,
+        // like `body_invariant!(..)`.
+        let mut variants = vec![];
+        for bbi in self.get_loop_spec_blocks(loop_head) {
+            for stmt in &self.mir.basic_blocks()[bbi].statements {
+                if let mir::StatementKind::Assign(box (
+                    _,
+                    mir::Rvalue::Aggregate(box mir::AggregateKind::Closure(cl_def_id, _), _),
+                )) = stmt.kind {
+                    variants.extend(self.encoder.get_loop_specs(cl_def_id).unwrap().variant);
+                }
+            }
+        }
+        trace!("loop head {:?} has variants {:?}", loop_head, variants);
+
+        let variant = match variants.len() {
+            0 => return Ok(None),
+            1 => variants.pop().unwrap(),
+            _ => {
+                let spans = variants
+                    .iter()
+                    .flat_map(|variant| {
+                        typed::Spanned::get_spans(variant, &self.mir, self.encoder.env().tcx())
+                    })
+                    .collect();
+                return Err(SpannedEncodingError::incorrect(
+                    "a loop can have at most one variant",
+                    MultiSpan::from_spans(spans),
+                ));
+            }
+        };
+
+        let encoded_args: Vec<vir::Expr> = self
+            .mir
+            .args_iter()
+            .map(|local| self.mir_encoder.encode_local(local).map(|l| l.into()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let encoded_variant = self.encoder.encode_assertion(
+            &variant,
+            &self.mir,
+            Some(PRECONDITION_LABEL),
+            &encoded_args,
+            None,
+            false,
+            Some(loop_inv_block),
+            ErrorCtxt::GenericExpression,
+        )?;
+        let variant_spans = typed::Spanned::get_spans(&variant, &self.mir, self.encoder.env().tcx());
+        Ok(Some((encoded_variant, MultiSpan::from_spans(variant_spans))))
+    }
+
+    /// Encode the `prusti_assert!`, `prusti_assume!` and `prusti_refute!` statements that are
+    /// type-checked in the spec blocks starting at `spec_block`.
+    fn encode_ghost_statements(
+        &mut self,
+        spec_block: BasicBlockIndex,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let encoded_args: Vec<vir::Expr> = self
+            .mir
+            .args_iter()
+            .map(|local| self.mir_encoder.encode_local(local).map(|l| l.into()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmts = vec![];
+        for (_, cl_def_id) in self.procedure.get_spec_closures(spec_block) {
+            let ghost_statement = match self.encoder.get_ghost_statement(cl_def_id) {
+                Some(ghost_statement) => ghost_statement,
+                None => continue,
+            };
+            let ctxt = match ghost_statement.kind {
+                typed::GhostStatementKind::Assert => ErrorCtxt::PrustiAssert,
+                typed::GhostStatementKind::Assume => ErrorCtxt::PrustiAssume,
+                typed::GhostStatementKind::Refute => ErrorCtxt::PrustiRefute,
+            };
+            let encoded_assertion = self.encoder.encode_assertion(
+                &ghost_statement.assertion,
+                &self.mir,
+                Some(PRECONDITION_LABEL),
+                &encoded_args,
+                None,
+                false,
+                Some(spec_block),
+                ctxt.clone(),
+            )?;
+            let spans = typed::Spanned::get_spans(
+                &ghost_statement.assertion,
+                &self.mir,
+                self.encoder.env().tcx(),
+            );
+            stmts.push(vir::Stmt::comment(format!("prusti_{}!(..)", ghost_statement.kind)));
+            match ghost_statement.kind {
+                typed::GhostStatementKind::Assert => {
+                    let pos = self.encoder.error_manager().register(spans, ctxt);
+                    stmts.push(vir::Stmt::Assert(encoded_assertion, pos));
+                }
+                typed::GhostStatementKind::Assume => {
+                    stmts.push(vir::Stmt::Inhale(encoded_assertion));
+                }
+                typed::GhostStatementKind::Refute => {
+                    // The refutation is checked in a branch that is then discarded, so that its
+                    // failure does not affect the rest of the method.
+                    let pos = self.encoder.error_manager().register_refutation(spans);
+                    let guard = self.cfg_method.add_fresh_local_var(vir::Type::Bool);
+                    stmts.push(vir::Stmt::If(
+                        guard.into(),
+                        vec![
+                            vir::Stmt::Assert(encoded_assertion, pos),
+                            vir::Stmt::Inhale(false.into()),
+                        ],
+                        vec![],
+                    ));
+                }
+            }
+        }
+        Ok(stmts)
+    }
+
     fn encode_loop_invariant_exhale_stmts(
         &mut self,
         loop_head: BasicBlockIndex,
@@ -4796,6 +6105,81 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         Ok(stmts)
     }
 
+    fn encode_slice_direct_assign(
+        &mut self,
+        base: PlaceEncoding<'tcx>,
+        index: vir::Expr,
+        slice_ty: ty::Ty<'tcx>,
+        rhs: &mir::Rvalue<'tcx>,
+        location: mir::Location,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let span = self.mir_encoder.get_span_of_location(location);
+
+        // Same as `encode_array_direct_assign`, except that the length of the slice is not
+        // statically known, so it also has to be preserved across the havoc.
+
+        let (encoded_slice, mut stmts) = self.postprocess_place_encoding(
+            base,
+            ArrayAccessKind::Shared,
+        ).with_span(span)?;
+
+        let label = self.cfg_method.get_fresh_label_name();
+        stmts.push(vir::Stmt::label(&label));
+
+        let slice_types = self.encoder.encode_slice_types(slice_ty).with_span(span)?;
+
+        let idx_val_int = self.encoder.patch_snapshots(vir::Expr::snap_app(index)).with_span(span)?;
+        let slice_len = slice_types.encode_slice_len_call(self.encoder, encoded_slice.clone());
+
+        let slice_acc_expr = vir::Expr::predicate_access_predicate(
+            slice_types.slice_pred.clone(),
+            encoded_slice.clone(),
+            vir::PermAmount::Write,
+        );
+
+        // exhale and re-inhale to havoc
+        stmts.push(vir!{ exhale [slice_acc_expr] });
+        stmts.push(vir!{ inhale [slice_acc_expr] });
+
+        let old = |e| { vir::Expr::labelled_old(&label, e) };
+
+        stmts.push(vir!{ inhale [ vir!{ [ slice_len ] == [ old(slice_len.clone()) ] } ] });
+
+        // inhale infos about slice contents back
+        let i_var: vir::Expr = vir_local!{ i: Int }.into();
+        let zero_le_i = vir!{ [vir::Expr::from(0)] <= [ i_var ] };
+        let i_lt_len = vir!{ [ i_var ] < [ old(slice_len) ] };
+        let i_ne_idx = vir!{ [ i_var ] != [ old(idx_val_int.clone()) ] };
+        let idx_conditions = vir!{ [zero_le_i] && ([i_lt_len] && [i_ne_idx]) };
+        let lookup_ret_ty = self.encoder.encode_snapshot_type(slice_types.elem_ty_rs).with_span(span)?;
+        let lookup_slice_i = slice_types.encode_lookup_pure_call(self.encoder, encoded_slice.clone(), i_var, lookup_ret_ty.clone());
+        let lookup_same_as_old = vir!{ [lookup_slice_i] == [old(lookup_slice_i.clone())] };
+        let forall_body = vir!{ [idx_conditions] ==> [lookup_same_as_old] };
+        let all_others_unchanged = vir!{ forall i: Int :: { [lookup_slice_i] } [ forall_body ] };
+
+        stmts.push(vir!{ inhale [ all_others_unchanged ]});
+
+        let tmp = vir::Expr::from(self.cfg_method.add_fresh_local_var(slice_types.elem_ty.clone()));
+        stmts.extend(
+            self.encode_assign(
+                tmp.clone(),
+                rhs,
+                slice_types.elem_ty_rs,
+                location,
+            ).with_span(span)?
+        );
+
+        let tmp_val_field = self.encoder.encode_value_expr(tmp, slice_types.elem_ty_rs).with_span(span)?;
+
+        let indexed_lookup_pure_call = slice_types
+            .encode_lookup_pure_call(self.encoder, encoded_slice, old(idx_val_int), lookup_ret_ty);
+        let indexed_updated = vir!{ [ indexed_lookup_pure_call ] == [ tmp_val_field ] };
+
+        stmts.push(vir!{ inhale [ indexed_updated ] });
+
+        Ok(stmts)
+    }
+
     /// Return type:
     /// - `Vec<vir::Stmt>`: the statements that encode the assignment of `operand` to `lhs`
     fn encode_assign_operand(
@@ -4813,7 +6197,11 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             mir::Operand::Move(ref place) => {
                 let (src, mut stmts, ty, _) = self.encode_place(place, ArrayAccessKind::Shared).with_span(span)?;
                 let encode_stmts = match ty.kind() {
-                    ty::TyKind::RawPtr(..) | ty::TyKind::Ref(..) => {
+                    ty::TyKind::RawPtr(..) => {
+                        let field = self.encoder.encode_value_field(ty).with_span(span)?;
+                        self.encode_raw_ptr_copy(lhs, src.field(field), ty, location)?
+                    }
+                    ty::TyKind::Ref(..) => {
                         // Reborrow.
                         let field = self.encoder.encode_value_field(ty).with_span(span)?;
                         let mut alloc_stmts = self.prepare_assign_target(
@@ -4850,7 +6238,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
 
             mir::Operand::Copy(ref place) => {
                 let (src, mut stmts, ty, _) = self.encode_place(place, ArrayAccessKind::Shared).with_span(span)?;
-                let encode_stmts = if self.mir_encoder.is_reference(ty) {
+                let encode_stmts = if ty.is_unsafe_ptr() {
+                    let field = self.encoder.encode_value_field(ty).with_span(span)?;
+                    self.encode_raw_ptr_copy(lhs, src.field(field), ty, location)?
+                } else if self.mir_encoder.is_reference(ty) {
                     let loan = self.polonius_info().get_loan_at_location(location);
                     let ref_field = self.encoder.encode_value_field(ty).with_span(span)?;
                     let mut stmts = self.prepare_assign_target(
@@ -4887,6 +6278,9 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 };
                 match ty.kind() {
                     ty::TyKind::Tuple(elements) if elements.is_empty() => Vec::new(),
+                    ty::TyKind::Ref(_, inner_ty, _) if inner_ty.is_str() => {
+                        self.encode_str_literal_assign(lhs, ty, &val, location)?
+                    }
                     _ => {
                         let field = self.encoder.encode_value_field(ty).with_span(span)?;
                         let mut stmts = self.prepare_assign_target(
@@ -4910,13 +6304,141 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 }
             }
         };
-        debug!(
-            "[enter] encode_assign_operand(lhs={}, operand={:?}, location={:?}) = {}",
-            lhs,
-            operand,
-            location,
-            vir::stmts_to_str(&stmts)
-        );
+        debug!(
+            "[enter] encode_assign_operand(lhs={}, operand={:?}, location={:?}) = {}",
+            lhs,
+            operand,
+            location,
+            vir::stmts_to_str(&stmts)
+        );
+        Ok(stmts)
+    }
+
+    /// Assignment of a string literal. The literal is a shared reference to a string, so
+    /// we inhale read permission to it and fix its contents.
+    fn encode_str_literal_assign(
+        &mut self,
+        lhs: &vir::Expr,
+        ty: ty::Ty<'tcx>,
+        val: &ty::ConstKind<'tcx>,
+        location: mir::Location,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let span = self.mir_encoder.get_span_of_location(location);
+        let field = self.encoder.encode_value_field(ty).with_span(span)?;
+        let mut stmts = if !self.init_info.is_vir_place_accessible(lhs, location) {
+            let mut alloc_stmts = self.encode_havoc(lhs);
+            alloc_stmts.push(vir::Stmt::Inhale(
+                vir::Expr::acc_permission(lhs.clone().field(field.clone()), vir::PermAmount::Write),
+            ));
+            alloc_stmts
+        } else {
+            vec![]
+        };
+        let literal = lhs.clone().field(field);
+        let literal_snapshot = self.encoder.encode_str_literal_snapshot(val).with_span(span)?;
+        stmts.push(vir::Stmt::Inhale(
+            vir::Expr::pred_permission(literal.clone(), vir::PermAmount::Read).unwrap(),
+        ));
+        stmts.push(vir::Stmt::Inhale(
+            vir!{ [vir::Expr::snap_app(literal)] == [literal_snapshot] },
+        ));
+        Ok(stmts)
+    }
+
+    /// Assignment of the address of a place to a raw pointer.
+    fn encode_assign_address_of(
+        &mut self,
+        encoded_lhs: vir::Expr,
+        place: &mir::Place<'tcx>,
+        ty: ty::Ty<'tcx>,
+        location: mir::Location,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let span = self.mir_encoder.get_span_of_location(location);
+        let (encoded_place, _, _) = self.mir_encoder.encode_place(place).with_span(span)?;
+        let src_addr = match encoded_place.into_array_base() {
+            ExprOrArrayBase::Expr(e) => e,
+            ExprOrArrayBase::ArrayBase(_) | ExprOrArrayBase::SliceBase(_) => {
+                return Err(SpannedEncodingError::unsupported(
+                    "taking the raw address of an array or slice element is not supported",
+                    span,
+                ));
+            }
+        };
+        let field = self.encoder.encode_value_field(ty).with_span(span)?;
+        let target = encoded_lhs.clone().field(field.clone());
+        // The permission stays with the place, copies of the pointer borrow it from there.
+        let mut stmts = self.prepare_raw_ptr_target(&encoded_lhs, field, location);
+        stmts.push(vir::Stmt::Assign(target.clone(), src_addr.clone(), vir::AssignKind::Ghost));
+        self.raw_ptr_origins.insert(target, src_addr);
+        Ok(stmts)
+    }
+
+    /// The first place of the chain of copies that produced the address `addr`.
+    fn raw_ptr_root(&self, addr: &vir::Expr) -> vir::Expr {
+        let mut root = addr;
+        let mut visited = HashSet::new();
+        while let Some(origin) = self.raw_ptr_origins.get(root) {
+            if !visited.insert(origin) {
+                break;
+            }
+            root = origin;
+        }
+        root.clone()
+    }
+
+    /// Allocate the address field of the raw pointer `lhs`, if necessary.
+    fn prepare_raw_ptr_target(
+        &mut self,
+        lhs: &vir::Expr,
+        field: vir::Field,
+        location: mir::Location,
+    ) -> Vec<vir::Stmt> {
+        if !self.init_info.is_vir_place_accessible(lhs, location) {
+            let mut alloc_stmts = self.encode_havoc(lhs);
+            alloc_stmts.push(vir::Stmt::Inhale(
+                vir::Expr::acc_permission(lhs.clone().field(field), vir::PermAmount::Write),
+            ));
+            alloc_stmts
+        } else {
+            vec![]
+        }
+    }
+
+    /// Copy the address `src_addr` into the raw pointer `lhs`. The permission to the pointee,
+    /// if any, is moved to the copy and given back to the root of the chain of copies after
+    /// the next call that takes `lhs` as an argument.
+    fn encode_raw_ptr_copy(
+        &mut self,
+        lhs: &vir::Expr,
+        src_addr: vir::Expr,
+        ty: ty::Ty<'tcx>,
+        location: mir::Location,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let span = self.mir_encoder.get_span_of_location(location);
+        let field = self.encoder.encode_value_field(ty).with_span(span)?;
+        let target = lhs.clone().field(field.clone());
+        let mut stmts = self.prepare_raw_ptr_target(lhs, field, location);
+        stmts.push(vir::Stmt::Assign(target.clone(), src_addr.clone(), vir::AssignKind::Ghost));
+        let root = self.raw_ptr_root(&src_addr);
+        stmts.push(vir::Stmt::TransferPerm(root, target.clone(), false));
+        self.raw_ptr_origins.insert(target, src_addr);
+        Ok(stmts)
+    }
+
+    /// Assign a computed address, such as `null` or an offset, to the raw pointer `lhs`.
+    fn encode_raw_ptr_value_assign(
+        &mut self,
+        lhs: &vir::Expr,
+        addr: vir::Expr,
+        ty: ty::Ty<'tcx>,
+        location: mir::Location,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let span = self.mir_encoder.get_span_of_location(location);
+        let field = self.encoder.encode_value_field(ty).with_span(span)?;
+        let target = lhs.clone().field(field.clone());
+        let mut stmts = self.prepare_raw_ptr_target(lhs, field, location);
+        stmts.push(vir::Stmt::Assign(target.clone(), addr, vir::AssignKind::Copy));
+        self.raw_ptr_origins.remove(&target);
         Ok(stmts)
     }
 
@@ -4938,14 +6460,20 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             right
         );
         let span = self.mir_encoder.get_span_of_location(location);
-        let encoded_left = self.mir_encoder.encode_operand_expr(left)
+        let (encoded_left, mut stmts) = self.encode_operand_expr_with_stmts(left)
             .with_span(span)?;
-        let encoded_right = self.mir_encoder.encode_operand_expr(right)
+        let (encoded_right, right_stmts) = self.encode_operand_expr_with_stmts(right)
             .with_span(span)?;
+        stmts.extend(right_stmts);
         let encoded_value =
-            self.mir_encoder.encode_bin_op_expr(op, encoded_left, encoded_right, ty)
-                .with_span(span)?;
-        self.encode_copy_value_assign(encoded_lhs, encoded_value, ty, location)
+            self.mir_encoder.encode_bin_op_expr(
+                op,
+                encoded_left,
+                encoded_right,
+                self.mir_encoder.get_operand_ty(left),
+            ).with_span(span)?;
+        stmts.extend(self.encode_copy_value_assign(encoded_lhs, encoded_value, ty, location)?);
+        Ok(stmts)
     }
 
     fn encode_copy_value_assign(
@@ -4988,9 +6516,9 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         } else {
             unreachable!()
         };
-        let encoded_left = self.mir_encoder.encode_operand_expr(left)
+        let (encoded_left, operand_stmts) = self.encode_operand_expr_with_stmts(left)
             .with_span(span)?;
-        let encoded_right = self.mir_encoder.encode_operand_expr(right)
+        let (encoded_right, right_stmts) = self.encode_operand_expr_with_stmts(right)
             .with_span(span)?;
         let encoded_value = self.mir_encoder.encode_bin_op_expr(
             op,
@@ -5017,7 +6545,9 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             .encode_raw_ref_field("tuple_1".to_string(), field_types[1].expect_ty())
             .with_span(span)?;
         let check_field_value = self.encoder.encode_value_field(field_types[1].expect_ty()).with_span(span)?;
-        let mut stmts = if !self
+        let mut stmts = operand_stmts;
+        stmts.extend(right_stmts);
+        stmts.extend(if !self
             .init_info
             .is_vir_place_accessible(&encoded_lhs, location)
         {
@@ -5044,7 +6574,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             alloc_stmts
         } else {
             Vec::with_capacity(2)
-        };
+        });
         // Initialize lhs.field
         stmts.push(vir::Stmt::Assign(
             encoded_lhs
@@ -5078,13 +6608,14 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             op,
             operand
         );
-        let encoded_val = self.mir_encoder.encode_operand_expr(operand)
+        let (encoded_val, mut stmts) = self.encode_operand_expr_with_stmts(operand)
             .with_span(
                 self.mir_encoder.get_span_of_location(location)
             )?;
-        let encoded_value = self.mir_encoder.encode_unary_op_expr(op, encoded_val);
+        let encoded_value = self.mir_encoder.encode_unary_op_expr(op, encoded_val, ty);
         // Initialize `lhs.field`
-        self.encode_copy_value_assign(encoded_lhs, encoded_value, ty, location)
+        stmts.extend(self.encode_copy_value_assign(encoded_lhs, encoded_value, ty, location)?);
+        Ok(stmts)
     }
 
     /// Assignment with a nullary op on the RHS.
@@ -5354,6 +6885,67 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         Ok(stmts)
     }
 
+    /// Unsizing a reference or box to a trait object. The dynamic type is abstracted away: the
+    /// permission to the pointee is transferred from the source to a fresh abstract predicate of
+    /// the trait object, and calls on it are encoded using the specification of the trait.
+    fn encode_assign_trait_object(
+        &mut self,
+        encoded_lhs: vir::Expr,
+        operand: &mir::Operand<'tcx>,
+        ty: ty::Ty<'tcx>,
+        location: mir::Location,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        trace!("encode_assign_trait_object(lhs={:?}, operand={:?}, ty={:?})", encoded_lhs, operand, ty);
+        let span = self.mir_encoder.get_span_of_location(location);
+        let perm_amount = match ty.kind() {
+            ty::TyKind::Ref(_, _, mir::Mutability::Not) => vir::PermAmount::Read,
+            ty::TyKind::Adt(adt_def, _) if adt_def.is_box() => vir::PermAmount::Write,
+            _ => return Err(SpannedEncodingError::unsupported(
+                "mutable references to trait objects are not supported",
+                span,
+            )),
+        };
+        let mut stmts = Vec::new();
+
+        let label = self.cfg_method.get_fresh_label_name();
+        stmts.push(vir::Stmt::Label(label.clone()));
+
+        // Take the permission to the pointee from the source. A box is moved, so the whole
+        // permission goes to the trait object. The permission of a shared reference can be
+        // duplicated, so it is only checked to be there.
+        let source_place = match operand {
+            mir::Operand::Move(place) | mir::Operand::Copy(place) => place,
+            mir::Operand::Constant(_) => return Err(SpannedEncodingError::unsupported(
+                "unsizing a constant to a trait object is not supported",
+                span,
+            )),
+        };
+        let (encoded_source, source_ty, _) = self.mir_encoder.encode_place(source_place)
+            .with_span(span)?;
+        let source_pointee_ty = source_ty.builtin_deref(true).unwrap().ty;
+        let source_deref_field = self.encoder.encode_dereference_field(source_pointee_ty)
+            .with_span(span)?;
+        let source_expr = encoded_source.try_into_expr().with_span(span)?.field(source_deref_field);
+        let source_perm = vir::Expr::pred_permission(source_expr.clone(), perm_amount).unwrap();
+        if perm_amount == vir::PermAmount::Write {
+            stmts.push(vir!{ exhale [source_perm] });
+            stmts.push(vir!{ exhale [vir::Expr::acc_permission(source_expr.clone(), vir::PermAmount::Write)] });
+        } else {
+            stmts.push(vir!{ assert [source_perm] });
+        }
+
+        let object_ty = ty.builtin_deref(true).unwrap().ty;
+        stmts.extend(self.encode_havoc(&encoded_lhs));
+        let deref_field = self.encoder.encode_dereference_field(object_ty).with_span(span)?;
+        let object_expr = encoded_lhs.field(deref_field);
+        stmts.push(vir!{ inhale [vir::Expr::acc_permission(object_expr.clone(), vir::PermAmount::Write)] });
+        stmts.push(vir!{ inhale [vir::Expr::pred_permission(object_expr, perm_amount).unwrap()] });
+
+        self.label_after_location.insert(location, label);
+
+        Ok(stmts)
+    }
+
     fn encode_assign_sequence_len(
         &mut self,
         encoded_lhs: vir::Expr,
@@ -5611,6 +7203,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             ty::TyKind::Bool
             | ty::TyKind::Int(_)
             | ty::TyKind::Uint(_)
+            | ty::TyKind::Float(_)
             | ty::TyKind::Char => {
                 self.encode_copy_primitive_value(src, dst, self_ty, location)?
             }
@@ -5618,6 +7211,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             ty::TyKind::Adt(_, _)
             | ty::TyKind::Tuple(_)
             | ty::TyKind::Param(_)
+            | ty::TyKind::Projection(_)
             | ty::TyKind::Array(_, _) => {
                 self.encode_copy_snapshot_value(src, dst)?
             }
@@ -5837,6 +7431,24 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         Ok((encoded_expr, encoding_stmts, ty, variant_idx))
     }
 
+    /// Encode an operand that may contain an array or slice lookup, returning the statements
+    /// needed to evaluate the lookup together with the value of the operand.
+    fn encode_operand_expr_with_stmts(
+        &mut self,
+        operand: &mir::Operand<'tcx>,
+    ) -> EncodingResult<(vir::Expr, Vec<vir::Stmt>)> {
+        match operand {
+            mir::Operand::Copy(place) | mir::Operand::Move(place) => {
+                let (encoded_place, stmts, ty, _) = self.encode_place(place, ArrayAccessKind::Shared)?;
+                let encoded_value = self.encoder.encode_value_expr(encoded_place, ty)?;
+                Ok((encoded_value, stmts))
+            }
+            mir::Operand::Constant(_) => {
+                Ok((self.mir_encoder.encode_operand_expr(operand)?, vec![]))
+            }
+        }
+    }
+
     fn encode_projection(
         &mut self,
         local: mir::Local,
@@ -6007,30 +7619,296 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 (vir::Expr::Variant(box expr, field, vir::Position::default()), stmts)
             }
             PlaceEncoding::SliceAccess { base, index, rust_slice_ty, .. } => {
-                let slice_types = self.encoder.encode_slice_types(rust_slice_ty)?;
+                if let ArrayAccessKind::Mutable(loan, location) = array_encode_kind {
+                    self.encode_slice_lookup_mut(*base, index, rust_slice_ty, loan, location)?
+                } else {
+                    self.encode_slice_lookup_shared(*base, index, rust_slice_ty)?
+                }
+            }
+            PlaceEncoding::SubsliceAccess { base, from, to, rust_slice_ty, .. } => {
+                self.encode_subslice(*base, from, to, rust_slice_ty, array_encode_kind)?
+            }
+            PlaceEncoding::ArraySubsliceAccess { base, from, rust_array_ty, rust_subarray_ty, .. } => {
+                self.encode_array_subslice(*base, from, rust_array_ty, rust_subarray_ty, array_encode_kind)?
+            }
+        })
+    }
 
-                let res = vir::Expr::local(self.cfg_method.add_fresh_local_var(slice_types.elem_ty.clone()));
-                let val_field = self.encoder.encode_value_field(slice_types.elem_ty_rs)?;
-                let res_val_field = res.clone().field(val_field);
-                let elem_snap_ty = self.encoder.encode_snapshot_type(slice_types.elem_ty_rs)?;
+    fn encode_slice_lookup_shared(
+        &mut self,
+        base: PlaceEncoding<'tcx>,
+        index: vir::Expr,
+        slice_ty: ty::Ty<'tcx>,
+    ) -> EncodingResult<(vir::Expr, Vec<vir::Stmt>)> {
+        let slice_types = self.encoder.encode_slice_types(slice_ty)?;
 
-                let (encoded_base_expr, mut stmts) = self.postprocess_place_encoding(*base, array_encode_kind)?;
-                stmts.extend(self.encode_havoc_and_allocation(&res));
+        let res = vir::Expr::local(self.cfg_method.add_fresh_local_var(slice_types.elem_ty.clone()));
+        let val_field = self.encoder.encode_value_field(slice_types.elem_ty_rs)?;
+        let res_val_field = res.clone().field(val_field);
+        let elem_snap_ty = self.encoder.encode_snapshot_type(slice_types.elem_ty_rs)?;
 
-                let idx_val_int = self.encoder.patch_snapshots(vir::Expr::snap_app(index))?;
+        let (encoded_base_expr, mut stmts) = self.postprocess_place_encoding(base, ArrayAccessKind::Shared)?;
+        stmts.extend(self.encode_havoc_and_allocation(&res));
 
-                let lookup_pure_call = slice_types.encode_lookup_pure_call(
-                    self.encoder,
-                    encoded_base_expr,
-                    idx_val_int,
-                    elem_snap_ty,
-                );
+        let idx_val_int = self.encoder.patch_snapshots(vir::Expr::snap_app(index))?;
 
-                stmts.push(vir::Stmt::Inhale(vir!{ [lookup_pure_call] == [res_val_field] }));
+        let lookup_pure_call = slice_types.encode_lookup_pure_call(
+            self.encoder,
+            encoded_base_expr,
+            idx_val_int,
+            elem_snap_ty,
+        );
 
-                (res, stmts)
-            }
-        })
+        stmts.push(vir::Stmt::Inhale(vir!{ [lookup_pure_call] == [res_val_field] }));
+
+        Ok((res, stmts))
+    }
+
+    fn encode_slice_lookup_mut(
+        &mut self,
+        base: PlaceEncoding<'tcx>,
+        index: vir::Expr,
+        slice_ty: ty::Ty<'tcx>,
+        _loan: Option<Borrow>,
+        location: mir::Location,
+    ) -> EncodingResult<(vir::Expr, Vec<vir::Stmt>)> {
+        let slice_types = self.encoder.encode_slice_types(slice_ty)?;
+
+        let res: vir::Expr = self.cfg_method.add_fresh_local_var(slice_types.elem_ty.clone()).into();
+        let val_field = self.encoder.encode_value_field(slice_types.elem_ty_rs)?;
+        let res_val_field = res.clone().field(val_field);
+
+        let (encoded_base_expr, mut stmts) = self.postprocess_place_encoding(base, ArrayAccessKind::Mutable(None, location))?;
+
+        let idx_val_int = self.encoder.patch_snapshots(vir::Expr::snap_app(index))?;
+
+        // var res: Ref := havoc_ref()
+        stmts.extend(self.encode_havoc_and_allocation(&res));
+
+        // label before for old[label](..) stuff
+        let before_label = self.cfg_method.get_fresh_label_name();
+        stmts.push(vir::Stmt::label(&before_label));
+
+        // exhale preconditions
+        let slice_len = slice_types.encode_slice_len_call(self.encoder, encoded_base_expr.clone());
+        stmts.push(vir::Stmt::Assert(
+            vir!{ [idx_val_int] < [slice_len] },
+            vir::Position::default(),
+        ));
+
+        // exhale Slice$i32(self)
+        let slice_access_pred = vir::Expr::pred_permission(
+            encoded_base_expr.clone(),
+            vir::PermAmount::Write,
+        ).unwrap();
+
+        stmts.push(vir!{ exhale [slice_access_pred] });
+
+        let old = |e| { vir::Expr::labelled_old(&before_label, e) };
+        let old_lhs = |e| { vir::Expr::labelled_old("lhs", e) };
+
+        // value of res
+        let lookup_ret_ty = self.encoder.encode_snapshot_type(slice_types.elem_ty_rs)?;
+        let lookup_pure_call = slice_types.encode_lookup_pure_call(
+            self.encoder,
+            encoded_base_expr.clone(),
+            idx_val_int.clone(),
+            lookup_ret_ty.clone(),
+        );
+        stmts.push(vir::Stmt::Inhale(
+            vir!{ [ old(lookup_pure_call) ] == [ res_val_field ] }
+        ));
+
+        // inhale magic wand
+        //
+        // ref$i32(result) --* Slice$i32(self)
+        //   // the length is unchanged
+        //   && Slice$len(self) == old(Slice$len(self))
+        //   // everything else unchanged
+        //   && forall i : Int :: { lookup_pure(self, i) }
+        //     0 <= i && i < old(Slice$len(self)) && index != i
+        //     ==> lookup_pure(self, i) == old[lhs](lookup_pure(self, i))
+        //   // the given index changed accordingly
+        //   && lookup_pure(self, index) == snap$i32(old[lhs](result))
+        let i_var: vir::Expr = vir_local!{ i: Int }.into();
+
+        let len_unchanged = vir!{ [ slice_len.clone() ] == [ old(slice_len.clone()) ] };
+        let zero_le_i = vir!{ [ vir::Expr::from(0) ] <= [ i_var ] };
+        let i_lt_len = vir!{ [ i_var ] < [ old(slice_len) ] };
+        let i_ne_idx = vir!{ [ i_var ] != [ old(idx_val_int.clone()) ] };
+        let idx_conditions = vir!{ [zero_le_i] && ([i_lt_len] && [i_ne_idx]) };
+        let lookup_slice_i = slice_types.encode_lookup_pure_call(
+            self.encoder,
+            encoded_base_expr.clone(),
+            i_var,
+            lookup_ret_ty.clone(),
+        );
+        let lookup_same_as_old = vir!{ [lookup_slice_i] == [old(lookup_slice_i.clone())] };
+        let forall_body = vir!{ [idx_conditions] ==> [lookup_same_as_old] };
+        let all_others_unchanged = vir!{ forall i: Int :: { [lookup_slice_i] } [ forall_body ] };
+        let indexed_lookup_pure = slice_types.encode_lookup_pure_call(
+            self.encoder,
+            encoded_base_expr.clone(),
+            old(idx_val_int),
+            lookup_ret_ty,
+        );
+        let indexed_updated = vir!{ [ indexed_lookup_pure ] == [ old_lhs(res_val_field.clone()) ] };
+
+        let magic_wand_rhs = vir!{ [len_unchanged] && ([all_others_unchanged] && [indexed_updated]) };
+        self.array_magic_wand_at.insert(
+            location,
+            (res_val_field, encoded_base_expr, magic_wand_rhs)
+        );
+
+        Ok((res, stmts))
+    }
+
+    /// Encode the subslice `base[from..len - to]` as a fresh slice whose elements are those of
+    /// `base` shifted by `from`. A mutable subslice takes the permission of `base` until it
+    /// expires, at which point the updated elements are written back.
+    /// Encodes the subarray of `base` that starts at `from` and has the type `subarray_ty`. The
+    /// result is a new array, whose elements are the ones of `base` from `from` on.
+    fn encode_array_subslice(
+        &mut self,
+        base: PlaceEncoding<'tcx>,
+        from: usize,
+        array_ty: ty::Ty<'tcx>,
+        subarray_ty: ty::Ty<'tcx>,
+        array_encode_kind: ArrayAccessKind,
+    ) -> EncodingResult<(vir::Expr, Vec<vir::Stmt>)> {
+        if let ArrayAccessKind::Mutable(..) = array_encode_kind {
+            return Err(EncodingError::unsupported(
+                "mutable subslice patterns on arrays are not supported",
+            ));
+        }
+        let array_types = self.encoder.encode_array_types(array_ty)?;
+        let subarray_types = self.encoder.encode_array_types(subarray_ty)?;
+        let (encoded_base_expr, mut stmts) = self.postprocess_place_encoding(base, ArrayAccessKind::Shared)?;
+
+        let res: vir::Expr = self.cfg_method.add_fresh_local_var(subarray_types.array_ty.clone()).into();
+        stmts.extend(self.encode_havoc_and_allocation(&res));
+
+        // forall i: Int :: { lookup_pure(res, i) }
+        //   0 <= i && i < len ==> lookup_pure(res, i) == lookup_pure(base, i + from)
+        let lookup_ret_ty = self.encoder.encode_snapshot_type(array_types.elem_ty_rs)?;
+        let i_var: vir::Expr = vir_local!{ i: Int }.into();
+        let lookup_res_i = subarray_types.encode_lookup_pure_call(
+            self.encoder,
+            res.clone(),
+            i_var.clone(),
+            lookup_ret_ty.clone(),
+        );
+        let lookup_base_shifted = array_types.encode_lookup_pure_call(
+            self.encoder,
+            encoded_base_expr,
+            vir!{ [ i_var ] + [ vir::Expr::from(from) ] },
+            lookup_ret_ty,
+        );
+        let in_subarray = vir!{
+            [ vir!{ [ vir::Expr::from(0) ] <= [ i_var ] } ]
+            && [ vir!{ [ i_var ] < [ vir::Expr::from(subarray_types.array_len) ] } ]
+        };
+        let elems_shifted = vir!{ [ lookup_res_i ] == [ lookup_base_shifted ] };
+        stmts.push(vir!{ inhale [ vir!{ forall i: Int :: { [lookup_res_i] } ([in_subarray] ==> [elems_shifted]) } ] });
+
+        Ok((res, stmts))
+    }
+
+    fn encode_subslice(
+        &mut self,
+        base: PlaceEncoding<'tcx>,
+        from: vir::Expr,
+        to: vir::Expr,
+        slice_ty: ty::Ty<'tcx>,
+        array_encode_kind: ArrayAccessKind,
+    ) -> EncodingResult<(vir::Expr, Vec<vir::Stmt>)> {
+        let slice_types = self.encoder.encode_slice_types(slice_ty)?;
+        let (encoded_base_expr, mut stmts) = self.postprocess_place_encoding(base, array_encode_kind)?;
+
+        let res: vir::Expr = self.cfg_method.add_fresh_local_var(slice_types.slice_ty.clone()).into();
+        stmts.extend(self.encode_havoc(&res));
+
+        let before_label = self.cfg_method.get_fresh_label_name();
+        stmts.push(vir::Stmt::label(&before_label));
+        let old = |e| { vir::Expr::labelled_old(&before_label, e) };
+
+        let perm_amount = if let ArrayAccessKind::Mutable(..) = array_encode_kind {
+            let base_access_pred = vir::Expr::pred_permission(
+                encoded_base_expr.clone(),
+                vir::PermAmount::Write,
+            ).unwrap();
+            stmts.push(vir!{ exhale [base_access_pred] });
+            vir::PermAmount::Write
+        } else {
+            vir::PermAmount::Read
+        };
+        stmts.push(vir!{ inhale [vir::Expr::predicate_access_predicate(
+            slice_types.slice_pred.clone(),
+            res.clone(),
+            perm_amount,
+        )] });
+
+        let base_len = slice_types.encode_slice_len_call(self.encoder, encoded_base_expr.clone());
+        let res_len = slice_types.encode_slice_len_call(self.encoder, res.clone());
+        let old_sub_len = vir!{ ([ old(base_len.clone()) ] - [ from.clone() ]) - [ to.clone() ] };
+        stmts.push(vir!{ inhale [ vir!{ [ res_len.clone() ] == [ old_sub_len.clone() ] } ] });
+
+        // forall i: Int :: { lookup_pure(res, i) }
+        //   0 <= i && i < Slice$len(res) ==> lookup_pure(res, i) == old(lookup_pure(base, i + from))
+        let lookup_ret_ty = self.encoder.encode_snapshot_type(slice_types.elem_ty_rs)?;
+        let i_var: vir::Expr = vir_local!{ i: Int }.into();
+        let zero_le_i = vir!{ [ vir::Expr::from(0) ] <= [ i_var ] };
+        let lookup_res_i = slice_types.encode_lookup_pure_call(
+            self.encoder,
+            res.clone(),
+            i_var.clone(),
+            lookup_ret_ty.clone(),
+        );
+        let lookup_base_shifted = slice_types.encode_lookup_pure_call(
+            self.encoder,
+            encoded_base_expr.clone(),
+            vir!{ [ i_var ] + [ from.clone() ] },
+            lookup_ret_ty.clone(),
+        );
+        let in_subslice = vir!{ [ zero_le_i.clone() ] && [ vir!{ [ i_var ] < [ res_len ] } ] };
+        let elems_shifted = vir!{ [ lookup_res_i ] == [ old(lookup_base_shifted.clone()) ] };
+        stmts.push(vir!{ inhale [ vir!{ forall i: Int :: { [lookup_res_i] } ([in_subslice] ==> [elems_shifted]) } ] });
+
+        if let ArrayAccessKind::Mutable(_, location) = array_encode_kind {
+            let old_lhs = |e| { vir::Expr::labelled_old("lhs", e) };
+            // the length of the base slice is unchanged
+            let len_unchanged = vir!{ [ base_len.clone() ] == [ old(base_len.clone()) ] };
+            // the elements outside of the subslice are unchanged
+            let lookup_base_i = slice_types.encode_lookup_pure_call(
+                self.encoder,
+                encoded_base_expr.clone(),
+                i_var.clone(),
+                lookup_ret_ty.clone(),
+            );
+            let before_from = vir!{ [ zero_le_i.clone() ] && [ vir!{ [ i_var ] < [ from ] } ] };
+            let after_to = vir!{
+                [ vir!{ [ vir!{ [ old(base_len.clone()) ] - [ to ] } ] <= [ i_var ] } ]
+                && [ vir!{ [ i_var ] < [ old(base_len) ] } ]
+            };
+            let outside_unchanged = vir!{ [ lookup_base_i ] == [ old(lookup_base_i.clone()) ] };
+            let others_unchanged = vir!{
+                forall i: Int :: { [lookup_base_i] } ([ vir!{ [before_from] || [after_to] } ] ==> [outside_unchanged])
+            };
+            // the elements of the subslice are written back
+            let in_old_subslice = vir!{ [ zero_le_i ] && [ vir!{ [ i_var ] < [ old_sub_len ] } ] };
+            let written_back = vir!{ [ lookup_base_shifted ] == [ old_lhs(lookup_res_i.clone()) ] };
+            let subslice_written_back = vir!{
+                forall i: Int :: { [ old_lhs(lookup_res_i) ] } ([in_old_subslice] ==> [written_back])
+            };
+
+            let magic_wand_rhs = vir!{ [len_unchanged] && ([others_unchanged] && [subslice_written_back]) };
+            self.array_magic_wand_at.insert(
+                location,
+                (res.clone(), encoded_base_expr, magic_wand_rhs)
+            );
+        }
+
+        Ok((res, stmts))
     }
 }
 
@@ -6044,3 +7922,19 @@ enum ArrayAccessKind {
 fn convert_loans_to_borrows(loans: &[facts::Loan]) -> Vec<Borrow> {
     loans.iter().map(|l| l.into()).collect()
 }
+
+/// The span of the first dereference of the raw pointer stored in the local variable of the
+/// encoded place `raw_pointer`.
+fn raw_pointer_deref_span<'tcx>(
+    mir: &mir::Body<'tcx>,
+    locals: &LocalVariableManager<'tcx>,
+    raw_pointer: &vir::Expr,
+) -> Option<Span> {
+    if !raw_pointer.is_local() {
+        return None;
+    }
+    let name = raw_pointer.get_base().name;
+    let local = mir.local_decls.indices().find(|&local| locals.get_name(local.into()) == name)?;
+    let location = detect_raw_pointer_deref(mir, local)?;
+    Some(mir.source_info(location).span)
+}
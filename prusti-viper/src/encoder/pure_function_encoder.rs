@@ -418,6 +418,8 @@ pub(super) struct PureFunctionBackwardInterpreter<'p, 'v: 'p, 'tcx: 'v> {
     encoder: &'p Encoder<'v, 'tcx>,
     mir: &'p mir::Body<'tcx>,
     mir_encoder: MirEncoder<'p, 'v, 'tcx>,
+    /// The function whose body is being encoded.
+    def_id: DefId,
     /// True if the encoder is currently encoding an assertion and not a pure function body. This
     /// flag is used to distinguish when assert terminators should be translated into `false` and
     /// when to a undefined function calls. This distinction allows overflow checks to be checked
@@ -439,6 +441,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> PureFunctionBackwardInterpreter<'p, 'v, 'tcx> {
             encoder,
             mir,
             mir_encoder: MirEncoder::new(encoder, mir, def_id),
+            def_id,
             is_encoding_assertion,
         }
     }
@@ -447,6 +450,79 @@ impl<'p, 'v: 'p, 'tcx: 'v> PureFunctionBackwardInterpreter<'p, 'v, 'tcx> {
         &self.mir_encoder
     }
 
+    /// Encode the condition under which a call to `called_def_id` with arguments `encoded_args`
+    /// is known to terminate: the termination measure of the called function, evaluated on the
+    /// arguments of the call, has to be non-negative and smaller than the measure evaluated on
+    /// the arguments of the function being encoded.
+    ///
+    /// Returns `None` if no check is needed, i.e. if the call is not recursive (the called
+    /// function cannot call back the function being encoded) or the function has no
+    /// `#[decreases(..)]` measure.
+    fn encode_recursive_call_termination(
+        &self,
+        called_def_id: DefId,
+        encoded_args: &[vir::Expr],
+        span: Span,
+    ) -> SpannedEncodingResult<Option<vir::Expr>> {
+        if self.is_encoding_assertion {
+            return Ok(None);
+        }
+        let caller_measure = if let Some(measure) = self.encoder.get_termination_measure(self.def_id) {
+            measure
+        } else {
+            return Ok(None);
+        };
+        if !self.encoder.is_recursive_call(self.def_id, called_def_id) {
+            return Ok(None);
+        }
+        let callee_measure = if let Some(measure) = self.encoder.get_termination_measure(called_def_id) {
+            measure
+        } else {
+            return Err(SpannedEncodingError::incorrect(
+                "the termination of this mutually recursive call cannot be checked, because \
+                the called function has no `#[decreases(..)]` clause",
+                span,
+            ));
+        };
+
+        // The arguments of the function being encoded. They are later replaced with the formal
+        // arguments of the Viper function.
+        let mut caller_args = vec![];
+        for arg in self.mir.args_iter() {
+            let arg_ty = self.mir_encoder.get_local_ty(arg);
+            let encoded_arg = self.mir_encoder.encode_local(arg)?;
+            caller_args.push(
+                self.encoder.encode_value_expr(vir::Expr::local(encoded_arg), arg_ty)
+                    .with_span(span)?
+            );
+        }
+
+        let caller_measure = self.encoder.encode_assertion(
+            caller_measure,
+            self.mir,
+            None,
+            &caller_args,
+            None,
+            true,
+            None,
+            ErrorCtxt::GenericExpression,
+        )?;
+        let callee_measure = self.encoder.encode_assertion(
+            callee_measure,
+            self.mir,
+            None,
+            encoded_args,
+            None,
+            true,
+            None,
+            ErrorCtxt::GenericExpression,
+        )?;
+        Ok(Some(vir::Expr::and(
+            vir::Expr::le_cmp(0.into(), callee_measure.clone()),
+            vir::Expr::lt_cmp(callee_measure, caller_measure),
+        )))
+    }
+
     /// Wrap all expressions contained in the state with downcast information to be used by the
     /// fold-unfold pass.
     fn apply_downcasts(&self, state: &mut MultiExprBackwardInterpreterState, location: mir::Location)
@@ -548,6 +624,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> PureFunctionBackwardInterpreter<'p, 'v, 'tcx> {
                     idx_val_int,
                 )?
             }
+            PlaceEncoding::SubsliceAccess { .. }
+            | PlaceEncoding::ArraySubsliceAccess { .. } => {
+                return Err(EncodingError::unsupported(
+                    "subslice patterns are not supported in pure functions",
+                ));
+            }
         })
     }
 }
@@ -821,6 +903,28 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                                 state
                             }
 
+                            "prusti_contracts::own" => {
+                                trace!("Encoding ownership of raw pointer {:?}", args[0]);
+                                assert_eq!(args.len(), 1);
+                                // `encoded_args[0]` is the address stored in the pointer
+                                let encoded_rhs = vir::Expr::pred_permission(
+                                    encoded_args[0].clone(),
+                                    vir::PermAmount::Write,
+                                ).unwrap();
+                                let mut state = states[&target_block].clone();
+                                state.substitute_value(&lhs_value, encoded_rhs);
+                                state
+                            }
+
+                            "core::ptr::const_ptr::<impl *const T>::is_null"
+                            | "core::ptr::mut_ptr::<impl *mut T>::is_null" => {
+                                assert_eq!(args.len(), 1);
+                                let null = vir::Expr::Const(vir::Const::Null, vir::Position::default());
+                                let mut state = states[target_block].clone();
+                                state.substitute_value(&lhs_value, vir!{ [encoded_args[0]] == [null] });
+                                state
+                            }
+
                             "std::cmp::PartialEq::eq"
                             if self.encoder.has_structural_eq_impl(
                                 self.mir_encoder.get_operand_ty(&args[0])
@@ -860,6 +964,38 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                                 state
                             }
 
+                            "core::slice::<impl [T]>::is_empty" => {
+                                assert_eq!(args.len(), 1);
+                                let slice_ty = self.mir_encoder.get_operand_ty(&args[0]);
+                                let len = self.encoder.encode_snapshot_slice_len(slice_ty, encoded_args[0].clone())
+                                    .with_span(span)?;
+
+                                let mut state = states[target_block].clone();
+                                state.substitute_value(&lhs_value, vir!{ [len] == [vir::Expr::from(0)] });
+                                state
+                            }
+
+                            // strings are encoded as slices of bytes, so these conversions are
+                            // the identity
+                            "core::str::<impl str>::as_bytes"
+                            | "std::string::String::as_str"
+                            | "std::string::String::as_bytes" => {
+                                assert_eq!(args.len(), 1);
+                                let mut state = states[target_block].clone();
+                                state.substitute_value(&lhs_value, encoded_args[0].clone());
+                                state
+                            }
+
+                            "std::ops::Deref::deref"
+                            if self.encoder.string_slice_ty(
+                                self.mir_encoder.get_operand_ty(&args[0]).peel_refs()
+                            ).is_some() => {
+                                assert_eq!(args.len(), 1);
+                                let mut state = states[target_block].clone();
+                                state.substitute_value(&lhs_value, encoded_args[0].clone());
+                                state
+                            }
+
                             "std::ops::Index::index" => {
                                 assert_eq!(args.len(), 2);
                                 trace!("slice::index(args={:?}, encoded_args={:?}, ty={:?}, lhs_value={:?})", args, encoded_args, ty, lhs_value);
@@ -907,8 +1043,20 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                                 state
                             }
 
+                            _ if self.encoder.ghost_collection_method_kind(def_id).is_some() => {
+                                let encoded_rhs = self.encoder.encode_ghost_method_call(
+                                    def_id,
+                                    substs,
+                                    encoded_args.into_iter().map(vir::Expr::snap_app).collect(),
+                                ).with_span(span)?;
+                                let mut state = states[target_block].clone();
+                                state.substitute_value(&lhs_value, encoded_rhs);
+                                state
+                            }
+
                             // simple function call
                             _ => {
+                                let def_id = self.encoder.resolve_pure_trait_method(def_id, substs);
                                 let is_pure_function = self.encoder.is_pure(def_id);
                                 let (function_name, return_type) = if is_pure_function {
                                     self.encoder.encode_pure_function_use(def_id)
@@ -934,6 +1082,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                                     .collect::<Result<_, _>>()
                                     .with_span(term.source_info.span)?;
 
+                                let termination_check = self.encode_recursive_call_termination(
+                                    def_id,
+                                    &encoded_args,
+                                    term.source_info.span,
+                                )?;
+
                                 let pos = self
                                     .encoder
                                     .error_manager()
@@ -947,6 +1101,25 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                                 );
                                 let mut state = states[&target_block].clone();
                                 state.substitute_value(&lhs_value, encoded_rhs);
+
+                                // A recursive call that does not decrease the termination
+                                // measure must be unreachable.
+                                if let Some(guard) = termination_check {
+                                    let pos = self.encoder.error_manager().register(
+                                        term.source_info.span,
+                                        ErrorCtxt::TerminationMeasureDecrease,
+                                    );
+                                    let failure_result = unreachable_expr(pos)
+                                        .with_span(term.source_info.span)?;
+                                    for expr in state.exprs_mut() {
+                                        let base = mem::replace(expr, true.into());
+                                        *expr = vir::Expr::ite(
+                                            guard.clone(),
+                                            base,
+                                            failure_result.clone(),
+                                        );
+                                    }
+                                }
                                 state
                             }
                         }
@@ -1089,6 +1262,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                     ty::TyKind::Bool
                     | ty::TyKind::Int(..)
                     | ty::TyKind::Uint(..)
+                    | ty::TyKind::Float(..)
                     | ty::TyKind::RawPtr(..)
                     | ty::TyKind::Ref(..) => Some(
                         self.encoder.encode_value_expr(
@@ -1103,7 +1277,8 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                 };
 
                 match rhs {
-                    &mir::Rvalue::Use(ref operand) => {
+                    &mir::Rvalue::Use(ref operand)
+                    | &mir::Rvalue::Cast(mir::CastKind::Pointer(ty::adjustment::PointerCast::MutToConstPointer), ref operand, _) => {
                         let opt_encoded_rhs = self.encode_operand_place(operand)
                             .with_span(span)?;
 
@@ -1261,7 +1436,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                             op,
                             vir::Expr::snap_app(encoded_left),
                             vir::Expr::snap_app(encoded_right),
-                            ty,
+                            self.mir_encoder.get_operand_ty(left),
                         ).with_span(span)?;
 
                         // Substitute a place of a value with an expression
@@ -1326,7 +1501,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                     &mir::Rvalue::UnaryOp(op, ref operand) => {
                         let encoded_val = self.mir_encoder.encode_operand_expr(operand)
                             .with_span(span)?;
-                        let encoded_value = self.mir_encoder.encode_unary_op_expr(op, encoded_val);
+                        let encoded_value = self.mir_encoder.encode_unary_op_expr(op, encoded_val, ty);
 
                         // Substitute a place of a value with an expression
                         state.substitute_value(&opt_lhs_value_place.unwrap(), encoded_value);
@@ -1486,6 +1661,7 @@ fn is_supported_type_of_pure_expression<'tcx>(tcx: ty::TyCtxt<'tcx>, ty: ty::Ty<
         ty::TyKind::Bool
         | ty::TyKind::Int(_)
         | ty::TyKind::Uint(_)
+        | ty::TyKind::Float(_)
         | ty::TyKind::Char => true,
 
         ty::TyKind::Tuple(elems) => {
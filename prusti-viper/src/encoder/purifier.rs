@@ -213,7 +213,9 @@ fn translate_type(encoder: &Encoder, typ: vir::Type) -> vir::Type {
             let mir_typ = encoder.decode_type_predicate(name).unwrap(); // FIXME: unwrap
             encoder.encode_snapshot_type(mir_typ).unwrap() // FIXME: unwrap
         }
-        vir::Type::Seq(_) => unreachable!(),
+        vir::Type::Seq(_)
+        | vir::Type::Set(_)
+        | vir::Type::Map(..) => unreachable!(),
     }
 }
 
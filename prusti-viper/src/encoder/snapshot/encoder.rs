@@ -238,6 +238,17 @@ impl SnapshotEncoder {
                         expr.clone(),
                         vir::Field::new("val_bool", Type::Bool),
                     ),
+                    ty::TyKind::Float(_) => Expr::field(
+                        expr.clone(),
+                        encoder.encode_value_field(ty)?,
+                    ),
+                    ty::TyKind::Adt(_, _) if encoder.ghost_collection_kind(ty).is_some() => {
+                        let collection_ty = self.encode_type(encoder, ty)?;
+                        Expr::field(
+                            expr.clone(),
+                            encoder.encode_ghost_value_field(ty, collection_ty)?,
+                        )
+                    }
                     ty::TyKind::Tuple(substs) if substs.is_empty() => self.snap_unit(),
                     ty::TyKind::Adt(adt_def, _) if adt_def.variants.is_empty() => self.snap_unit(),
                     ty::TyKind::Adt(adt_def, _) if adt_def.variants.len() == 1 && adt_def.variants[rustc_target::abi::VariantIdx::from_u32(0)].fields.is_empty() => self.snap_unit(),
@@ -253,6 +264,9 @@ impl SnapshotEncoder {
             // handle SnapApp on already patched expressions
             vir::Type::Domain(dom) if dom == UNIT_DOMAIN_NAME => Ok(expr),
             vir::Type::Snapshot(_)
+            | vir::Type::Seq(_)
+            | vir::Type::Set(_)
+            | vir::Type::Map(..)
             | vir::Type::Bool // TODO: restrict to snapshot-produced Bools and Ints
             | vir::Type::Int => Ok(expr),
 
@@ -546,6 +560,24 @@ impl SnapshotEncoder {
             .map(|snapshot| snapshot.get_type())
     }
 
+    /// Encodes the Viper collection type of a ghost collection, whose elements
+    /// are the snapshots of its type arguments.
+    fn encode_ghost_collection_type<'p, 'v: 'p, 'tcx: 'v>(
+        &mut self,
+        encoder: &'p Encoder<'v, 'tcx>,
+        ty: ty::Ty<'tcx>,
+    ) -> EncodingResult<Type> {
+        let kind = encoder.ghost_collection_kind(ty).unwrap();
+        let substs = match ty.kind() {
+            ty::TyKind::Adt(_, substs) => substs,
+            _ => unreachable!(),
+        };
+        let type_args = substs.types()
+            .map(|arg_ty| self.encode_type(encoder, arg_ty))
+            .collect::<Result<_, _>>()?;
+        Ok(kind.encode_type(type_args))
+    }
+
     /// Starts the snapshot encoding for the given type. This function is
     /// a wrapper that takes care of:
     /// * Resolving the given type and stripping it down to the type we will
@@ -559,6 +591,8 @@ impl SnapshotEncoder {
         ty: ty::Ty<'tcx>,
     ) -> EncodingResult<Snapshot> {
         let ty = encoder.resolve_typaram(strip_refs_and_boxes(ty));
+        // strings share the snapshot of byte slices
+        let ty = encoder.string_slice_ty(ty).unwrap_or(ty);
         let predicate_name = encoder.encode_type_predicate_use(ty)?;
 
         // was the snapshot for the type already encoded?
@@ -579,6 +613,10 @@ impl SnapshotEncoder {
             ty::TyKind::Uint(_) => Type::Int,
             ty::TyKind::Char => Type::Int,
             ty::TyKind::Bool => Type::Bool,
+            ty::TyKind::Float(_) => encoder.encode_float_domain_type(ty),
+            ty::TyKind::Adt(_, _) if encoder.ghost_collection_kind(ty).is_some() => {
+                self.encode_ghost_collection_type(encoder, ty)?
+            }
             ty::TyKind::Tuple(substs) if substs.is_empty() => self.snap_unit().get_type().clone(),
             ty::TyKind::Adt(adt_def, _) if adt_def.variants.is_empty() => self.snap_unit().get_type().clone(),
             ty::TyKind::Adt(adt_def, _) if adt_def.variants.len() == 1 && adt_def.variants[rustc_target::abi::VariantIdx::from_u32(0)].fields.is_empty() => self.snap_unit().get_type().clone(),
@@ -632,6 +670,10 @@ impl SnapshotEncoder {
             | ty::TyKind::Uint(_)
             | ty::TyKind::Char => Ok(Snapshot::Primitive(Type::Int)),
             ty::TyKind::Bool => Ok(Snapshot::Primitive(Type::Bool)),
+            ty::TyKind::Float(_) => Ok(Snapshot::Primitive(encoder.encode_float_domain_type(ty))),
+            ty::TyKind::Adt(_, _) if encoder.ghost_collection_kind(ty).is_some() => {
+                Ok(Snapshot::Primitive(self.encode_ghost_collection_type(encoder, ty)?))
+            }
 
             // handle types with no data
             ty::TyKind::Tuple(substs) if substs.is_empty() => Ok(Snapshot::Unit),
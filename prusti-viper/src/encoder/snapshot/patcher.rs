@@ -110,6 +110,12 @@ impl<'v, 'tcx: 'v> FallibleExprFolder for SnapshotPatcher<'v, 'tcx> {
                 match receiver.get_type() {
                     vir::Type::Int if field.name == "val_int" => Ok(*receiver),
                     vir::Type::Bool if field.name == "val_bool" => Ok(*receiver),
+                    // floats
+                    vir::Type::Domain(_) if field.typ == *receiver.get_type() => Ok(*receiver),
+                    // ghost collections
+                    vir::Type::Seq(_)
+                    | vir::Type::Set(_)
+                    | vir::Type::Map(..) if field.typ == *receiver.get_type() => Ok(*receiver),
                     vir::Type::Snapshot(_) => {
                         let res = match field.name.as_str() {
                             "val_ref" => Ok(*receiver),
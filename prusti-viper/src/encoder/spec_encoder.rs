@@ -19,15 +19,16 @@ use prusti_common::config;
 use crate::encoder::SpecFunctionKind;
 use prusti_common::vir;
 use prusti_common::vir::ExprIterator;
+use prusti_interface::environment::ProcedureLoops;
 use prusti_interface::specs::typed;
 use rustc_hir as hir;
 use rustc_hir::def_id::DefId;
 use rustc_middle::mir;
 use rustc_middle::ty;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use rustc_ast::ast;
 use log::{debug, trace};
-use prusti_interface::utils::read_prusti_attr;
+use prusti_interface::utils::{has_prusti_attr, read_prusti_attr};
 
 /// Encode an assertion coming from a specification to a `vir::Expr`.
 ///
@@ -70,6 +71,71 @@ pub fn encode_spec_assertion<'v, 'tcx: 'v>(
     spec_encoder.encode_assertion(assertion)
 }
 
+/// Collect the spec entailments `f |= ..` that `assertion` states unconditionally, i.e. the ones
+/// that are not nested in an implication or a quantifier. Each entailment is returned together
+/// with the type of the closure or function pointer `f` that it talks about.
+pub fn collect_spec_entailments<'a, 'v, 'tcx: 'v>(
+    encoder: &Encoder<'v, 'tcx>,
+    assertion: &'a typed::Assertion<'tcx>,
+) -> Vec<(ty::Ty<'tcx>, &'a typed::Assertion<'tcx>)> {
+    match assertion.kind {
+        box typed::AssertionKind::And(ref assertions) => assertions
+            .iter()
+            .flat_map(|x| collect_spec_entailments(encoder, x))
+            .collect(),
+        box typed::AssertionKind::SpecEntailment { ref closure, .. } => {
+            let mir = encoder.env().mir(closure.expr);
+            let closure_ty = mir.local_decls[(0 as u32).into()].ty;
+            vec![(closure_ty, assertion)]
+        }
+        _ => vec![],
+    }
+}
+
+/// Encode the contract that the spec entailment `entailment` gives to a call of its closure.
+///
+/// The result is a pair with the preconditions of the entailment, which must hold before the
+/// call, and its postconditions, which hold after the call. The arguments of the entailment are
+/// replaced by `call_args` and its result by `call_result`, which must encode values.
+/// `target_args` encode the arguments of the procedure whose precondition contains `entailment`.
+pub fn encode_spec_entailment_call<'v, 'tcx: 'v>(
+    encoder: &Encoder<'v, 'tcx>,
+    entailment: &typed::Assertion<'tcx>,
+    target_args: &[vir::Expr],
+    call_args: &[vir::Expr],
+    call_result: &vir::Expr,
+) -> SpannedEncodingResult<(vir::Expr, vir::Expr)> {
+    let spec_encoder = SpecEncoder::new(encoder, "", target_args, None, false, None);
+    spec_encoder.encode_spec_entailment_call(entailment, call_args, call_result)
+}
+
+/// Encode the spec entailment `entailment` of a function pointer that is known to point to the
+/// function `fn_def_id`, i.e. check the entailment against the specification of `fn_def_id`.
+/// `target_args` encode the arguments of the procedure whose precondition contains `entailment`.
+pub fn encode_spec_entailment_check<'v, 'tcx: 'v>(
+    encoder: &Encoder<'v, 'tcx>,
+    entailment: &typed::Assertion<'tcx>,
+    fn_def_id: DefId,
+    target_args: &[vir::Expr],
+) -> SpannedEncodingResult<vir::Expr> {
+    let spec_encoder = SpecEncoder::new(encoder, "", target_args, None, false, None);
+    match entailment.kind {
+        box typed::AssertionKind::SpecEntailment {
+            ref closure,
+            arg_binders: ref vars,
+            ref pres,
+            ref posts,
+        } => spec_encoder.encode_spec_entailment_of(
+            fn_def_id,
+            encoder.env().tcx().def_span(closure.expr),
+            vars,
+            pres,
+            posts,
+        ),
+        _ => unreachable!("not a spec entailment: {:?}", entailment),
+    }
+}
+
 struct SpecEncoder<'p, 'v: 'p, 'tcx: 'v> {
     encoder: &'p Encoder<'v, 'tcx>,
     /// The label to encode `old(..)` expressions
@@ -228,9 +294,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> SpecEncoder<'p, 'v, 'tcx> {
                 ref pres,
                 ref posts,
             } => {
-                // TODO: refactor, simplify, or extract into a function
-                let tcx = self.encoder.env().tcx();
-                let mir = self.encoder.env().local_mir(closure.expr);
+                let mir = self.encoder.env().mir(closure.expr);
                 let result = &mir.local_decls[(0 as u32).into()];
                 let ty = result.ty;
                 if let Some(ty_repl) = self.encoder.current_tymap().get(ty) {
@@ -239,103 +303,13 @@ impl<'p, 'v: 'p, 'tcx: 'v> SpecEncoder<'p, 'v, 'tcx> {
                     match ty_repl.kind() {
                         ty::TyKind::Closure(def_id, _substs)
                         | ty::TyKind::FnDef(def_id, _substs) => {
-                            let encoded_pres = pres.iter()
-                                .map(|x| self.encode_assertion(x))
-                                .collect::<Result<Vec<vir::Expr>, _>>()?
-                                .into_iter()
-                                .conjoin();
-
-                            // encode_quantifier_arg() above only works for integers.
-                            // Therefore, for the time being, check that we're working with integers:
-                            vars.args.iter().for_each(|(_arg, arg_ty)| {
-                                match arg_ty.kind() {
-                                    ty::TyKind::Int(..) | ty::TyKind::Uint(..) => {}
-                                    _ => { unimplemented!("Only integers are currently supported as closure arguments."); }
-                                }
-                            });
-                            match vars.result.1.kind() {
-                                ty::TyKind::Int(..) | ty::TyKind::Uint(..) => {}
-                                _ => { unimplemented!("Only integers are currently supported as closure return types."); }
-                            }
-
-                            let sf_pre_name = self.encoder.encode_spec_func_name(*def_id, SpecFunctionKind::Pre);
-                            let qvars_pre: Vec<_> = vars.args
-                                .iter()
-                                .map(|(arg, arg_ty)| self.encode_quantifier_arg(*arg, arg_ty, &format!("{}_{}", vars.spec_id, vars.pre_id)))
-                                .collect();
-                            let pre_conjunct = vir::Expr::forall(
-                                qvars_pre.clone(),
-                                vec![], // TODO: encode triggers
-                                vir::Expr::implies(
-                                    encoded_pres.clone(),
-                                    vir::Expr::FuncApp(
-                                        sf_pre_name,
-                                        qvars_pre.iter()
-                                            .map(|x| vir::Expr::Local(x.clone(), vir::Position::default()))
-                                            .collect(),
-                                        (0 .. vars.args.len())
-                                            .map(|i| vir::LocalVar::new(format!("_{}", i), vir::Type::Int))
-                                            .collect(),
-                                        vir::Type::Bool,
-                                        vir::Position::default()
-                                    )
-                                )
-                            );
-
-
-                            let sf_post_name = self.encoder.encode_spec_func_name(*def_id, SpecFunctionKind::Post);
-
-                            // The result is modeled as the final argument to the post() spec function
-                            let result_var = mir::Local::from_usize(vars.args.len() + 2);
-
-                            // The set of quantified variables
-                            let qvars_post: Vec<_> = vars.args
-                                .iter()
-                                .map(|(arg, arg_ty)|
-                                     self.encode_quantifier_arg(
-                                         *arg, arg_ty,
-                                         &format!("{}_{}", vars.spec_id, vars.post_id)))
-                                .chain(std::iter::once(
-                                    self.encode_quantifier_arg(
-                                        result_var, tcx.mk_ty(ty::TyKind::Int(ty::IntTy::I32)),
-                                        &format!("{}_{}", vars.spec_id, vars.post_id))))
-                                .collect();
-
-                            let post_conjunct = vir::Expr::forall(
-                                qvars_post.clone(),
-                                vec![], // TODO: encode triggers
-                                vir::Expr::implies(
-                                    // The quantified variables in the precondition have been encoded using
-                                    // different IDs (vars.pre_id vs. vars.post_id), so we need to fix them
-                                    (0 .. qvars_pre.len())
-                                        .fold(encoded_pres, |e, i| {
-                                            e.replace_place(&vir::Expr::Local(qvars_pre[i].clone(), vir::Position::default()),
-                                                            &vir::Expr::Local(qvars_post[i].clone(), vir::Position::default()))
-                                        }),
-                                    vir::Expr::implies(
-                                        vir::Expr::FuncApp(
-                                            sf_post_name,
-                                            qvars_post.iter()
-                                                .map(|x| vir::Expr::Local(x.clone(), vir::Position::default()))
-                                                .collect(),
-                                            (0 ..= vars.args.len())
-                                                .map(|i| vir::LocalVar::new(format!("_{}", i), vir::Type::Int))
-                                                .collect(),
-                                            vir::Type::Bool,
-                                            vir::Position::default()
-                                        ),
-                                        posts.iter()
-                                            .map(|x| self.encode_assertion(x))
-                                            .collect::<Result<Vec<vir::Expr>, _>>()?
-                                            .into_iter()
-                                            .conjoin()
-                                    )
-                                )
-                            );
-
-                            vec![pre_conjunct, post_conjunct]
-                                .into_iter()
-                                .conjoin()
+                            self.encode_spec_entailment_of(
+                                *def_id,
+                                self.encoder.env().tcx().def_span(closure.expr),
+                                vars,
+                                pres,
+                                posts,
+                            )?
                         }
                         _ => unreachable!()
                     }
@@ -347,6 +321,163 @@ impl<'p, 'v: 'p, 'tcx: 'v> SpecEncoder<'p, 'v, 'tcx> {
         })
     }
 
+    /// Encode the spec entailment `|= |vars| [requires(pres), ensures(posts)]` on the closure or
+    /// function `def_id`: its preconditions imply the ones of `def_id` and, together with the
+    /// postconditions of `def_id`, they imply its postconditions.
+    fn encode_spec_entailment_of(
+        &self,
+        def_id: DefId,
+        span: rustc_span::Span,
+        vars: &typed::SpecEntailmentVars<'tcx>,
+        pres: &[typed::Assertion<'tcx>],
+        posts: &[typed::Assertion<'tcx>],
+    ) -> SpannedEncodingResult<vir::Expr> {
+        let encoded_pres = pres.iter()
+            .map(|x| self.encode_assertion(x))
+            .collect::<Result<Vec<vir::Expr>, _>>()?
+            .into_iter()
+            .conjoin();
+
+        // encode_quantifier_arg() above only works for integers, and so do the spec functions.
+        check_integer_signature(vars, span)?;
+
+        let sf_pre_name = self.encoder.encode_spec_func_name(def_id, SpecFunctionKind::Pre);
+        let qvars_pre: Vec<_> = vars.args
+            .iter()
+            .map(|(arg, arg_ty)| self.encode_quantifier_arg(*arg, arg_ty, &format!("{}_{}", vars.spec_id, vars.pre_id)))
+            .collect();
+        let pre_conjunct = vir::Expr::forall(
+            qvars_pre.clone(),
+            vec![], // TODO: encode triggers
+            vir::Expr::implies(
+                encoded_pres.clone(),
+                vir::Expr::FuncApp(
+                    sf_pre_name,
+                    qvars_pre.iter()
+                        .map(|x| vir::Expr::Local(x.clone(), vir::Position::default()))
+                        .collect(),
+                    (0 .. vars.args.len())
+                        .map(|i| vir::LocalVar::new(format!("_{}", i), vir::Type::Int))
+                        .collect(),
+                    vir::Type::Bool,
+                    vir::Position::default()
+                )
+            )
+        );
+
+
+        let sf_post_name = self.encoder.encode_spec_func_name(def_id, SpecFunctionKind::Post);
+
+        // The result is modeled as the final argument to the post() spec function
+        let result_var = mir::Local::from_usize(vars.args.len() + 2);
+
+        // The set of quantified variables
+        let qvars_post: Vec<_> = vars.args
+            .iter()
+            .map(|(arg, arg_ty)|
+                 self.encode_quantifier_arg(
+                     *arg, arg_ty,
+                     &format!("{}_{}", vars.spec_id, vars.post_id)))
+            .chain(std::iter::once(
+                self.encode_quantifier_arg(
+                    result_var, vars.result.1,
+                    &format!("{}_{}", vars.spec_id, vars.post_id))))
+            .collect();
+
+        let post_conjunct = vir::Expr::forall(
+            qvars_post.clone(),
+            vec![], // TODO: encode triggers
+            vir::Expr::implies(
+                // The quantified variables in the precondition have been encoded using
+                // different IDs (vars.pre_id vs. vars.post_id), so we need to fix them
+                (0 .. qvars_pre.len())
+                    .fold(encoded_pres, |e, i| {
+                        e.replace_place(&vir::Expr::Local(qvars_pre[i].clone(), vir::Position::default()),
+                                        &vir::Expr::Local(qvars_post[i].clone(), vir::Position::default()))
+                    }),
+                vir::Expr::implies(
+                    vir::Expr::FuncApp(
+                        sf_post_name,
+                        qvars_post.iter()
+                            .map(|x| vir::Expr::Local(x.clone(), vir::Position::default()))
+                            .collect(),
+                        (0 ..= vars.args.len())
+                            .map(|i| vir::LocalVar::new(format!("_{}", i), vir::Type::Int))
+                            .collect(),
+                        vir::Type::Bool,
+                        vir::Position::default()
+                    ),
+                    posts.iter()
+                        .map(|x| self.encode_assertion(x))
+                        .collect::<Result<Vec<vir::Expr>, _>>()?
+                        .into_iter()
+                        .conjoin()
+                )
+            )
+        );
+
+        Ok(vec![pre_conjunct, post_conjunct]
+            .into_iter()
+            .conjoin())
+    }
+
+    /// See `encode_spec_entailment_call` at the top of this file.
+    fn encode_spec_entailment_call(
+        &self,
+        entailment: &typed::Assertion<'tcx>,
+        call_args: &[vir::Expr],
+        call_result: &vir::Expr,
+    ) -> SpannedEncodingResult<(vir::Expr, vir::Expr)> {
+        let (closure, vars, pres, posts) = match entailment.kind {
+            box typed::AssertionKind::SpecEntailment {
+                ref closure,
+                arg_binders: ref vars,
+                ref pres,
+                ref posts,
+            } => (closure, vars, pres, posts),
+            _ => unreachable!("not a spec entailment: {:?}", entailment),
+        };
+        assert_eq!(vars.args.len(), call_args.len());
+
+        // As in the encoding of the entailment itself, only integers are supported.
+        check_integer_signature(vars, self.encoder.env().tcx().def_span(closure.expr))?;
+
+        let pre_id = format!("{}_{}", vars.spec_id, vars.pre_id);
+        let post_id = format!("{}_{}", vars.spec_id, vars.post_id);
+        let mut pre_replacements = vec![];
+        let mut post_replacements = vec![];
+        for ((arg, arg_ty), call_arg) in vars.args.iter().zip(call_args) {
+            pre_replacements.push((
+                self.encode_quantifier_arg(*arg, arg_ty, &pre_id).into(),
+                call_arg.clone(),
+            ));
+            post_replacements.push((
+                self.encode_quantifier_arg(*arg, arg_ty, &post_id).into(),
+                call_arg.clone(),
+            ));
+        }
+        let (result, result_ty) = vars.result;
+        post_replacements.push((
+            self.encode_quantifier_arg(result, result_ty, &post_id).into(),
+            call_result.clone(),
+        ));
+
+        let encoded_pres = pres.iter()
+            .map(|x| self.encode_assertion(x))
+            .collect::<Result<Vec<vir::Expr>, _>>()?
+            .into_iter()
+            .conjoin();
+        let encoded_posts = posts.iter()
+            .map(|x| self.encode_assertion(x))
+            .collect::<Result<Vec<vir::Expr>, _>>()?
+            .into_iter()
+            .conjoin();
+        Ok((
+            encoded_pres.replace_multiple_places(&pre_replacements),
+            encoded_posts.replace_multiple_places(&post_replacements),
+        ))
+    }
+
     /// Encode a universal or existential quantifer. Encodes type bounds of
     /// quantified variables as:
     /// * premises in a universal quantifier, or
@@ -415,6 +546,8 @@ impl<'p, 'v: 'p, 'tcx: 'v> SpecEncoder<'p, 'v, 'tcx> {
     /// * Usages of the closure's captured state will be translated to the captured place.
     /// * Closure arguments will be treated as quantified variables and will be translated using
     ///   the `self.encode_quantifier_arg(..)` method.
+    /// * The argument of a loop invariant will be translated to the iterator of the enclosing
+    ///   `for` loop.
     ///
     /// The result is a tuple with:
     /// * the translated expression,
@@ -426,7 +559,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> SpecEncoder<'p, 'v, 'tcx> {
         inner_def_id: DefId,
     ) -> SpannedEncodingResult<(vir::Expr, DefId, mir::Location)> {
         debug!("translate_expr_to_closure_def_site {} {:?}", expr, inner_def_id);
-        let inner_mir = self.encoder.env().local_mir(inner_def_id.expect_local());
+        let inner_mir = self.encoder.env().mir(inner_def_id);
         let inner_mir_encoder = MirEncoder::new(self.encoder, &inner_mir, inner_def_id);
         let inner_attrs = self.encoder.env().tcx().get_attrs(inner_def_id);
 
@@ -441,7 +574,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> SpecEncoder<'p, 'v, 'tcx> {
         ) = opt_instantiation.expect(
             &format!("cannot find definition site for closure {:?}", inner_def_id)
         );
-        let outer_mir = self.encoder.env().local_mir(outer_def_id.expect_local());
+        let outer_mir = self.encoder.env().mir(outer_def_id);
         let outer_mir_encoder = MirEncoder::new(self.encoder, &outer_mir, outer_def_id);
         let outer_span = outer_mir_encoder.get_span_of_location(outer_location);
         trace!("Replacing variables of {:?} captured from {:?}", inner_def_id, outer_def_id);
@@ -529,6 +662,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> SpecEncoder<'p, 'v, 'tcx> {
                 let encoded_arg_value = match local_arg.ty.kind() {
                     ty::TyKind::Uint(_) |
                     ty::TyKind::Int(_) |
+                    ty::TyKind::Float(_) |
                     ty::TyKind::Bool |
                     ty::TyKind::Char => {
                         let span = inner_mir_encoder.get_local_span(local_arg_index);
@@ -549,6 +683,42 @@ impl<'p, 'v: 'p, 'tcx: 'v> SpecEncoder<'p, 'v, 'tcx> {
             }
         }
 
+        // Replacement 3: bind the argument of a loop invariant to the iterator of its `for` loop
+        if has_prusti_attr(inner_attrs, "loop_body_invariant_spec") && inner_mir.arg_count == 2 {
+            let tcx = self.encoder.env().tcx();
+            let local_arg_index = inner_mir.args_iter().nth(1).unwrap();
+            let local_arg_ty = inner_mir.local_decls[local_arg_index].ty;
+            let procedure = self.encoder.env().get_procedure(outer_def_id);
+            let loops = procedure.loop_info();
+            let iter_place = find_for_loop_iterator(tcx, &outer_mir, loops, outer_location)
+                .ok_or_else(|| SpannedEncodingError::incorrect(
+                    "a loop invariant with an iterator argument must be in the body of a `for` loop",
+                    outer_span,
+                ))?;
+            let iter_ty = iter_place.ty(&*outer_mir, tcx).ty;
+            if tcx.erase_regions(iter_ty) != tcx.erase_regions(local_arg_ty) {
+                return Err(SpannedEncodingError::incorrect(
+                    format!(
+                        "the iterator argument of the loop invariant has type {:?}, but the \
+                        `for` loop consumes an iterator of type {:?}",
+                        local_arg_ty,
+                        iter_ty,
+                    ),
+                    outer_span,
+                ));
+            }
+            let encoded_arg = inner_mir_encoder.encode_local(local_arg_index)?;
+            let (encoded_iter, _, _) = outer_mir_encoder.encode_place(&iter_place)
+                .with_span(outer_span)?;
+            let encoded_iter = encoded_iter.try_into_expr().with_span(outer_span)?;
+            trace!(
+                "Place {} will be renamed to {} because it is the iterator of the loop",
+                encoded_arg,
+                encoded_iter
+            );
+            replacements.push((encoded_arg.into(), encoded_iter));
+        }
+
         // Do the replacements
         let outer_expr = expr.replace_multiple_places(&replacements);
         debug!(
@@ -570,7 +740,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> SpecEncoder<'p, 'v, 'tcx> {
         target_location: mir::BasicBlock,
     ) -> SpannedEncodingResult<vir::Expr> {
         debug!("translate_expr_to_state {} {:?} {:?}", expr, def_id, expr_location);
-        let mir = self.encoder.env().local_mir(def_id.expect_local());
+        let mir = self.encoder.env().mir(def_id);
 
         // Translate an intermediate state to the state at the beginning of the method
         let state = MultiExprBackwardInterpreterState::new_single(
@@ -604,7 +774,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> SpecEncoder<'p, 'v, 'tcx> {
     {
         debug!("encode_expression {:?}", assertion_expr);
 
-        let mut curr_def_id = assertion_expr.expr.to_def_id();
+        let mut curr_def_id = assertion_expr.expr;
         let mut curr_expr = self.encoder.encode_pure_expression(curr_def_id)?;
 
         loop {
@@ -637,7 +807,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> SpecEncoder<'p, 'v, 'tcx> {
 
         // At this point `curr_def_id` should be either a SPEC item (when encoding a contract) or
         // the method being verified (when encoding a loop invariant).
-        let mir = self.encoder.env().local_mir(curr_def_id.expect_local());
+        let mir = self.encoder.env().mir(curr_def_id);
         let mir_encoder = MirEncoder::new(self.encoder, &mir, curr_def_id);
 
         // Replacements to use the provided `target_args` and `target_return`
@@ -709,6 +879,77 @@ impl<'p, 'v: 'p, 'tcx: 'v> SpecEncoder<'p, 'v, 'tcx> {
     }
 }
 
+/// Find the iterator consumed by the innermost `for` loop whose body contains `location`.
+///
+/// A `for pat in iter { .. }` loop is desugared to
+/// `let mut it = iter; loop { match Iterator::next(&mut it) { None => break, Some(pat) => .. } }`,
+/// so the head of the loop falls through to the call of `Iterator::next`, whose result is
+/// matched right after the call. The iterator is the place whose mutable borrow is passed to
+/// that call. Returns `None` if the innermost loop does not have this structure.
+fn find_for_loop_iterator<'tcx>(
+    tcx: ty::TyCtxt<'tcx>,
+    mir: &mir::Body<'tcx>,
+    loops: &ProcedureLoops,
+    location: mir::Location,
+) -> Option<mir::Place<'tcx>> {
+    // Follow the unconditional edges from the loop head to the call of `next`.
+    let mut bb = loops.get_loop_head(location.block)?;
+    let mut visited = HashSet::new();
+    let (func, args, destination) = loop {
+        if !visited.insert(bb) {
+            return None;
+        }
+        match mir[bb].terminator().kind {
+            mir::TerminatorKind::Goto { target } |
+            mir::TerminatorKind::FalseUnwind { real_target: target, .. } => bb = target,
+            mir::TerminatorKind::Call { ref func, ref args, ref destination, .. } => {
+                break (func, args, destination.as_ref()?);
+            }
+            _ => return None,
+        }
+    };
+    match func.ty(mir, tcx).kind() {
+        ty::TyKind::FnDef(def_id, _) => {
+            let func_name = tcx.def_path_str(*def_id);
+            if func_name != "std::iter::Iterator::next" && func_name != "core::iter::Iterator::next" {
+                return None;
+            }
+        }
+        _ => return None,
+    }
+
+    // The result of `next` must be matched right after the call.
+    let &(result, match_bb) = destination;
+    let matches_result = mir[match_bb].statements.iter().any(|stmt| matches!(
+        stmt.kind,
+        mir::StatementKind::Assign(box (_, mir::Rvalue::Discriminant(place))) if place == result
+    ));
+    if !matches_result
+        || !matches!(mir[match_bb].terminator().kind, mir::TerminatorKind::SwitchInt { .. })
+    {
+        return None;
+    }
+
+    // Resolve the argument `&mut (*tmp)`, where `tmp = &mut it`, to the iterator `it`.
+    let mut place = args.get(0)?.place()?;
+    for stmt in mir[bb].statements.iter().rev() {
+        if let mir::StatementKind::Assign(box (
+            lhs,
+            mir::Rvalue::Ref(_, mir::BorrowKind::Mut { .. }, borrowed),
+        )) = stmt.kind {
+            if lhs == place {
+                match borrowed.as_ref() {
+                    mir::PlaceRef { local, projection: [mir::ProjectionElem::Deref] } => {
+                        place = local.into();
+                    }
+                    _ => return Some(borrowed),
+                }
+            }
+        }
+    }
+    None
+}
+
 struct StraightLineBackwardInterpreter<'p, 'v: 'p, 'tcx: 'v> {
     interpreter: PureFunctionBackwardInterpreter<'p, 'v, 'tcx>,
 }
@@ -771,3 +1012,21 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
         Ok(())
     }
 }
+
+/// Spec entailments are only supported on closures and function pointers whose arguments and
+/// result are integers, because their arguments are encoded as quantified integer variables.
+fn check_integer_signature(
+    vars: &typed::SpecEntailmentVars<'_>,
+    span: rustc_span::Span,
+) -> SpannedEncodingResult<()> {
+    let is_integer = |ty: ty::Ty| matches!(ty.kind(), ty::TyKind::Int(..) | ty::TyKind::Uint(..));
+    if vars.args.iter().all(|(_, arg_ty)| is_integer(arg_ty)) && is_integer(vars.result.1) {
+        Ok(())
+    } else {
+        Err(SpannedEncodingError::unsupported(
+            "spec entailments are only supported on closures and function pointers with integer \
+            arguments and results",
+            span,
+        ))
+    }
+}
@@ -8,13 +8,13 @@ use std::collections::{HashMap, HashSet};
 use log::{debug, trace};
 use rustc_hir as hir;
 use rustc_middle::mir;
-use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_hir::def_id::DefId;
 use rustc_middle::ty;
 use prusti_interface::environment::Environment;
 
 /// Structure to collect closure instantiations annotated with `prusti::spec_only`.
 pub struct SpecsClosuresCollector<'tcx> {
-    visited: HashSet<LocalDefId>,
+    visited: HashSet<DefId>,
     /// For each instantiation of each closure: DefId, location, operands and types of operands.
     instantiations: HashMap<
         DefId,
@@ -41,13 +41,14 @@ impl<'tcx> SpecsClosuresCollector<'tcx> {
         let tcx = env.tcx();
         for &def_id in tcx.mir_keys(()).iter() {
             if env.has_prusti_attribute(def_id.to_def_id(), "spec_only") {
-                self.collect(env, def_id);
+                self.collect(env, def_id.to_def_id());
             }
         }
     }
 
-    /// Collect instantiations of `prusti::spec_only` closures from a given procedure.
-    pub fn collect(&mut self, env: &Environment<'tcx>, def_id: LocalDefId) {
+    /// Collect instantiations of `prusti::spec_only` closures from a given procedure, which
+    /// may belong to another crate.
+    pub fn collect(&mut self, env: &Environment<'tcx>, def_id: DefId) {
         debug!("Collecting closure instantiations in {:?}", def_id);
         // Avoid visiting the same procedure multiple times
         if self.visited.contains(&def_id) {
@@ -57,7 +58,11 @@ impl<'tcx> SpecsClosuresCollector<'tcx> {
             self.visited.insert(def_id);
         }
         let tcx = env.tcx();
-        let mir = env.local_mir(def_id);
+        let mir = env.mir(def_id);
+        if !def_id.is_local() {
+            self.collect_deaggregated(env, def_id, &mir);
+            return;
+        }
         for (bb_index, bb_data) in mir.basic_blocks().iter_enumerated() {
             for (stmt_index, stmt) in bb_data.statements.iter().enumerate() {
                 if let mir::StatementKind::Assign(
@@ -80,7 +85,7 @@ impl<'tcx> SpecsClosuresCollector<'tcx> {
                     let instantiations =
                         self.instantiations.entry(cl_def_id).or_insert(vec![]);
                     instantiations.push((
-                        def_id.to_def_id(),
+                        def_id,
                         mir::Location {
                             block: bb_index,
                             statement_index: stmt_index,
@@ -93,6 +98,68 @@ impl<'tcx> SpecsClosuresCollector<'tcx> {
         }
     }
 
+    /// Collect instantiations of `prusti::spec_only` closures from the MIR of another crate.
+    /// That MIR has been deaggregated by the compiler: instead of being built by an
+    /// `Rvalue::Aggregate`, a closure is built by assigning each captured operand to a field of
+    /// the local that holds the closure.
+    fn collect_deaggregated(
+        &mut self,
+        env: &Environment<'tcx>,
+        def_id: DefId,
+        mir: &mir::Body<'tcx>,
+    ) {
+        let tcx = env.tcx();
+        for (local, local_decl) in mir.local_decls.iter_enumerated() {
+            let cl_def_id = match local_decl.ty.kind() {
+                ty::TyKind::Closure(cl_def_id, _) => *cl_def_id,
+                _ => continue,
+            };
+            // Skip closures that are not annotated with `prusti::spec_only`.
+            if !env.has_prusti_attribute(cl_def_id, "spec_only") {
+                continue;
+            }
+            let mut fields = vec![];
+            let mut fields_location = None;
+            let mut storage_location = None;
+            for (bb_index, bb_data) in mir.basic_blocks().iter_enumerated() {
+                for (stmt_index, stmt) in bb_data.statements.iter().enumerate() {
+                    let location = mir::Location {
+                        block: bb_index,
+                        statement_index: stmt_index,
+                    };
+                    match stmt.kind {
+                        mir::StatementKind::Assign(
+                            box (place, mir::Rvalue::Use(ref operand))
+                        ) if place.local == local => {
+                            if let &[mir::ProjectionElem::Field(field, _)] = &place.projection[..] {
+                                fields_location.get_or_insert(location);
+                                fields.push((field.index(), operand.clone()));
+                            }
+                        }
+                        mir::StatementKind::StorageLive(storage_local) if storage_local == local => {
+                            storage_location.get_or_insert(location);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            // The operands are evaluated before the first field is assigned. A closure that
+            // captures nothing has no fields, so any location will do.
+            let location = fields_location
+                .or(storage_location)
+                .unwrap_or(mir::Location::START);
+            trace!("Found deaggregated closure instantiation of {:?} at {:?}", cl_def_id, location);
+            fields.sort_by_key(|(index, _)| *index);
+            let operands: Vec<_> = fields.into_iter().map(|(_, operand)| operand).collect();
+            let operand_tys = operands.iter().map(
+                |operand| operand.ty(mir, tcx)
+            ).collect();
+            let instantiations =
+                self.instantiations.entry(cl_def_id).or_insert(vec![]);
+            instantiations.push((def_id, location, operands, operand_tys));
+        }
+    }
+
     fn get_instantiations(&self, closure_def_id: DefId) -> Option<&[(
         DefId,
         mir::Location,
@@ -12,7 +12,7 @@ use prusti_common::{
     config,
     vir,
     vir_local,
-    vir::{ExprIterator, ExprFolder},
+    vir::ExprIterator,
 };
 // use prusti_interface::specifications::*;
 // use rustc::middle::const_val::ConstVal;
@@ -24,12 +24,12 @@ use rustc_middle::ty::layout::IntegerExt;
 use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
-use prusti_interface::specs::typed;
 use rustc_attr::IntType::SignedInt;
 use rustc_target::abi::Integer;
 use log::{debug, trace};
 use crate::encoder::errors::{EncodingError, EncodingResult};
 use crate::encoder::builtin_encoder::BuiltinFunctionKind;
+use crate::encoder::spec_encoder::encode_spec_assertion;
 use std::convert::TryInto;
 
 pub struct TypeEncoder<'p, 'v: 'p, 'tcx: 'v> {
@@ -39,6 +39,9 @@ pub struct TypeEncoder<'p, 'v: 'p, 'tcx: 'v> {
 
 impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
     pub fn new(encoder: &'p Encoder<'v, 'tcx>, ty: ty::Ty<'tcx>) -> Self {
+        let ty = encoder.normalize_projections(ty);
+        // strings are encoded as slices of bytes
+        let ty = encoder.string_slice_ty(ty).unwrap_or(ty);
         TypeEncoder { encoder, ty }
     }
 
@@ -49,11 +52,16 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
             | ty::TyKind::Int(_)
             | ty::TyKind::Uint(_)
             | ty::TyKind::Char
+            | ty::TyKind::Float(_)
             | ty::TyKind::Ref(_, _, _)
+            | ty::TyKind::RawPtr(_)
             | ty::TyKind::Adt(_, _)
             | ty::TyKind::Tuple(_)
             | ty::TyKind::Never
-            | ty::TyKind::Param(_) => true,
+            | ty::TyKind::Param(_)
+            | ty::TyKind::Projection(_)
+            | ty::TyKind::FnPtr(_)
+            | ty::TyKind::Dynamic(..) => true,
             _ => false,
         }
     }
@@ -116,39 +124,42 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
                 vir::Field::new("val_int", vir::Type::Int)
             }
 
-            ty::TyKind::Ref(_, ref ty, _) => {
+            ty::TyKind::Float(float_ty) => {
+                vir::Field::new(
+                    format!("val_{}", float_ty.name_str()),
+                    self.encoder.encode_float_domain_type(self.ty),
+                )
+            }
+
+            ty::TyKind::RawPtr(ty::TypeAndMut { ref ty, .. })
+            | ty::TyKind::Ref(_, ref ty, _) => {
                 let type_name = self.encoder.encode_type_predicate_use(ty)?;
                 vir::Field::new("val_ref", vir::Type::TypedRef(type_name))
             }
 
+            // Ghost collections hold their Viper value like primitive types.
+            ty::TyKind::Adt(_, _) if self.encoder.ghost_collection_kind(self.ty).is_some() => {
+                let collection_ty = self.encoder.encode_snapshot_type(self.ty)?;
+                self.encoder.encode_ghost_value_field(self.ty, collection_ty)?
+            }
+
             // For composed data structures, we typically use a snapshot rather than a field.
             // To unify how parameters are passed to functions, we treat them like a reference.
             ty::TyKind::Adt(_, _)
             | ty::TyKind::Tuple(_)
             | ty::TyKind::Closure(_, _)
-            | ty::TyKind::FnDef(_, _) => {
+            | ty::TyKind::FnDef(_, _)
+            | ty::TyKind::FnPtr(_) => {
                 let type_name = self.encoder.encode_type_predicate_use(self.ty)?;
                 vir::Field::new("val_ref", vir::Type::TypedRef(type_name))
             }
 
-            ty::TyKind::RawPtr(ty::TypeAndMut { .. }) => {
-                return Err(EncodingError::unsupported(
-                    "raw pointers are not supported"
-                ));
-            }
-
             ty::TyKind::Array(..) => {
                 return Err(EncodingError::internal(
                     "TypeEncoder::encode_value_field should not be called for arrays"
                 ));
             }
 
-            ty::TyKind::Float(_) => {
-                return Err(EncodingError::unsupported(
-                    "float type is not supported"
-                ));
-            }
-
             ty::TyKind::Foreign(_) => {
                 return Err(EncodingError::unsupported(
                     "foreign function interface is not supported"
@@ -167,12 +178,6 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
                 ));
             }
 
-            ty::TyKind::FnPtr(_) => {
-                return Err(EncodingError::unsupported(
-                    "function pointers are not supported"
-                ));
-            }
-
             ty::TyKind::Dynamic(..) => {
                 return Err(EncodingError::unsupported(
                     "trait objects are not supported"
@@ -194,7 +199,7 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
 
             ty::TyKind::Projection(_) => {
                 return Err(EncodingError::unsupported(
-                    "projections are not supported"
+                    "associated types in arrays are not supported"
                 ));
             }
 
@@ -236,7 +241,7 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
         })
     }
 
-    fn get_integer_bounds(&self) -> Option<(vir::Expr, vir::Expr)> {
+    pub fn get_integer_bounds(&self) -> Option<(vir::Expr, vir::Expr)> {
         match self.ty.kind() {
             ty::TyKind::Int(int_ty) => {
                 let bounds = match int_ty {
@@ -263,7 +268,7 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
             ty::TyKind::Char => {
                 Some((0.into(), std::char::MAX.into()))
             }
-            ty::TyKind::Bool | ty::TyKind::Ref(_, _, _) => None,
+            ty::TyKind::Bool | ty::TyKind::Float(_) | ty::TyKind::Ref(_, _, _) => None,
             ref x => unreachable!("{:?}", x),
         }
     }
@@ -285,13 +290,22 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
         let typ = vir::Type::TypedRef(predicate_name.clone());
 
         Ok(match self.ty.kind() {
-            ty::TyKind::Bool => vec![vir::Predicate::new_primitive_value(
+            ty::TyKind::Bool | ty::TyKind::Float(_) => vec![vir::Predicate::new_primitive_value(
                 typ,
                 self.encoder.encode_value_field(self.ty)?,
                 None,
                 false,
             )],
 
+            ty::TyKind::Adt(_, _) if self.encoder.ghost_collection_kind(self.ty).is_some() => {
+                vec![vir::Predicate::new_primitive_value(
+                    typ,
+                    self.encoder.encode_value_field(self.ty)?,
+                    None,
+                    false,
+                )]
+            }
+
             ty::TyKind::Int(_) | ty::TyKind::Uint(_) | ty::TyKind::Char => {
                 let bounds = if config::check_overflows() {
                     self.get_integer_bounds()
@@ -318,6 +332,17 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
                 )]
             },
 
+            ty::TyKind::RawPtr(_) => {
+                // A raw pointer is only an address, the permission to the pointee is stated
+                // separately with `own`.
+                vec![vir::Predicate::new_primitive_value(
+                    typ,
+                    self.encoder.encode_value_field(self.ty)?,
+                    None,
+                    false,
+                )]
+            }
+
             ty::TyKind::Tuple(elems) => {
                 let fields = elems
                     .iter()
@@ -424,6 +449,22 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
                 vec![vir::Predicate::new_abstract(typ)]
             }
 
+            ty::TyKind::Projection(_) => {
+                // projections that could not be normalized are opaque, like type parameters
+                vec![vir::Predicate::new_abstract(typ)]
+            }
+
+            ty::TyKind::Dynamic(..) => {
+                // like type parameters, trait objects are encoded as *abstract* predicates
+                vec![vir::Predicate::new_abstract(typ)]
+            }
+
+            ty::TyKind::FnPtr(..) => {
+                // the function that is pointed to is not known, so function pointers are
+                // encoded as *abstract* predicates and specified with spec entailments
+                vec![vir::Predicate::new_abstract(typ)]
+            }
+
             ty::TyKind::Closure(_def_id, internal_substs) => {
                 let closure_substs = internal_substs.as_closure();
                 match closure_substs.tupled_upvars_ty().kind() {
@@ -505,9 +546,17 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
 
             ty::TyKind::Char => "char".to_string(),
 
+            ty::TyKind::Float(ty::FloatTy::F32) => "f32".to_string(),
+            ty::TyKind::Float(ty::FloatTy::F64) => "f64".to_string(),
+
             ty::TyKind::RawPtr(ty::TypeAndMut { ref ty, .. }) => {
                 format!("raw_ref${}", self.encoder.encode_type_predicate_use(ty)?)
             }
+            ty::TyKind::Ref(_, ref ty, rustc_middle::mir::Mutability::Mut) if ty.is_trait() => {
+                return Err(EncodingError::unsupported(
+                    "mutable references to trait objects are not supported"
+                ));
+            }
             ty::TyKind::Ref(_, ref ty, _) => {
                 format!("ref${}", self.encoder.encode_type_predicate_use(ty)?)
             }
@@ -584,12 +633,27 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
                 composed_name.join("$")
             }
 
-            ty::TyKind::Dynamic(..) => {
-                "unsupported$dynamic".to_string()
+            ty::TyKind::Dynamic(preds, _) => {
+                // the dynamic type is abstracted, only the principal trait is kept
+                match preds.principal_def_id() {
+                    Some(trait_def_id) => format!("dyn${}", self.encoder.encode_item_name(trait_def_id)),
+                    None => "dyn$".to_string(),
+                }
             }
 
-            ty::TyKind::FnPtr(..) => {
-                "unsupported$fnptr".to_string()
+            ty::TyKind::FnPtr(sig) => {
+                let sig = sig.skip_binder();
+                let input_predicate_names: EncodingResult<Vec<_>> = sig
+                    .inputs()
+                    .iter()
+                    .map(|ty| self.encoder.encode_type_predicate_use(ty))
+                    .collect();
+                format!(
+                    "fnptr{}${}${}",
+                    sig.inputs().len(),
+                    input_predicate_names?.join("$"),
+                    self.encoder.encode_type_predicate_use(sig.output())?,
+                )
             }
 
             ty::TyKind::FnDef(..) => {
@@ -624,6 +688,10 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
                 ])
             }
 
+            ty::TyKind::Adt(_, _) if self.encoder.ghost_collection_kind(self.ty).is_some() => {
+                Some(vec![])
+            }
+
             ty::TyKind::Adt(ref adt_def, ref subst) if !adt_def.is_box() => {
                 if self.is_supported_struct_type(adt_def, subst) {
                     let own_substs =
@@ -647,50 +715,17 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
                     let num_variants = adt_def.variants.len();
                     let tcx = self.encoder.env().tcx();
 
-                    let specs: Vec<typed::SpecificationSet> = Vec::new();
-                    // FIXME: type invariants need to be collected separately
-                    // in `SpecCollector`, and encoder should get a
-                    // `get_struct_specs` method or similar.
-                    // `get_procedure_specs` now only returns procedure specs,
-                    // so the match below for `SpecSet::Struct` would never
-                    // succeed.
-
-                    //if let Some(spec) = self.encoder.get_procedure_specs(adt_def.did) {
-                    //    specs.push(spec);
-                    //}
-
-                    // let traits = self.encoder.env().get_traits_decls_for_type(&self.ty);
-                    // for trait_id in traits {
-                    //     //if let Some(spec) = self.encoder.get_procedure_specs(trait_id) {
-                    //     //    specs.push(spec);
-                    //     //}
-                    // }
-
-                    for spec in specs.into_iter() {
-                        //let encoded_args = vec![vir::Expr::from(self_local_var.clone())];
-                        // let mut hacky_folder = HackyExprFolder {
-                        //     saelf: self_local_var.clone(),
-                        // };
-
-                        match spec {
-                            typed::SpecificationSet::Struct(items) => {
-                                for _item in items {
-                                    // let enc = encode_simple_spec_assertion(
-                                    //     self.encoder,
-                                    //     &[],
-                                    //     &item.assertion
-                                    // );
-                                    let _enc = unimplemented!(
-                                        "TODO: type invariants need to be upgraded \
-                                        to the new compiler version"
-                                    );
-                                    // OPEN TODO: hacky fix here to convert the closure var to "self"...
-                                    // let enc = hacky_folder.fold(enc);
-                                    // exprs.push(enc);
-                                }
-                            }
-                            _ => unreachable!(),
-                        }
+                    // The invariants written by the user on the type
+                    for spec in self.encoder.get_struct_specs(adt_def.did).unwrap_or_default() {
+                        exprs.push(encode_spec_assertion(
+                            self.encoder,
+                            &spec.assertion,
+                            None,
+                            &[self_local_var.clone().into()],
+                            None,
+                            false,
+                            None,
+                        )?);
                     }
 
                     if num_variants == 0 {
@@ -791,7 +826,7 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
         let tag_name = self.encoder.encode_type_tag_use(self.ty);
 
         let body = match self.ty.kind() {
-            ty::TyKind::Param(_param_ty) => None,
+            ty::TyKind::Param(_param_ty) | ty::TyKind::Projection(_) => None,
             _ => Some((vir::Const::Int((self.ty as *const ty::TyS<'tcx>) as i64)).into()),
         };
 
@@ -870,13 +905,3 @@ pub fn compute_discriminant_bounds<'tcx>(
     let discr_values = compute_discriminant_values(adt_def, tcx);
     build_discr_range_expr(discriminant_loc, discr_values)
 }
-
-struct HackyExprFolder {
-    saelf: vir::LocalVar,
-}
-
-impl ExprFolder for HackyExprFolder {
-    fn fold_local(&mut self, _var: vir::LocalVar, pos: vir::Position) -> vir::Expr {
-        vir::Expr::Local(self.saelf.clone(), pos)
-    }
-}
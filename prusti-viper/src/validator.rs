@@ -0,0 +1,215 @@
+// © 2021, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Check, before the encoding starts, whether a procedure uses constructs that the encoder does
+//! not support.
+//!
+//! The validator is conservative: it only reports constructs that the encoder always rejects, so
+//! a procedure with a supported status might still fail to be encoded.
+
+use prusti_interface::environment::Environment;
+use prusti_interface::data::ProcedureDefId;
+use rustc_middle::mir;
+use rustc_middle::ty;
+use rustc_span::Span;
+
+/// The constructs of a procedure that are not supported, with their spans.
+#[derive(Debug, Default)]
+pub struct SupportStatus {
+    unsupported: Vec<(String, Span)>,
+}
+
+impl SupportStatus {
+    fn unsupported<S: ToString>(&mut self, reason: S, span: Span) {
+        self.unsupported.push((reason.to_string(), span));
+    }
+
+    pub fn is_supported(&self) -> bool {
+        self.unsupported.is_empty()
+    }
+
+    pub fn unsupported_constructs(&self) -> &[(String, Span)] {
+        &self.unsupported
+    }
+}
+
+pub struct Validator<'v, 'tcx: 'v> {
+    env: &'v Environment<'tcx>,
+}
+
+impl<'v, 'tcx: 'v> Validator<'v, 'tcx> {
+    pub fn new(env: &'v Environment<'tcx>) -> Self {
+        Validator { env }
+    }
+
+    pub fn procedure_support_status(&self, proc_id: ProcedureDefId) -> SupportStatus {
+        self.support_status(proc_id, false)
+    }
+
+    pub fn pure_function_support_status(&self, proc_id: ProcedureDefId) -> SupportStatus {
+        self.support_status(proc_id, true)
+    }
+
+    fn support_status(&self, proc_id: ProcedureDefId, is_pure: bool) -> SupportStatus {
+        let procedure = self.env.get_procedure(proc_id);
+        let mir = procedure.get_mir();
+        let mut status = SupportStatus::default();
+        for local_decl in mir.local_decls.iter() {
+            self.check_local_ty(local_decl.ty, local_decl.source_info.span, &mut status);
+        }
+        for bb in procedure.get_reachable_nonspec_cfg_blocks() {
+            let bb_data = &mir[bb];
+            if bb_data.is_cleanup {
+                continue;
+            }
+            for stmt in &bb_data.statements {
+                self.check_statement(stmt, is_pure, &mut status);
+            }
+            self.check_terminator(bb_data.terminator(), is_pure, &mut status);
+        }
+        status
+    }
+
+    fn check_local_ty(&self, ty: ty::Ty<'tcx>, span: Span, status: &mut SupportStatus) {
+        let has_mut_trait_object = ty.walk().any(|arg| match arg.unpack() {
+            ty::subst::GenericArgKind::Type(ty) => matches!(
+                ty.kind(),
+                ty::TyKind::Ref(_, inner_ty, mir::Mutability::Mut) if inner_ty.is_trait()
+            ),
+            _ => false,
+        });
+        if has_mut_trait_object {
+            status.unsupported("mutable references to trait objects are not supported", span);
+        }
+    }
+
+    fn check_statement(
+        &self,
+        stmt: &mir::Statement<'tcx>,
+        is_pure: bool,
+        status: &mut SupportStatus,
+    ) {
+        let span = stmt.source_info.span;
+        match stmt.kind {
+            mir::StatementKind::StorageLive(..)
+            | mir::StatementKind::StorageDead(..)
+            | mir::StatementKind::FakeRead(..)
+            | mir::StatementKind::AscribeUserType(..)
+            | mir::StatementKind::Coverage(..)
+            | mir::StatementKind::Nop => {}
+
+            mir::StatementKind::Assign(box (_, ref rhs)) => {
+                self.check_rvalue(rhs, span, is_pure, status);
+            }
+
+            mir::StatementKind::LlvmInlineAsm(..) => {
+                status.unsupported("inline assembly is not supported", span);
+            }
+
+            _ => {
+                status.unsupported(format!("the statement '{:?}' is not supported", stmt), span);
+            }
+        }
+    }
+
+    fn check_rvalue(
+        &self,
+        rvalue: &mir::Rvalue<'tcx>,
+        span: Span,
+        is_pure: bool,
+        status: &mut SupportStatus,
+    ) {
+        match rvalue {
+            mir::Rvalue::ThreadLocalRef(_) => {
+                status.unsupported("references to thread-local storage are not supported", span);
+            }
+
+            mir::Rvalue::Ref(_, mir::BorrowKind::Shallow, _) => {
+                status.unsupported(
+                    "shallow borrows (implicitly created when lowering matches) are not supported",
+                    span,
+                );
+            }
+
+            mir::Rvalue::Ref(_, mir::BorrowKind::Unique, _) if !is_pure => {
+                status.unsupported(
+                    "unique borrows (implicitly created in closure bindings) are not supported",
+                    span,
+                );
+            }
+
+            mir::Rvalue::Cast(mir::CastKind::Pointer(cast), _, _) => match cast {
+                ty::adjustment::PointerCast::Unsize
+                | ty::adjustment::PointerCast::MutToConstPointer => {}
+                ty::adjustment::PointerCast::ReifyFnPointer if !is_pure => {}
+                _ => status.unsupported("this kind of pointer cast is not supported", span),
+            },
+
+            mir::Rvalue::Aggregate(kind, _) => match kind.as_ref() {
+                mir::AggregateKind::Generator(..) => {
+                    status.unsupported("construction of generators is not supported", span);
+                }
+                mir::AggregateKind::Adt(adt_def, ..) if adt_def.is_union() => {
+                    status.unsupported("unions are not supported", span);
+                }
+                mir::AggregateKind::Closure(..) if is_pure => {
+                    status.unsupported(
+                        "construction of closures is not supported in pure functions",
+                        span,
+                    );
+                }
+                _ => {}
+            },
+
+            mir::Rvalue::NullaryOp(..)
+            | mir::Rvalue::Repeat(..)
+            | mir::Rvalue::AddressOf(..) if is_pure => {
+                status.unsupported(
+                    format!("the expression '{:?}' is not supported in pure functions", rvalue),
+                    span,
+                );
+            }
+
+            _ => {}
+        }
+    }
+
+    fn check_terminator(
+        &self,
+        term: &mir::Terminator<'tcx>,
+        is_pure: bool,
+        status: &mut SupportStatus,
+    ) {
+        let span = term.source_info.span;
+        match term.kind {
+            mir::TerminatorKind::Yield { .. } | mir::TerminatorKind::GeneratorDrop => {
+                status.unsupported("generators are not supported", span);
+            }
+
+            mir::TerminatorKind::InlineAsm { .. } => {
+                status.unsupported("inline assembly is not supported", span);
+            }
+
+            mir::TerminatorKind::Call { ref func, .. } if is_pure => {
+                if !matches!(func, mir::Operand::Constant(..)) {
+                    status.unsupported(
+                        "calls through function pointers are not supported in pure functions",
+                        span,
+                    );
+                }
+            }
+
+            mir::TerminatorKind::DropAndReplace { .. } if is_pure => {
+                status.unsupported(
+                    "assignments that drop the previous value are not supported in pure functions",
+                    span,
+                );
+            }
+
+            _ => {}
+        }
+    }
+}
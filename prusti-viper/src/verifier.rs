@@ -6,24 +6,27 @@
 
 use prusti_common::vir::{self, optimizations, ToViper, ToViperDecl};
 use prusti_common::{
-    config, report::log, verification_context::VerifierBuilder, verification_service::*, Stopwatch,
+    config, report::{log, user}, verification_context::VerifierBuilder, verification_service::*, Stopwatch,
 };
 use crate::encoder::Encoder;
-// use prusti_filter::validators::Validator;
+use crate::validator::Validator;
 use prusti_interface::data::VerificationResult;
 use prusti_interface::data::VerificationTask;
 use prusti_interface::environment::Environment;
 use prusti_interface::PrustiError;
 // use prusti_interface::specifications::TypedSpecificationMap;
 use std::time::Instant;
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::thread;
 use viper::{self, VerificationBackend, Viper};
 use std::path::PathBuf;
 use std::fs::{create_dir_all, canonicalize};
 use std::ffi::OsString;
 use prusti_interface::specs::typed;
-use ::log::{info, debug, error};
-use prusti_server::{PrustiServerConnection, ServerSideService, VerifierRunner};
-use rustc_span::DUMMY_SP;
+use ::log::{info, debug, error, warn};
+use prusti_server::{PersistentCache, PrustiServerConnection, ServerSideService, VerifierRunner};
+use rustc_span::{MultiSpan, DUMMY_SP};
 
 // /// A verifier builder is an object that lives entire program's
 // /// lifetime, has no mutable state, and is responsible for constructing
@@ -170,75 +173,77 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
             info!(" - {} from {:?} ({})", proc_name, proc_span, proc_def_path);
         }
 
-        // // Check support status, and queue encoding
-        // let validator = Validator::new(self.env.tcx());
-
-        // let report_support_status = config::report_support_status();
-        // let skip_unsupported_features = config::skip_unsupported_features();
-        // let error_on_partially_supported = config::error_on_partially_supported();
-        // let mut skipped_functions_count = 0;
-
-        // for &proc_id in &task.procedures {
-        //     let proc_name = self.env.get_absolute_item_name(proc_id);
-        //     let proc_span = self.env.get_item_span(proc_id);
-        //     let is_pure_function = self.env.has_prusti_attribute(proc_id, "pure");
-
-        //     let support_status = if is_pure_function {
-        //         validator.pure_function_support_status(proc_id)
-        //     } else {
-        //         validator.procedure_support_status(proc_id)
-        //     };
-
-        //     if report_support_status {
-        //         support_status.report_support_status(
-        //             &self.env,
-        //             is_pure_function,
-        //             // true ==> raise compiler errors for partially supported functions
-        //             error_on_partially_supported,
-        //             // true ==> raise compiler errors for unsupported functions
-        //             error_on_partially_supported || !skip_unsupported_features,
-        //         );
-        //     }
-
-        //     if !support_status.is_supported() && skip_unsupported_features {
-        //         warn!(
-        //             "Skip verification of {}, as it is not fully supported.",
-        //             proc_name
-        //         );
-        //         self.env.span_warn_with_help_and_note(
-        //             proc_span,
-        //             &format!(
-        //                 "this function will be ignored because it is not fully supported by \
-        //                 Prusti: {}",
-        //                 proc_name
-        //             ),
-        //             &Some(if report_support_status {
-        //                 "Disable the SKIP_UNSUPPORTED_FEATURES configuration flag to verify \
-        //                     this function anyway."
-        //                     .to_string()
-        //             } else {
-        //                 "Enable the REPORT_SUPPORT_STATUS configuration flag for more details \
-        //                     on why the function is not fully supported, or disable \
-        //                     SKIP_UNSUPPORTED_FEATURES to verify this function anyway."
-        //                     .to_string()
-        //             }),
-        //             &None,
-        //         );
-        //         skipped_functions_count += 1;
-        //     } else {
-        //         self.encoder.queue_procedure_encoding(proc_id);
-        //     }
-        // }
-        // info!(
-        //     "Out of {} functions, {} are not fully supported and have been skipped.",
-        //     task.procedures.len(),
-        //     skipped_functions_count,
-        // );
+        // Check support status, and queue encoding
+        let validator = Validator::new(self.env);
+
+        let report_support_status = config::report_support_status();
+        let skip_unsupported_features = config::skip_unsupported_features();
+        let mut skipped_functions_count = 0;
 
         for &proc_id in task.procedures.iter().rev() {
-            // FIXME: Use the loop above.
-            self.encoder.queue_procedure_encoding(proc_id);
+            let proc_name = self.env.get_absolute_item_name(proc_id);
+            let proc_span = self.env.get_item_span(proc_id);
+            let is_pure_function = self.env.has_prusti_attribute(proc_id, "pure");
+
+            let support_status = if is_pure_function {
+                validator.pure_function_support_status(proc_id)
+            } else {
+                validator.procedure_support_status(proc_id)
+            };
+
+            if report_support_status {
+                if support_status.is_supported() {
+                    user::message(format!("Support status of {}: supported", proc_name));
+                } else {
+                    user::message(format!(
+                        "Support status of {}: {} unsupported constructs",
+                        proc_name,
+                        support_status.unsupported_constructs().len(),
+                    ));
+                    if !skip_unsupported_features {
+                        let mut warning = PrustiError::unsupported(
+                            format!("this function is not fully supported by Prusti: {}", proc_name),
+                            MultiSpan::from_span(proc_span),
+                        );
+                        warning.set_warning();
+                        for (reason, span) in support_status.unsupported_constructs() {
+                            warning = warning.set_note(reason, *span);
+                        }
+                        warning.emit(self.env);
+                    }
+                }
+            }
+
+            if !support_status.is_supported() && skip_unsupported_features {
+                warn!(
+                    "Skip verification of {}, as it is not fully supported.",
+                    proc_name
+                );
+                let mut warning = PrustiError::unsupported(
+                    format!(
+                        "this function will be ignored because it is not fully supported by \
+                        Prusti: {}",
+                        proc_name
+                    ),
+                    MultiSpan::from_span(proc_span),
+                ).set_help(
+                    "Disable the SKIP_UNSUPPORTED_FEATURES configuration flag to verify \
+                    this function anyway."
+                );
+                for (reason, span) in support_status.unsupported_constructs() {
+                    warning = warning.set_note(reason, *span);
+                }
+                warning.emit(self.env);
+                skipped_functions_count += 1;
+            } else {
+                self.encoder.queue_procedure_encoding(proc_id);
+            }
         }
+        info!(
+            "Out of {} functions, {} are not fully supported and have been skipped.",
+            task.procedures.len(),
+            skipped_functions_count,
+        );
         self.encoder.process_encoding_queue();
 
         let encoding_errors_count = self.encoder.count_encoding_errors();
@@ -258,68 +263,207 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
             .to_str()
             .unwrap()
             .to_owned();
-        let verification_result: viper::VerificationResult = if let Some(server_address) =
-            config::server_address()
-        {
+        // With a cache or a concurrent verification, each method is verified in its own
+        // request, so that the results can be cached and the methods verified in parallel.
+        let concurrency = config::verification_concurrency().unwrap_or(1);
+        let cache_path = config::cache_path();
+        let programs = if concurrency > 1 || !cache_path.is_empty() {
+            program.split_by_method()
+        } else {
+            vec![program]
+        };
+        let is_split = programs.len() > 1;
+        let request_refutations: Vec<HashSet<u64>> = programs
+            .iter()
+            .map(|program| {
+                let error_manager = self.encoder.error_manager();
+                program.methods
+                    .iter()
+                    .flat_map(|method| error_manager.method_refutations(method))
+                    .collect()
+            })
+            .collect();
+        let requests: Vec<_> = programs
+            .into_iter()
+            .zip(&request_refutations)
+            .map(|(program, refutations)| {
+                let mut backend_config = ViperBackendConfig::default();
+                // A refutation succeeds if its check fails, so all the errors of the method
+                // are needed to know which refutations failed.
+                if !refutations.is_empty() {
+                    backend_config.report_all_errors();
+                }
+                VerificationRequest {
+                    program_name: if is_split {
+                        let member_name = program.methods.first()
+                            .map(|method| method.name())
+                            .unwrap_or_else(|| "functions".to_string());
+                        format!("{}_{}", program_name, member_name)
+                    } else {
+                        program_name.clone()
+                    },
+                    program,
+                    backend_config,
+                }
+            })
+            .collect();
+
+        // Requests that were verified successfully in an earlier run are answered from the cache.
+        let mut cache = if cache_path.is_empty() {
+            None
+        } else {
+            Some(PersistentCache::load(cache_path))
+        };
+        let mut keys = Vec::with_capacity(requests.len());
+        let mut cached_results = Vec::with_capacity(requests.len());
+        let mut pending_requests = vec![];
+        for request in requests {
+            let key = cache.as_ref().map(|_| PersistentCache::request_key(&request));
+            let cached_result = cache
+                .as_ref()
+                .zip(key)
+                .and_then(|(cache, key)| cache.get(key));
+            if cached_result.is_none() {
+                pending_requests.push(request);
+            }
+            keys.push(key);
+            cached_results.push(cached_result);
+        }
+        info!(
+            "Verifying {} of {} requests, the rest is cached",
+            pending_requests.len(),
+            keys.len()
+        );
+
+        let new_results = if pending_requests.is_empty() {
+            vec![]
+        } else if let Some(server_address) = config::server_address() {
             let server_address = if server_address == "MOCK" {
                 ServerSideService::spawn_off_thread().to_string()
             } else {
                 server_address
             };
             info!("Connecting to Prusti server at {}", server_address);
-            let service = PrustiServerConnection::new(&server_address).unwrap_or_else(|error| {
-                panic!(
-                    "Could not parse server address ({}) due to {:?}",
-                    server_address, error
-                )
-            });
-
-            let request = VerificationRequest {
-                program,
-                program_name,
-                backend_config: Default::default(),
-            };
-            service.verify(request)
+            dispatch_concurrently(pending_requests, concurrency, move |next_request| {
+                let service = PrustiServerConnection::new(&server_address).unwrap_or_else(|error| {
+                    panic!(
+                        "Could not parse server address ({}) due to {:?}",
+                        server_address, error
+                    )
+                });
+                let mut results = vec![];
+                while let Some((index, request)) = next_request() {
+                    results.push((index, service.verify(request)));
+                }
+                results
+            })
         } else {
             let mut stopwatch = Stopwatch::start("prusti-viper", "JVM startup");
             let verifier_builder = VerifierBuilder::new();
             stopwatch.start_next("running verifier");
-            VerifierRunner::with_default_configured_runner(&verifier_builder, |runner| {
-                runner.verify(program, program_name.as_str())
+            dispatch_concurrently(pending_requests, concurrency, move |next_request| {
+                let default_config = ViperBackendConfig::default();
+                VerifierRunner::with_runner(&verifier_builder, &default_config, |runner| {
+                    let mut results = vec![];
+                    while let Some((index, request)) = next_request() {
+                        let result = if request.backend_config == default_config {
+                            runner.verify(request.program, request.program_name.as_str())
+                        } else {
+                            VerifierRunner::with_runner(
+                                &verifier_builder,
+                                &request.backend_config,
+                                |runner| runner.verify(request.program, request.program_name.as_str()),
+                            )
+                        };
+                        results.push((index, result));
+                    }
+                    results
+                })
             })
         };
 
         stopwatch.finish();
 
-        let verification_errors = match verification_result {
-            viper::VerificationResult::Success() => vec![],
-            viper::VerificationResult::Failure(errors) => errors,
-            viper::VerificationResult::ConsistencyErrors(errors) => {
-                debug_assert!(!errors.is_empty());
-                errors.iter().for_each(|e| {
+        let mut new_results = new_results.into_iter();
+        let verification_results: Vec<_> = cached_results
+            .into_iter()
+            .zip(keys)
+            .map(|(cached_result, key)| {
+                cached_result.unwrap_or_else(|| {
+                    let result = new_results.next().unwrap();
+                    if let (Some(cache), Some(key)) = (cache.as_mut(), key) {
+                        cache.insert(key, &result);
+                    }
+                    result
+                })
+            })
+            .collect();
+        if let Some(cache) = &cache {
+            cache.save();
+        }
+
+        // Functions are verified only in the first request, so every error is reported once.
+        let mut verification_errors = vec![];
+        let mut consistency_errors = vec![];
+        let mut internal_errors = false;
+        // The refutations of the methods whose verification completed.
+        let mut checked_refutations = HashSet::new();
+        for (verification_result, refutations) in verification_results.into_iter().zip(request_refutations) {
+            match verification_result {
+                viper::VerificationResult::Success() => {
+                    checked_refutations.extend(refutations);
+                }
+                viper::VerificationResult::Failure(errors) => {
+                    checked_refutations.extend(refutations);
+                    verification_errors.extend(errors);
+                }
+                viper::VerificationResult::ConsistencyErrors(errors) => {
+                    debug_assert!(!errors.is_empty());
+                    for error in errors {
+                        if !consistency_errors.contains(&error) {
+                            PrustiError::internal(
+                                format!("consistency error: {}", error), DUMMY_SP.into()
+                            ).emit(self.env);
+                            consistency_errors.push(error);
+                        }
+                    }
+                    internal_errors = true;
+                }
+                viper::VerificationResult::JavaException(exception) => {
+                    error!("Java exception: {}", exception.get_stack_trace());
                     PrustiError::internal(
-                        format!("consistency error: {}", e), DUMMY_SP.into()
-                    ).emit(self.env)
-                });
-                return VerificationResult::Failure;
-            }
-            viper::VerificationResult::JavaException(exception) => {
-                error!("Java exception: {}", exception.get_stack_trace());
-                PrustiError::internal(
-                    format!("{}", exception), DUMMY_SP.into()
-                ).emit(self.env);
-                return VerificationResult::Failure;
+                        format!("{}", exception), DUMMY_SP.into()
+                    ).emit(self.env);
+                    internal_errors = true;
+                }
             }
-        };
+        }
+        if internal_errors {
+            return VerificationResult::Failure;
+        }
 
-        if encoding_errors_count == 0 && verification_errors.is_empty() {
+        let (verification_errors, refutation_errors) = self.encoder.error_manager()
+            .check_refutations(verification_errors, &checked_refutations);
+
+        if encoding_errors_count == 0
+            && verification_errors.is_empty()
+            && refutation_errors.is_empty()
+        {
             VerificationResult::Success
         } else {
             let error_manager = self.encoder.error_manager();
 
+            for refutation_error in refutation_errors {
+                debug!("Prusti error: {:?}", refutation_error);
+                refutation_error.emit(self.env);
+            }
+
             for verification_error in verification_errors {
                 debug!("Verification error: {:?}", verification_error);
-                let prusti_error = error_manager.translate_verification_error(&verification_error);
+                let mut prusti_error = error_manager.translate_verification_error(&verification_error);
+                if let Some(entries) = error_manager.translate_counterexample(&verification_error) {
+                    prusti_error = prusti_error.set_counterexample(entries);
+                }
                 debug!("Prusti error: {:?}", prusti_error);
                 prusti_error.emit(self.env);
             }
@@ -327,3 +471,38 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
         }
     }
 }
+
+/// Distribute the verification requests over at most `concurrency` threads running `worker`.
+/// A worker takes requests one by one with the function it is given, and returns their
+/// results tagged with the index of the request. The results are returned in the order of the
+/// requests.
+fn dispatch_concurrently<W>(
+    requests: Vec<VerificationRequest>,
+    concurrency: usize,
+    worker: W,
+) -> Vec<viper::VerificationResult>
+where
+    W: Fn(&dyn Fn() -> Option<(usize, VerificationRequest)>) -> Vec<(usize, viper::VerificationResult)>
+        + Send
+        + Sync
+        + 'static,
+{
+    let thread_count = concurrency.max(1).min(requests.len());
+    let queue = Arc::new(Mutex::new(
+        requests.into_iter().enumerate().collect::<VecDeque<_>>()
+    ));
+    let worker = Arc::new(worker);
+    let handles: Vec<_> = (0..thread_count)
+        .map(|_| {
+            let queue = queue.clone();
+            let worker = worker.clone();
+            thread::spawn(move || worker(&|| queue.lock().unwrap().pop_front()))
+        })
+        .collect();
+    let mut results: Vec<_> = handles
+        .into_iter()
+        .flat_map(|handle| handle.join().expect("verification thread panicked"))
+        .collect();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
@@ -1,16 +1,39 @@
-use prusti_interface::{specs, environment::Environment};
+use prusti_interface::{diagnostics, specs, environment::Environment};
+use prusti_interface::specs::external::STD_SPECS_MODULE;
+use prusti_interface::specs::cross_crate::{CrateSpecs, SPECS_FILE_EXTENSION};
+use rustc_ast::ast;
 use rustc_driver::Compilation;
 use rustc_hir::intravisit;
-use rustc_interface::interface::Compiler;
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_interface::interface::{Compiler, Config};
 use rustc_interface::Queries;
+use rustc_middle::ty::TyCtxt;
+use rustc_parse::parser::ForceCollect;
+use rustc_session::{Session, config::CrateType};
+use rustc_span::{FileName, symbol::sym};
 use regex::Regex;
 use prusti_common::config;
 use crate::verifier::verify;
+use crate::ghost_erasure;
 
 #[derive(Default)]
 pub struct PrustiCompilerCalls;
 
 impl rustc_driver::Callbacks for PrustiCompilerCalls {
+    fn config(&mut self, config: &mut Config) {
+        // Erase the ghost code from the compiled code; it is verified on the MIR built
+        // before the optimizations.
+        config.override_queries = Some(ghost_erasure::override_queries);
+    }
+    fn after_parsing<'tcx>(
+        &mut self,
+        compiler: &Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        let krate = &mut *queries.parse().unwrap().peek_mut();
+        add_std_specs(compiler.session(), krate);
+        Compilation::Continue
+    }
     fn after_expansion<'tcx>(
         &mut self,
         compiler: &Compiler,
@@ -41,13 +64,18 @@ impl rustc_driver::Callbacks for PrustiCompilerCalls {
             let hir = tcx.hir();
             let krate = hir.krate();
             let env = Environment::new(tcx);
+            let crate_name = env.crate_name();
             let mut spec_checker = specs::checker::SpecChecker::new();
             spec_checker.check_predicate_usages(tcx, krate);
             spec_checker.report_errors(&env);
+            if env.has_errors() {
+                write_diagnostics(&crate_name);
+            }
             compiler.session().abort_if_errors();
 
             let mut spec_collector = specs::SpecCollector::new(tcx);
             intravisit::walk_crate(&mut spec_collector, &krate);
+            export_specs(tcx, &spec_collector.export_specs());
             let def_spec = spec_collector.build_def_specs(&env);
             if config::print_typeckd_specs() {
                 let mut values: Vec<_> = def_spec
@@ -78,6 +106,7 @@ impl rustc_driver::Callbacks for PrustiCompilerCalls {
             if !config::no_verify() {
                 verify(env, def_spec);
             }
+            write_diagnostics(&crate_name);
         });
 
         compiler.session().abort_if_errors();
@@ -87,4 +116,65 @@ impl rustc_driver::Callbacks for PrustiCompilerCalls {
             Compilation::Stop
         }
     }
-}
\ No newline at end of file
+}
+
+/// Adds the specifications of the standard library bundled in `prusti-std` to the
+/// crate, so that they are expanded and type-checked like the specifications written
+/// by the user. The specifications of natively encoded types are always added, the
+/// others only if `PRUSTI_STD_SPECS` is enabled. Crates that do not link the standard
+/// library or `prusti_contracts` are left untouched.
+fn add_std_specs(session: &Session, krate: &mut ast::Crate) {
+    if session.contains_name(&krate.attrs, sym::no_std)
+        || session.opts.externs.get("prusti_contracts").is_none() {
+        return;
+    }
+    let mut source = format!("#[allow(warnings)]\nmod {} {{\n", STD_SPECS_MODULE);
+    let mut modules = prusti_std::CORE_SPECIFICATIONS.to_vec();
+    if config::std_specs() {
+        modules.extend_from_slice(prusti_std::SPECIFICATIONS);
+    }
+    for (name, specs) in modules {
+        source.push_str(&format!("mod {} {{\n{}\n}}\n", name, specs));
+    }
+    source.push_str("}\n");
+    let mut parser = rustc_parse::new_parser_from_source_str(
+        &session.parse_sess,
+        FileName::Custom("prusti-std".to_string()),
+        source,
+    );
+    match parser.parse_item(ForceCollect::No) {
+        Ok(Some(item)) => krate.items.push(item),
+        Ok(None) => unreachable!("the bundled specifications are not a module"),
+        Err(mut err) => err.emit(),
+    }
+}
+
+/// Writes the specifications of a library next to its metadata, from where they
+/// are loaded when the crates that depend on the library are verified.
+fn export_specs(tcx: TyCtxt, crate_specs: &CrateSpecs) {
+    let is_library = tcx.sess.crate_types().iter().any(|crate_type| {
+        matches!(crate_type, CrateType::Rlib | CrateType::Dylib)
+    });
+    if !is_library || crate_specs.is_empty() {
+        return;
+    }
+    let crate_name = tcx.crate_name(LOCAL_CRATE).to_string();
+    let path = rustc_session::output::filename_for_metadata(
+        tcx.sess,
+        &crate_name,
+        &tcx.output_filenames(()),
+    ).with_extension(SPECS_FILE_EXTENSION);
+    if let Err(error) = crate_specs.write(&path) {
+        tcx.sess.warn(&format!(
+            "could not write the Prusti specifications to {}: {}",
+            path.display(),
+            error,
+        ));
+    }
+}
+
+fn write_diagnostics(crate_name: &str) {
+    if let Err(error) = diagnostics::write(crate_name) {
+        eprintln!("Could not write the Prusti diagnostics: {}", error);
+    }
+}
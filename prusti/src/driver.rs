@@ -33,11 +33,13 @@ extern crate prusti_common;
 mod callbacks;
 mod verifier;
 mod arg_value;
+mod ghost_erasure;
 
 use std::{env, panic, borrow::Cow, path::PathBuf};
 use prusti_common::report::user;
 use lazy_static::lazy_static;
 use callbacks::PrustiCompilerCalls;
+use ghost_erasure::GhostErasureCalls;
 use prusti_common::config;
 use arg_value::arg_value;
 use rustc_interface::interface::try_print_query_stack;
@@ -119,11 +121,12 @@ fn init_loggers() {
     rustc_driver::init_rustc_env_logger();
 }
 
-const PRUSTI_PACKAGES: [&str; 4] = [
+const PRUSTI_PACKAGES: [&str; 5] = [
     "prusti-contracts-internal",
     "prusti-contracts-impl",
     "prusti-contracts",
     "prusti-specs",
+    "prusti-std",
 ];
 
 fn main() {
@@ -138,7 +141,13 @@ fn main() {
     let are_lints_disabled = arg_value(&rustc_args, "--cap-lints", |val| val == "allow").is_some();
     let is_prusti_package = env::var("CARGO_PKG_NAME").map(|name| PRUSTI_PACKAGES.contains(&name.as_str())).unwrap_or(false);
     if prusti_be_rustc || are_lints_disabled || is_prusti_package {
-        rustc_driver::main();
+        // The crate is not verified, but its ghost code, which `prusti-contracts-internal`
+        // keeps in the MIR, must still not be executed.
+        rustc_driver::init_rustc_env_logger();
+        let exit_code = rustc_driver::catch_with_exit_code(move || {
+            rustc_driver::RunCompiler::new(&rustc_args, &mut GhostErasureCalls).run()
+        });
+        std::process::exit(exit_code)
     }
 
     lazy_static::initialize(&ICE_HOOK);
@@ -171,6 +180,9 @@ fn main() {
         ));
         rustc_args.push("-Zdump-mir=renumber".to_owned());
         rustc_args.push("-Zalways-encode-mir".to_owned());
+        // Optimizations would remove the closures of the specifications from the MIR stored in
+        // the metadata, which is where the specifications of dependencies are encoded from.
+        rustc_args.push("-Zmir-opt-level=0".to_owned());
         rustc_args.push("-Zcrate-attr=feature(register_tool)".to_owned());
         rustc_args.push("-Zcrate-attr=register_tool(prusti)".to_owned());
 
@@ -0,0 +1,88 @@
+//! Erasure of the `ghost!` blocks from the compiled code.
+//!
+//! When a crate is compiled by Prusti, `ghost!` blocks are expanded by
+//! `prusti-contracts-internal` and kept in the MIR, so that they can be verified.
+//! Ghost code must not run, though: the ghost collections, for example, cannot be
+//! executed. So the MIR from which the compiler generates code, which is computed by
+//! the `optimized_mir` query, jumps from the beginning of each `ghost!` block to its
+//! end. The verification uses the MIR built before the optimizations, so it still
+//! sees the ghost code.
+
+use rustc_driver::Callbacks;
+use rustc_hir::def_id::DefId;
+use rustc_interface::{interface::Config, DEFAULT_QUERY_PROVIDERS};
+use rustc_middle::mir;
+use rustc_middle::ty::{self, query::Providers, TyCtxt};
+use rustc_session::Session;
+use std::collections::HashSet;
+
+/// Callbacks that only erase the ghost code, used to compile crates without verifying them.
+pub struct GhostErasureCalls;
+
+impl Callbacks for GhostErasureCalls {
+    fn config(&mut self, config: &mut Config) {
+        config.override_queries = Some(override_queries);
+    }
+}
+
+pub fn override_queries(_session: &Session, local: &mut Providers, _external: &mut Providers) {
+    local.optimized_mir = optimized_mir;
+}
+
+fn optimized_mir<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId) -> &'tcx mir::Body<'tcx> {
+    let body = (DEFAULT_QUERY_PROVIDERS.optimized_mir)(tcx, def_id);
+    let ghost_blocks: Vec<_> = body.basic_blocks().indices()
+        .filter_map(|bb| ghost_block_bounds(tcx, body, bb))
+        .collect();
+    if ghost_blocks.is_empty() {
+        return body;
+    }
+    let mut body = body.clone();
+    for (begin, end_target) in ghost_blocks {
+        body.basic_blocks_mut()[begin].terminator_mut().kind =
+            mir::TerminatorKind::Goto { target: end_target };
+    }
+    tcx.arena.alloc(body)
+}
+
+/// If `begin` calls `ghost_begin`, returns `begin` and the block that follows the call
+/// of the matching `ghost_end`. Prusti checks that the execution of a `ghost!` block
+/// can only leave it through its end, so the first call of `ghost_end` is the one.
+fn ghost_block_bounds<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &mir::Body<'tcx>,
+    begin: mir::BasicBlock,
+) -> Option<(mir::BasicBlock, mir::BasicBlock)> {
+    let begin_target = marker_call_target(tcx, body, begin, "prusti_contracts::ghost_begin")?;
+    let mut visited = HashSet::new();
+    let mut to_visit = vec![begin_target];
+    while let Some(bb) = to_visit.pop() {
+        if body[bb].is_cleanup || !visited.insert(bb) {
+            continue;
+        }
+        if let Some(end_target) = marker_call_target(tcx, body, bb, "prusti_contracts::ghost_end") {
+            return Some((begin, end_target));
+        }
+        to_visit.extend(body[bb].terminator().successors().cloned());
+    }
+    None
+}
+
+/// If `bb` ends with a call of the marker function `path`, returns the block that
+/// follows the call.
+fn marker_call_target<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &mir::Body<'tcx>,
+    bb: mir::BasicBlock,
+    path: &str,
+) -> Option<mir::BasicBlock> {
+    match body[bb].terminator().kind {
+        mir::TerminatorKind::Call { ref func, destination: Some((_, target)), .. } => {
+            match func.ty(body, tcx).kind() {
+                ty::TyKind::FnDef(def_id, _) if tcx.def_path_str(*def_id) == path => Some(target),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
@@ -3,9 +3,10 @@
 use prusti_interface::specs::typed;
 use log::{debug, trace, warn};
 use prusti_interface::{
-    data::{VerificationResult, VerificationTask},
+    data::{ProcedureDefId, VerificationResult, VerificationTask},
     environment::Environment,
 };
+use glob::Pattern;
 use prusti_viper::verifier::Verifier;
 use prusti_common::config;
 use prusti_common::report::user;
@@ -21,15 +22,29 @@ pub fn verify<'tcx>(
     } else {
         debug!("Prepare verification task...");
         let annotated_procedures = env.get_annotated_procedures();
-        let verification_task = VerificationTask {
-            procedures: annotated_procedures,
+        let partition = config::check_concurrency()
+            .and_then(|()| filter_procedures(&env, annotated_procedures));
+        let (procedures, skipped_procedures) = match partition {
+            Ok(partition) => partition,
+            Err(message) => {
+                env.err(&format!("[Prusti: invalid configuration] {}", message));
+                trace!("[verify] exit");
+                return;
+            }
         };
+        let verification_task = VerificationTask { procedures };
         debug!("Verification task: {:?}", &verification_task);
 
         user::message(format!(
             "Verification of {} items...",
             verification_task.procedures.len()
         ));
+        if !skipped_procedures.is_empty() {
+            user::message(format!("Skipped {} items:", skipped_procedures.len()));
+            for procedure in &skipped_procedures {
+                user::message(format!("  {}", env.get_item_def_path(*procedure)));
+            }
+        }
 
         if config::print_collected_verification_items() {
             println!("Collected verification items {}:", verification_task.procedures.len());
@@ -70,4 +85,49 @@ pub fn verify<'tcx>(
     }
 
     trace!("[verify] exit");
-}
\ No newline at end of file
+}
+
+/// Split the procedures into the ones to verify and the ones that are skipped, either because
+/// they (or an enclosing item) are annotated with `#[prusti::skip]` or because their path does
+/// not match any of the `VERIFY_ONLY` patterns.
+///
+/// Returns an error message if one of the `VERIFY_ONLY` patterns is not a valid glob.
+fn filter_procedures(
+    env: &Environment,
+    procedures: Vec<ProcedureDefId>,
+) -> Result<(Vec<ProcedureDefId>, Vec<ProcedureDefId>), String> {
+    let crate_name = env.crate_name();
+    let patterns = config::verify_only()
+        .into_iter()
+        .map(|pattern| {
+            // `crate::` refers to the crate being verified, as in Rust paths.
+            let pattern = match pattern.strip_prefix("crate::") {
+                Some(rest) => format!("{}::{}", crate_name, rest),
+                None => pattern,
+            };
+            Pattern::new(&pattern).map_err(|error| {
+                format!("invalid VERIFY_ONLY pattern {:?}: {}", pattern, error)
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(procedures.into_iter().partition(|&procedure| {
+        !is_skipped(env, procedure) && (
+            patterns.is_empty() || {
+                let path = env.get_item_def_path(procedure);
+                patterns.iter().any(|pattern| pattern.matches(&path))
+            }
+        )
+    }))
+}
+
+fn is_skipped(env: &Environment, procedure: ProcedureDefId) -> bool {
+    let tcx = env.tcx();
+    let mut def_id = Some(procedure);
+    while let Some(current) = def_id {
+        if prusti_interface::utils::has_prusti_attr(tcx.get_attrs(current), "skip") {
+            return true;
+        }
+        def_id = tcx.parent(current);
+    }
+    false
+}
@@ -94,6 +94,14 @@ fn main() {
             java_class!("scala.None$", vec![
                 object_getter!(),
             ]),
+            java_class!("scala.Option", vec![
+                method!("isDefined"),
+                method!("get"),
+            ]),
+            java_class!("scala.Tuple2", vec![
+                method!("_1"),
+                method!("_2"),
+            ]),
             java_class!("scala.Predef", vec![
                 method!("wrapRefArray"),
             ]),
@@ -115,6 +123,9 @@ fn main() {
             java_class!("scala.collection.immutable.Nil$", vec![
                 object_getter!(),
             ]),
+            java_class!("scala.collection.IterableOnceOps", vec![
+                method!("toSeq"),
+            ]),
             java_class!("scala.collection.Seq", vec![
                 method!("length"),
                 method!("apply", "(I)Ljava/lang/Object;"),
@@ -231,6 +242,9 @@ fn main() {
             java_class!("viper.silver.ast.EmptySeq", vec![
                 constructor!(),
             ]),
+            java_class!("viper.silver.ast.EmptyMap", vec![
+                constructor!(),
+            ]),
             java_class!("viper.silver.ast.EmptyMultiset", vec![
                 constructor!(),
             ]),
@@ -249,6 +263,9 @@ fn main() {
             java_class!("viper.silver.ast.Exists", vec![
                 constructor!(),
             ]),
+            java_class!("viper.silver.ast.ExplicitMap", vec![
+                constructor!(),
+            ]),
             java_class!("viper.silver.ast.ExplicitMultiset", vec![
                 constructor!(),
             ]),
@@ -389,6 +406,24 @@ fn main() {
             java_class!("viper.silver.ast.MagicWand", vec![
                 constructor!(),
             ]),
+            java_class!("viper.silver.ast.MapCardinality", vec![
+                constructor!(),
+            ]),
+            java_class!("viper.silver.ast.MapContains", vec![
+                constructor!(),
+            ]),
+            java_class!("viper.silver.ast.MapLookup", vec![
+                constructor!(),
+            ]),
+            java_class!("viper.silver.ast.MapType", vec![
+                constructor!(),
+            ]),
+            java_class!("viper.silver.ast.MapUpdate", vec![
+                constructor!(),
+            ]),
+            java_class!("viper.silver.ast.Maplet", vec![
+                constructor!(),
+            ]),
             java_class!("viper.silver.ast.Method", vec![
                 constructor!(),
             ]),
@@ -603,6 +638,13 @@ fn main() {
                 method!("fullId"),
                 method!("reason"),
                 method!("readableMessage", "()Ljava/lang/String;"),
+                method!("counterexample"),
+            ]),
+            java_class!("viper.silver.verifier.Counterexample", vec![
+                method!("model"),
+            ]),
+            java_class!("viper.silver.verifier.Model", vec![
+                method!("entries"),
             ]),
             java_class!("viper.silver.verifier.ErrorReason", vec![
                 method!("id"),
@@ -612,6 +654,22 @@ fn main() {
             java_class!("viper.silver.verifier.ConsistencyError", vec![
                 constructor!(),
             ]),
+            java_class!("viper.silver.parser.FastParser", vec![
+                constructor!("()V"),
+                method!("parse"),
+            ]),
+            java_class!("viper.silver.parser.PProgram", vec![
+                method!("errors"),
+            ]),
+            java_class!("viper.silver.parser.Resolver", vec![
+                constructor!("(Lviper/silver/parser/PProgram;)V"),
+                method!("run"),
+                method!("messages"),
+            ]),
+            java_class!("viper.silver.parser.Translator", vec![
+                constructor!("(Lviper/silver/parser/PProgram;)V"),
+                method!("translate"),
+            ]),
         ])
         .generate(&generated_dir)
         .unwrap_or_else(|e| {
@@ -69,4 +69,11 @@ impl<'a> AstFactory<'a> {
             .unwrap_result(ast::SeqType::with(self.env).new(element_type.to_jobject()));
         Type::new(obj)
     }
+
+    pub fn map_type(&self, key_type: Type, value_type: Type) -> Type<'a> {
+        let obj = self.jni.unwrap_result(
+            ast::MapType::with(self.env).new(key_type.to_jobject(), value_type.to_jobject()),
+        );
+        Type::new(obj)
+    }
 }
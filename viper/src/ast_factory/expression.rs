@@ -924,6 +924,52 @@ impl<'a> AstFactory<'a> {
         build_ast_node!(self, Expr, ast::AnySetCardinality, set.to_jobject())
     }
 
+    pub fn empty_map(&self, key_type: Type, value_type: Type) -> Expr<'a> {
+        build_ast_node!(
+            self,
+            Expr,
+            ast::EmptyMap,
+            key_type.to_jobject(),
+            value_type.to_jobject()
+        )
+    }
+
+    pub fn maplet(&self, key: Expr, value: Expr) -> Expr<'a> {
+        build_ast_node!(self, Expr, ast::Maplet, key.to_jobject(), value.to_jobject())
+    }
+
+    pub fn explicit_map(&self, maplets: &[Expr]) -> Expr<'a> {
+        build_ast_node!(
+            self,
+            Expr,
+            ast::ExplicitMap,
+            self.jni.new_seq(&map_to_jobjects!(maplets))
+        )
+    }
+
+    pub fn map_update(&self, map: Expr, key: Expr, value: Expr) -> Expr<'a> {
+        build_ast_node!(
+            self,
+            Expr,
+            ast::MapUpdate,
+            map.to_jobject(),
+            key.to_jobject(),
+            value.to_jobject()
+        )
+    }
+
+    pub fn map_lookup(&self, map: Expr, key: Expr) -> Expr<'a> {
+        build_ast_node!(self, Expr, ast::MapLookup, map.to_jobject(), key.to_jobject())
+    }
+
+    pub fn map_contains(&self, key: Expr, map: Expr) -> Expr<'a> {
+        build_ast_node!(self, Expr, ast::MapContains, key.to_jobject(), map.to_jobject())
+    }
+
+    pub fn map_cardinality(&self, map: Expr) -> Expr<'a> {
+        build_ast_node!(self, Expr, ast::MapCardinality, map.to_jobject())
+    }
+
     pub fn simplified_expression(&self, expr: Expr) -> Expr<'a> {
         let simplifier_object_wrapper = ast::utility::Simplifier_object::with(self.env);
         let obj = self.jni.unwrap_result(
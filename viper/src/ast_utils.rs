@@ -8,6 +8,7 @@ use ast_factory::Program;
 use jni::objects::JObject;
 use jni::JNIEnv;
 use jni_utils::JniUtils;
+use viper_sys::wrappers::java;
 use viper_sys::wrappers::viper::*;
 use JavaException;
 
@@ -46,6 +47,51 @@ impl<'a> AstUtils<'a> {
         )
     }
 
+    /// Parse, type check and translate the Viper source code of a program. On failure, returns
+    /// the messages reported by the parser or by the type checker.
+    pub fn parse_program(&self, source: &str) -> Result<Program<'a>, Vec<String>> {
+        let path = self.jni.unwrap_result(
+            java::nio::file::Paths::with(self.env)
+                .call_get(self.jni.new_string("source.vpr"), self.jni.new_object_array(0)),
+        );
+
+        let fast_parser_wrapper = silver::parser::FastParser::with(self.env);
+        let parse_program = self.jni.unwrap_result(fast_parser_wrapper.call_parse(
+            self.jni.unwrap_result(fast_parser_wrapper.new()),
+            self.jni.new_string(source),
+            path,
+            self.jni.new_option(None),
+        ));
+        let parse_errors = self.jni.seq_to_vec(self.jni.unwrap_result(
+            silver::parser::PProgram::with(self.env).call_errors(parse_program),
+        ));
+        if !parse_errors.is_empty() {
+            return Err(parse_errors.into_iter().map(|e| self.jni.to_string(e)).collect());
+        }
+
+        let resolver_wrapper = silver::parser::Resolver::with(self.env);
+        let resolver = self.jni.unwrap_result(resolver_wrapper.new(parse_program));
+        let resolved_program = self.jni.option_to_option(
+            self.jni.unwrap_result(resolver_wrapper.call_run(resolver)),
+        );
+        let resolved_program = match resolved_program {
+            Some(resolved_program) => resolved_program,
+            None => {
+                let messages = self.jni.seq_to_vec(
+                    self.jni.unwrap_result(resolver_wrapper.call_messages(resolver)),
+                );
+                return Err(messages.into_iter().map(|m| self.jni.to_string(m)).collect());
+            }
+        };
+
+        let translator_wrapper = silver::parser::Translator::with(self.env);
+        let translator = self.jni.unwrap_result(translator_wrapper.new(resolved_program));
+        self.jni
+            .option_to_option(self.jni.unwrap_result(translator_wrapper.call_translate(translator)))
+            .map(Program::new)
+            .ok_or_else(|| vec!["the program could not be translated".to_string()])
+    }
+
     pub fn to_string(&self, program: Program<'a>) -> String {
         self.jni.to_string(program.to_jobject())
     }
@@ -141,6 +141,30 @@ impl<'a> JniUtils<'a> {
         res
     }
 
+    /// Convert a Scala Map to a Rust Vec of key-value pairs
+    pub fn map_to_vec(&self, map: JObject<'a>) -> Vec<(JObject<'a>, JObject<'a>)> {
+        let iterable_wrapper = scala::collection::IterableOnceOps::with(self.env);
+        let tuple_wrapper = scala::Tuple2::with(self.env);
+        let sequence = self.unwrap_result(iterable_wrapper.call_toSeq(map));
+        self.seq_to_vec(sequence)
+            .into_iter()
+            .map(|pair| (
+                self.unwrap_result(tuple_wrapper.call__1(pair)),
+                self.unwrap_result(tuple_wrapper.call__2(pair)),
+            ))
+            .collect()
+    }
+
+    /// Converts a Scala Option to a Rust Option<JObject>
+    pub fn option_to_option(&self, option: JObject<'a>) -> Option<JObject<'a>> {
+        let option_wrapper = scala::Option::with(self.env);
+        if self.unwrap_result(option_wrapper.call_isDefined(option)) {
+            Some(self.unwrap_result(option_wrapper.call_get(option)))
+        } else {
+            None
+        }
+    }
+
     /// Checks if an object is a subtype of a Java class
     pub fn is_instance_of(&self, object: JObject, class: &str) -> bool {
         let object_class = self.unwrap_result(self.env.get_object_class(object));
@@ -5,6 +5,7 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use JavaException;
+use std::collections::BTreeMap;
 
 /// The result of a verification request on a Viper program.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -25,6 +26,7 @@ pub struct VerificationError {
     pub pos_id: Option<String>,
     pub reason_pos_id: Option<String>,
     pub message: String,
+    pub counterexample: Option<SiliconCounterexample>,
 }
 
 impl VerificationError {
@@ -33,12 +35,28 @@ impl VerificationError {
         pos_id: Option<String>,
         reason_pos_id: Option<String>,
         message: String,
+        counterexample: Option<SiliconCounterexample>,
     ) -> Self {
         VerificationError {
             full_id,
             pos_id,
             reason_pos_id,
             message,
+            counterexample,
         }
     }
 }
+
+/// The model of a failing verification, as reported by Silicon when the
+/// `--counterexample` option is enabled.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SiliconCounterexample {
+    /// The value of each Viper variable in the model, keyed by the variable name.
+    pub entries: BTreeMap<String, String>,
+}
+
+impl SiliconCounterexample {
+    pub fn new(entries: BTreeMap<String, String>) -> Self {
+        SiliconCounterexample { entries }
+    }
+}
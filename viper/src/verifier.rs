@@ -14,6 +14,7 @@ use jni_utils::JniUtils;
 use std::marker::PhantomData;
 use std::path::PathBuf;
 use verification_backend::VerificationBackend;
+use verification_result::SiliconCounterexample;
 use verification_result::VerificationError;
 use verification_result::VerificationResult;
 use viper_sys::wrappers::viper::*;
@@ -263,11 +264,18 @@ impl<'a> Verifier<'a, state::Started> {
                         None
                     };
 
+                let counterexample = self.jni
+                    .option_to_option(self.jni.unwrap_result(
+                        verification_error_wrapper.call_counterexample(viper_error),
+                    ))
+                    .map(|counterexample| self.extract_counterexample(counterexample));
+
                 errors.push(VerificationError::new(
                     error_full_id,
                     pos_id,
                     reason_pos_id,
                     message,
+                    counterexample,
                 ))
             }
 
@@ -276,4 +284,21 @@ impl<'a> Verifier<'a, state::Started> {
             VerificationResult::Success()
         }
     }
+
+    /// Convert the model of a Viper counterexample to a map from variable names to values.
+    fn extract_counterexample(&self, counterexample: JObject<'a>) -> SiliconCounterexample {
+        let model = self.jni.unwrap_result(
+            silver::verifier::Counterexample::with(self.env).call_model(counterexample),
+        );
+        let entries = self.jni.unwrap_result(
+            silver::verifier::Model::with(self.env).call_entries(model),
+        );
+        SiliconCounterexample::new(
+            self.jni
+                .map_to_vec(entries)
+                .into_iter()
+                .map(|(name, value)| (self.jni.get_string(name), self.jni.to_string(value)))
+                .collect(),
+        )
+    }
 }